@@ -0,0 +1,285 @@
+use std::path::{Path, PathBuf};
+
+use simple_3D_renderer::rendering_engine::error::RenderError;
+use simple_3D_renderer::rendering_engine::format::gltf::GltfImport;
+use simple_3D_renderer::rendering_engine::format::obj::material_libraries;
+use simple_3D_renderer::rendering_engine::scene::camera::display::Display;
+use simple_3D_renderer::rendering_engine::scene::camera::options::CameraOptions;
+use simple_3D_renderer::rendering_engine::scene::camera::Camera;
+use simple_3D_renderer::rendering_engine::scene::model::color::Color;
+use simple_3D_renderer::rendering_engine::scene::model_3d::point::Point as Point3D;
+use simple_3D_renderer::rendering_engine::scene::model_3d::vector::Vector;
+use simple_3D_renderer::rendering_engine::scene::{CameraID, MeshID, NodeID, Scene};
+use simple_3D_renderer::rendering_engine::{RenderingEngine, SceneId};
+
+/** loaded models without own cameras are placed in front of the origin */
+pub const MODEL_DISTANCE: f32 = 500.0;
+
+pub const USAGE: &str = "usage:
+  simple-3D-renderer [file]
+      opens a window showing the file, or demo cubes without one
+  simple-3D-renderer render <file> <output> [options]
+      renders the file to a png, ppm, rgba or raw file without opening a window
+
+files: scenes (.json, binary .scene) and models (.obj with an optional .mtl path after it in window mode, .gltf, .glb, .stl)
+
+options:
+  --camera <index or id>        camera of the file, the first one by default, scene cameras are ordered by id
+  --position <x,y,z>            render through a new camera at the position, can't be combined with --camera
+  --rotation <pitch,yaw,roll>   rotation of the new camera in degrees, can't be combined with --camera
+  --size <width>x<height>       output size, 800x800 by default
+  --background <r,g,b>          background color, white by default";
+
+struct RenderOptions {
+    input_path: PathBuf,
+    output_path: PathBuf,
+    /** index or id */
+    camera: Option<String>,
+    position: Option<Vector>,
+    rotation: Option<Vector>,
+    width: usize,
+    height: usize,
+    background_color: Color,
+}
+
+/** runs the 'render' command, args are the ones following the command name */
+pub fn run_render(args: &[String]) -> Result<(), String> {
+    let options: RenderOptions = parse_render_args(args)?;
+
+    let mut renderer: RenderingEngine = RenderingEngine::new();
+
+    let display: Display = Display::new(options.width, options.height);
+    let (scene_id, file_cameras): (SceneId, Vec<CameraID>) = open_scene(&mut renderer, &options.input_path, None, &display)?;
+
+    let scene: &mut Scene = renderer.get_scene(scene_id).unwrap();
+
+    let camera_id: CameraID = if options.position.is_some() || options.rotation.is_some() {
+        let position: Vector = options.position.unwrap_or(Vector::zero());
+        let rotation: Vector = options.rotation.unwrap_or(Vector::zero());
+
+        scene.add_camera(default_camera(&display, Point3D { x: position.x, y: position.y, z: position.z }, &rotation)?)
+    } else {
+        match (&options.camera, file_cameras.first()) {
+            (Some(camera), _) => select_camera(camera, &file_cameras)?,
+            (None, Some(camera_id)) => *camera_id,
+            (None, None) => scene.add_camera(default_camera(&display, Point3D { x: 0.0, y: 0.0, z: 0.0 }, &Vector::zero())?)
+        }
+    };
+
+    renderer
+        .render_to_file(scene_id, camera_id, options.background_color, &options.output_path)
+        .map_err(|error: RenderError| format!("{}: {}", options.output_path.display(), error))
+}
+
+/**
+ * Scene files (.json or binary .scene) are opened as they are, models are loaded into a new scene.
+ * Returns the scene and the cameras the file defines, the ones of scene files are sorted by id.
+ */
+pub fn open_scene(renderer: &mut RenderingEngine, path: &Path, mtl_path: Option<&Path>, display: &Display) -> Result<(SceneId, Vec<CameraID>), String> {
+    let extension: String = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    if extension == "json" || extension == "scene" {
+        let bytes: Vec<u8> = std::fs::read(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        let scene_id: SceneId = renderer.load_scene(&bytes).map_err(|error| format!("{}: {}", path.display(), error))?;
+
+        let camera_ids: Vec<CameraID> = renderer.get_scene(scene_id).unwrap().get_camera_ids();
+
+        return Ok((scene_id, camera_ids));
+    }
+
+    let scene_id: SceneId = renderer.create_scene();
+    let camera_ids: Vec<CameraID> = load_model(renderer.get_scene(scene_id).unwrap(), path, mtl_path, display)?;
+
+    Ok((scene_id, camera_ids))
+}
+
+fn select_camera(camera: &str, file_cameras: &[CameraID]) -> Result<CameraID, String> {
+    if let Ok(camera_id) = CameraID::parse_str(camera) {
+        return file_cameras
+            .iter()
+            .copied()
+            .find(|file_camera: &CameraID| *file_camera == camera_id)
+            .ok_or_else(|| format!("camera {} does not exist", camera_id));
+    }
+
+    let index: usize = camera.parse().map_err(|_| format!("invalid camera '{}', expected an index or an id", camera))?;
+
+    file_cameras
+        .get(index)
+        .copied()
+        .ok_or_else(|| format!("camera {} does not exist, the file has {} cameras", index, file_cameras.len()))
+}
+
+/** camera with the default field of view of CameraOptions, rotation is pitch, yaw and roll in degrees */
+pub fn default_camera(display: &Display, position: Point3D, rotation: &Vector) -> Result<Camera, String> {
+    let options: CameraOptions = CameraOptions {
+        width: display.width,
+        height: display.height,
+        position,
+        pitch: rotation.x,
+        yaw: rotation.y,
+        roll: rotation.z,
+        ..CameraOptions::default()
+    };
+
+    options.to_camera().map_err(|error: RenderError| error.to_string())
+}
+
+/**
+ * Loads the model file into the scene by its extension, returns the cameras the file defines.
+ * Models without cameras are moved MODEL_DISTANCE along z, so a default camera sees them.
+ */
+pub fn load_model(scene: &mut Scene, path: &Path, mtl_path: Option<&Path>, display: &Display) -> Result<Vec<CameraID>, String> {
+    let error = |message: String| format!("{}: {}", path.display(), message);
+
+    let extension: String = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    let (mesh_ids, root_ids, camera_ids): (Vec<MeshID>, Vec<NodeID>, Vec<CameraID>) = match extension.as_str() {
+        "gltf" | "glb" => {
+            let bytes: Vec<u8> = std::fs::read(path).map_err(|io_error| error(io_error.to_string()))?;
+            let import: GltfImport = scene.load_gltf(&bytes, display).map_err(|parse_error| error(parse_error.to_string()))?;
+
+            for warning in &import.warnings {
+                eprintln!("{}", error(warning.to_string()));
+            }
+
+            let roots: Vec<NodeID> = import.node_ids
+                .iter()
+                .copied()
+                .filter(|node_id: &NodeID| scene.get_node(*node_id).unwrap().parent().is_none())
+                .collect();
+
+            (Vec::new(), roots, import.camera_ids)
+        }
+        "stl" => {
+            let bytes: Vec<u8> = std::fs::read(path).map_err(|io_error| error(io_error.to_string()))?;
+            let mesh_id: MeshID = scene
+                .load_stl(&bytes, Color::new(160, 160, 160, 1.0))
+                .map_err(|parse_error| error(parse_error.to_string()))?;
+
+            (vec![mesh_id], Vec::new(), Vec::new())
+        }
+        "obj" => {
+            let obj: String = std::fs::read_to_string(path).map_err(|io_error| error(io_error.to_string()))?;
+
+            /* without an explicit material library the first one referenced by the model is used */
+            let mtl_path: Option<PathBuf> = mtl_path.map(Path::to_path_buf).or_else(|| {
+                material_libraries(&obj)
+                    .first()
+                    .map(|name: &String| path.with_file_name(name))
+            });
+            let mtl: Option<String> = match mtl_path {
+                Some(mtl_path) => Some(std::fs::read_to_string(&mtl_path).map_err(|io_error| format!("{}: {}", mtl_path.display(), io_error))?),
+                None => None
+            };
+
+            let mesh_ids: Vec<MeshID> = scene.load_obj(&obj, mtl.as_deref()).map_err(|parse_error| error(parse_error.to_string()))?;
+
+            (mesh_ids, Vec::new(), Vec::new())
+        }
+        _ => return Err(error(String::from("unknown model format, expected obj, gltf, glb or stl")))
+    };
+
+    if camera_ids.is_empty() {
+        let offset: Vector = Vector { x: 0.0, y: 0.0, z: MODEL_DISTANCE };
+
+        for mesh_id in mesh_ids {
+            scene.translate_mesh(mesh_id, &offset).map_err(|render_error| error(render_error.to_string()))?;
+        }
+
+        for node_id in root_ids {
+            scene.translate_node(node_id, &offset).map_err(|render_error| error(render_error.to_string()))?;
+        }
+    }
+
+    Ok(camera_ids)
+}
+
+fn parse_render_args(args: &[String]) -> Result<RenderOptions, String> {
+    let mut positional: Vec<&String> = Vec::new();
+    let mut options: RenderOptions = RenderOptions {
+        input_path: PathBuf::new(),
+        output_path: PathBuf::new(),
+        camera: None,
+        position: None,
+        rotation: None,
+        width: 800,
+        height: 800,
+        background_color: Color::new(255, 255, 255, 1.0),
+    };
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            positional.push(arg);
+            continue;
+        }
+
+        let value: &String = args.next().ok_or_else(|| format!("{} expects a value", arg))?;
+
+        match arg.as_str() {
+            "--camera" => options.camera = Some(value.clone()),
+            "--position" => {
+                let [x, y, z] = parse_triple(arg, value)?;
+                options.position = Some(Vector::new(x, y, z));
+            }
+            "--rotation" => {
+                let [pitch, yaw, roll] = parse_triple(arg, value)?;
+                options.rotation = Some(Vector::new(pitch, yaw, roll));
+            }
+            "--size" => {
+                let (width, height) = value
+                    .split_once('x')
+                    .and_then(|(width, height)| Some((width.parse::<usize>().ok()?, height.parse::<usize>().ok()?)))
+                    .filter(|(width, height)| *width > 0 && *height > 0)
+                    .ok_or_else(|| format!("invalid size '{}', expected <width>x<height>", value))?;
+
+                options.width = width;
+                options.height = height;
+            }
+            "--background" => {
+                let [r, g, b] = parse_triple(arg, value)?;
+
+                if [r, g, b].iter().any(|channel: &f32| !(0.0..=255.0).contains(channel)) {
+                    return Err(format!("invalid background '{}', channels are from 0 to 255", value));
+                }
+
+                options.background_color = Color::new(r as u8, g as u8, b as u8, 1.0);
+            }
+            _ => return Err(format!("unknown option {}\n\n{}", arg, USAGE))
+        }
+    }
+
+    if options.camera.is_some() && (options.position.is_some() || options.rotation.is_some()) {
+        return Err(format!("--camera can't be combined with --position or --rotation, they create a new camera\n\n{}", USAGE));
+    }
+
+    match positional.as_slice() {
+        [input_path, output_path] => {
+            options.input_path = PathBuf::from(input_path);
+            options.output_path = PathBuf::from(output_path);
+
+            Ok(options)
+        }
+        _ => Err(String::from(USAGE))
+    }
+}
+
+fn parse_triple(option: &str, value: &str) -> Result<[f32; 3], String> {
+    let numbers: Vec<f32> = value
+        .split(',')
+        .map(|number: &str| number.trim().parse::<f32>())
+        .collect::<Result<Vec<f32>, _>>()
+        .map_err(|_| format!("invalid {} '{}'", option, value))?;
+
+    <[f32; 3]>::try_from(numbers).map_err(|_| format!("{} expects 3 comma separated numbers", option))
+}
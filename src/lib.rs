@@ -1,16 +1,31 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use uuid::Uuid;
 use wasm_bindgen::prelude::wasm_bindgen;
-use wasm_bindgen::JsValue;
+use wasm_bindgen::{JsError, JsValue};
 
+use crate::rendering_engine::error::RenderError;
+use crate::rendering_engine::format::bytes_to_text;
+use crate::rendering_engine::format::gltf::GltfImport;
+use crate::rendering_engine::format::scene_file::SceneFileFormat;
+use crate::rendering_engine::scene::camera::controller::{CameraController, FlyController, OrbitController, WORLD_UP};
 use crate::rendering_engine::scene::camera::display::Display;
-use crate::rendering_engine::scene::camera::Camera;
+use crate::rendering_engine::scene::camera::options::{check_fov, check_lens, check_projection, CameraOptions};
+use crate::rendering_engine::scene::camera::sensor::Sensor;
+use crate::rendering_engine::scene::camera::{Camera, Projection};
+use crate::rendering_engine::scene::light::Light;
 use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model::culling::Culling;
+use crate::rendering_engine::scene::model::shading::Shading;
+use crate::rendering_engine::scene::model::texture::{Sampler, Texture};
+use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
 use crate::rendering_engine::scene::model_3d::face::Face;
 use crate::rendering_engine::scene::model_3d::point::Point as Point3D;
+use crate::rendering_engine::scene::model_3d::transform::Transform;
 use crate::rendering_engine::scene::model_3d::vector::Vector as Vector3D;
 use crate::rendering_engine::RenderingEngine;
 
-mod rendering_engine;
+pub mod rendering_engine;
 
 #[wasm_bindgen]
 extern "C" {
@@ -18,133 +33,819 @@ extern "C" {
     fn log(s: &str);
 }
 
-static mut RENDERING_ENGINES: Vec<RenderingEngine> = vec![];
-
+/** owns its engine, every instance is independent and is freed when JS calls free() */
 #[wasm_bindgen]
-pub unsafe fn init_renderer() {
-    RENDERING_ENGINES.push(RenderingEngine::new());
+#[derive(Default)]
+pub struct Renderer {
+    engine: RenderingEngine,
+    /** RGBA bytes of the last frame, reused by every render call */
+    frame: Vec<u8>,
+    frame_width: usize,
+    frame_height: usize,
 }
 
-#[wasm_bindgen]
-pub unsafe fn init_scene() -> String {
-    RENDERING_ENGINES[0].create_scene().to_string()
+/** ids are passed from JS as strings, every RenderError of an export is thrown as a JS Error through JsError */
+fn parse_id(id: &str) -> Result<Uuid, RenderError> {
+    Uuid::parse_str(id).map_err(|_| RenderError::BadInput(format!("'{}' is not a valid id", id)))
 }
 
-#[wasm_bindgen]
-pub unsafe fn add_camera(scene_id: String) -> String {
-    let scene_id: Uuid = Uuid::parse_str(scene_id.as_str()).unwrap();
-
-    let width: usize = 800;
-    let height: usize = 800;
-
-    RENDERING_ENGINES[0]
-        .get_scene(scene_id).unwrap()
-        .add_camera(Camera::new(
-            (width as f32) * 2.0,
-            Point3D { x: 0.0, y: 0.0, z: 0.0 },
-            0.0,
-            0.0,
-            0.0,
-            Display::new(width, height)
-        ))
-        .to_string()
+fn parse_optional_id(id: Option<String>) -> Result<Option<Uuid>, RenderError> {
+    id.as_deref().map(parse_id).transpose()
 }
 
-#[wasm_bindgen]
-pub unsafe fn add_mesh(scene_id: String) -> String {
-    let scene_id: Uuid = Uuid::parse_str(scene_id.as_str()).unwrap();
-
-    RENDERING_ENGINES[0]
-        .get_scene(scene_id).unwrap()
-        .add_mesh(
-            vec![
-                Point3D { x: 0.0, y: 0.0, z: 250.0 }, /* bottom left */
-                Point3D { x: 0.0, y: 50.0, z: 250.0 }, /* top left */
-                Point3D { x: 50.0, y: 0.0, z: 250.0 }, /* top right */
-            ],
-            vec![
-                Face::new([0, 1, 2], Color::new(255, 0, 0, 0.5))
-            ]
-        ).to_string()
+/** name is the argument the value was passed as, for the error message */
+fn from_js<T: DeserializeOwned>(value: JsValue, name: &str) -> Result<T, RenderError> {
+    serde_wasm_bindgen::from_value(value).map_err(|error: serde_wasm_bindgen::Error| RenderError::BadInput(format!("{}: {}", name, error)))
 }
 
-#[wasm_bindgen]
-pub unsafe fn add_cube(scene_id: String, position: JsValue, width: f32, height: f32, length: f32) -> String {
-    let scene_id: Uuid = Uuid::parse_str(scene_id.as_str()).unwrap();
+/** undefined and null are None */
+fn optional_from_js<T: DeserializeOwned>(value: JsValue, name: &str) -> Result<Option<T>, RenderError> {
+    if value.is_undefined() || value.is_null() {
+        Ok(None)
+    } else {
+        from_js(value, name).map(Some)
+    }
+}
 
-    let position: Point3D = serde_wasm_bindgen::from_value(position).unwrap();
+/** the camera as it is saved, with the euler angles of its orientation which get_camera always returned */
+#[derive(Serialize)]
+struct CameraState<'a> {
+    #[serde(flatten)]
+    camera: &'a Camera,
+    pitch_angle: f32,
+    yaw_angle: f32,
+    roll_angle: f32,
+}
 
-    RENDERING_ENGINES[0].get_scene(scene_id).unwrap().add_cube(
-        position,
-        width, height, length,
-        Color::new(255, 0, 0, 0.5)
-    ).to_string()
+#[derive(Serialize)]
+struct FieldOfView {
+    focal_length: f32,
+    vertical_fov: f32,
+    horizontal_fov: f32,
+    lens_focal_length: f32,
 }
 
 #[wasm_bindgen]
-pub unsafe fn move_camera(scene_id: String, camera_id: String, delta: JsValue) {
-    let scene_id: Uuid = Uuid::parse_str(scene_id.as_str()).unwrap();
-    let camera_id: Uuid = Uuid::parse_str(camera_id.as_str()).unwrap();
+impl Renderer {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Renderer {
+        Renderer {
+            engine: RenderingEngine::new(),
+            frame: Vec::new(),
+            frame_width: 0,
+            frame_height: 0,
+        }
+    }
 
-    let delta: Vector3D = serde_wasm_bindgen::from_value(delta).unwrap();
+    pub fn init_scene(&mut self) -> String {
+        self.engine.create_scene().to_string()
+    }
 
-    RENDERING_ENGINES[0]
-        .get_scene(scene_id).unwrap()
-        .get_camera_mut(camera_id).unwrap()
-        .reposition(delta);
-}
+    /** returns false if the scene does not exist */
+    pub fn remove_scene(&mut self, scene_id: String) -> Result<bool, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
 
-#[wasm_bindgen]
-pub unsafe fn get_camera(scene_id: String, camera_id: String) -> JsValue {
-    let scene_id: Uuid = Uuid::parse_str(scene_id.as_str()).unwrap();
-    let camera_id: Uuid = Uuid::parse_str(camera_id.as_str()).unwrap();
-
-    serde_wasm_bindgen::to_value(
-        RENDERING_ENGINES[0]
-            .get_scene(scene_id).unwrap()
-            .get_camera_mut(camera_id).unwrap()
-    ).unwrap()
-}
+        Ok(self.engine.remove_scene(scene_id))
+    }
 
-#[wasm_bindgen]
-pub unsafe fn rotate_camera(scene_id: String, camera_id: String, delta: JsValue) {
-    let scene_id: Uuid = Uuid::parse_str(scene_id.as_str()).unwrap();
-    let camera_id: Uuid = Uuid::parse_str(camera_id.as_str()).unwrap();
+    /**
+     * Options are an object with any of:
+     * - width and height of the display in pixels, 800 by default
+     * - position {x, y, z}, the origin by default
+     * - pitch, yaw and roll in degrees, 0 by default
+     * - focal_length in pixels, vertical_fov or horizontal_fov in degrees, or lens_focal_length in millimeters,
+     *   at most one of these four, the focal length is width plus height without any of them
+     * - sensor {width, height} in millimeters for lens_focal_length, full frame by default
+     * - near and far clipping distances, 1 and 100000 by default, 0 < near < far
+     * - projection "perspective", the default, or {orthographic: {scale}} with the size of a pixel in world units
+     * Throws if an option is invalid or unknown.
+     */
+    pub fn add_camera(&mut self, scene_id: String, options: JsValue) -> Result<String, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
 
-    let delta: Vector3D = serde_wasm_bindgen::from_value(delta).unwrap();
+        let options: CameraOptions = optional_from_js(options, "options")?.unwrap_or_default();
+        let camera: Camera = options.to_camera()?;
 
-    RENDERING_ENGINES[0]
-        .get_scene(scene_id).unwrap()
-        .get_camera_mut(camera_id).unwrap()
-        .rotate(&delta);
-}
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .add_camera(camera)
+            .to_string())
+    }
 
-#[wasm_bindgen]
-pub unsafe fn move_camera_focal_length(scene_id: String, camera_id: String, delta: f32) {
-    let scene_id: Uuid = Uuid::parse_str(scene_id.as_str()).unwrap();
-    let camera_id: Uuid = Uuid::parse_str(camera_id.as_str()).unwrap();
-
-    RENDERING_ENGINES[0]
-        .get_scene(scene_id).unwrap()
-        .get_camera_mut(camera_id).unwrap()
-        .move_focal_length(delta);
-}
+    /** the controller of the camera is released, returns false if the camera does not exist */
+    pub fn remove_camera(&mut self, scene_id: String, camera_id: String) -> Result<bool, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
 
-// #[wasm_bindgen]
-// pub unsafe fn render(scene_id: String, camera_id: String) -> JsValue {
-//     let scene_id: Uuid = Uuid::parse_str(scene_id.as_str()).unwrap();
-//     let camera_id: Uuid = Uuid::parse_str(camera_id.as_str()).unwrap();
-//
-//     let triangles: Vec<Triangle2D> = RENDERING_ENGINES[0]
-//         .get_scene(scene_id).unwrap()
-//         .render(camera_id).unwrap();
-//
-//     serde_wasm_bindgen::to_value(&triangles).unwrap()
-// }
-#[wasm_bindgen]
-pub unsafe fn render_new(scene_id: String, camera_id: String) -> JsValue {
-    let scene_id: Uuid = Uuid::parse_str(scene_id.as_str()).unwrap();
-    let camera_id: Uuid = Uuid::parse_str(camera_id.as_str()).unwrap();
+        Ok(self.engine.remove_camera(scene_id, camera_id)?)
+    }
+
+    /** for canvas resizes, the camera keeps its position, orientation and vertical field of view */
+    pub fn set_camera_resolution(&mut self, scene_id: String, camera_id: String, width: usize, height: usize) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        if width == 0 || height == 0 {
+            return Err(RenderError::BadInput(format!("resolution {}x{} is empty", width, height)).into());
+        }
+
+        self.engine
+            .get_scene(scene_id)?
+            .get_camera_mut(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?
+            .set_display(Display::new(width, height));
+
+        Ok(())
+    }
+
+    /** in degrees, throws unless the angle is between 0 and 180 */
+    pub fn set_camera_vertical_fov(&mut self, scene_id: String, camera_id: String, vertical_fov: f32) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        check_fov("vertical_fov", vertical_fov)?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .get_camera_mut(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?
+            .set_vertical_fov(vertical_fov);
+
+        Ok(())
+    }
+
+    /** in degrees, throws unless the angle is between 0 and 180 */
+    pub fn set_camera_horizontal_fov(&mut self, scene_id: String, camera_id: String, horizontal_fov: f32) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        check_fov("horizontal_fov", horizontal_fov)?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .get_camera_mut(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?
+            .set_horizontal_fov(horizontal_fov);
+
+        Ok(())
+    }
+
+    /** lens focal length in millimeters, sensor is {width, height} in millimeters, full frame if undefined */
+    pub fn set_camera_lens(&mut self, scene_id: String, camera_id: String, lens_focal_length: f32, sensor: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        let sensor: Sensor = optional_from_js(sensor, "sensor")?.unwrap_or(Sensor::FULL_FRAME);
+        check_lens(lens_focal_length, &sensor)?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .get_camera_mut(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?
+            .set_lens(lens_focal_length, &sensor);
+
+        Ok(())
+    }
+
+    /**
+     * Projection is "perspective" or {orthographic: {scale}}, scale is the size of a pixel in world units.
+     * The focal length is kept while the camera is orthographic.
+     */
+    pub fn set_camera_projection(&mut self, scene_id: String, camera_id: String, projection: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        let projection: Projection = from_js(projection, "projection")?;
+        check_projection(&projection)?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .get_camera_mut(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?
+            .set_projection(projection);
+
+        Ok(())
+    }
+
+    /**
+     * Returns {focal_length, vertical_fov, horizontal_fov, lens_focal_length}, angles in degrees,
+     * lens_focal_length in millimeters on the sensor, full frame if undefined.
+     */
+    pub fn get_camera_field_of_view(&mut self, scene_id: String, camera_id: String, sensor: JsValue) -> Result<JsValue, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        let sensor: Sensor = optional_from_js(sensor, "sensor")?.unwrap_or(Sensor::FULL_FRAME);
+
+        let camera: &Camera = self.engine
+            .get_scene(scene_id)?
+            .get_camera(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?;
+
+        Ok(serde_wasm_bindgen::to_value(&FieldOfView {
+            focal_length: camera.focal_length(),
+            vertical_fov: camera.vertical_fov(),
+            horizontal_fov: camera.horizontal_fov(),
+            lens_focal_length: camera.lens_focal_length(&sensor),
+        })?)
+    }
+
+    pub fn add_mesh(&mut self, scene_id: String) -> Result<String, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .add_mesh(
+                vec![
+                    Point3D { x: 0.0, y: 0.0, z: 250.0 }, /* bottom left */
+                    Point3D { x: 0.0, y: 50.0, z: 250.0 }, /* top left */
+                    Point3D { x: 50.0, y: 0.0, z: 250.0 }, /* top right */
+                ],
+                vec![
+                    Face::new([0, 1, 2], Color::new(255, 0, 0, 0.5))
+                ]
+            )?
+            .to_string())
+    }
+
+    pub fn add_cube(&mut self, scene_id: String, position: JsValue, width: f32, height: f32, length: f32) -> Result<String, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+
+        let position: Point3D = from_js(position, "position")?;
+
+        Ok(self.engine.get_scene(scene_id)?.add_cube(
+            position,
+            width, height, length,
+            Color::new(255, 0, 0, 0.5)
+        )?.to_string())
+    }
+
+    pub fn move_camera(&mut self, scene_id: String, camera_id: String, delta: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        let delta: Vector3D = from_js(delta, "delta")?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .get_camera_mut(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?
+            .reposition(delta);
+
+        Ok(())
+    }
+
+    /**
+     * Returns {focal_length, center, pitch_angle, yaw_angle, roll_angle, orientation, display, near, far, projection},
+     * angles in degrees, orientation is the quaternion {w, x, y, z} the angles are taken from.
+     */
+    pub fn get_camera(&mut self, scene_id: String, camera_id: String) -> Result<JsValue, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        let camera: &Camera = self.engine
+            .get_scene(scene_id)?
+            .get_camera(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?;
+
+        Ok(serde_wasm_bindgen::to_value(&CameraState {
+            camera,
+            pitch_angle: camera.pitch_angle(),
+            yaw_angle: camera.yaw_angle(),
+            roll_angle: camera.roll_angle(),
+        })?)
+    }
+
+    pub fn rotate_camera(&mut self, scene_id: String, camera_id: String, delta: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        let delta: Vector3D = from_js(delta, "delta")?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .get_camera_mut(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?
+            .rotate(&delta);
+
+        Ok(())
+    }
+
+    /** the camera orbits around the pivot {x, y, z} and turns to it, see orbit_camera, pan_camera and zoom_camera */
+    pub fn set_camera_orbit(&mut self, scene_id: String, camera_id: String, pivot: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        let pivot: Point3D = from_js(pivot, "pivot")?;
+
+        Ok(self.engine.set_camera_controller(scene_id, camera_id, Some(CameraController::Orbit(OrbitController::new(pivot))))?)
+    }
+
+    /** first person control, see look_camera and fly_camera */
+    pub fn set_camera_fly(&mut self, scene_id: String, camera_id: String) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        Ok(self.engine.set_camera_controller(scene_id, camera_id, Some(CameraController::Fly(FlyController)))?)
+    }
+
+    pub fn release_camera_controller(&mut self, scene_id: String, camera_id: String) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        Ok(self.engine.set_camera_controller(scene_id, camera_id, None)?)
+    }
+
+    /** target and up are {x, y, z}, up is the y axis if undefined */
+    pub fn camera_look_at(&mut self, scene_id: String, camera_id: String, target: JsValue, up: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        let target: Point3D = from_js(target, "target")?;
+        let up: Vector3D = optional_from_js(up, "up")?.unwrap_or(WORLD_UP);
+
+        Ok(self.engine.camera_look_at(scene_id, camera_id, &target, &up)?)
+    }
+
+    /** angles in degrees, the camera needs an orbit controller */
+    pub fn orbit_camera(&mut self, scene_id: String, camera_id: String, delta_yaw: f32, delta_pitch: f32) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        Ok(self.engine.orbit_camera(scene_id, camera_id, delta_yaw, delta_pitch)?)
+    }
+
+    /** deltas in pixels, x right and y up, the camera needs an orbit controller */
+    pub fn pan_camera(&mut self, scene_id: String, camera_id: String, delta_x: f32, delta_y: f32) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        Ok(self.engine.pan_camera(scene_id, camera_id, delta_x, delta_y)?)
+    }
+
+    /** factors below 1 zoom in, the camera needs an orbit controller */
+    pub fn zoom_camera(&mut self, scene_id: String, camera_id: String, factor: f32) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        Ok(self.engine.zoom_camera(scene_id, camera_id, factor)?)
+    }
+
+    /** angles in degrees, positive yaw turns right and positive pitch looks up, the camera needs a fly controller */
+    pub fn look_camera(&mut self, scene_id: String, camera_id: String, delta_yaw: f32, delta_pitch: f32) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        Ok(self.engine.look_camera(scene_id, camera_id, delta_yaw, delta_pitch)?)
+    }
+
+    /** delta is {x, y, z}, z forward in the view direction, the camera needs a fly controller */
+    pub fn fly_camera(&mut self, scene_id: String, camera_id: String, delta: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        let delta: Vector3D = from_js(delta, "delta")?;
+
+        Ok(self.engine.fly_camera(scene_id, camera_id, &delta)?)
+    }
+
+    pub fn move_camera_focal_length(&mut self, scene_id: String, camera_id: String, delta: f32) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .get_camera_mut(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?
+            .move_focal_length(delta);
+
+        Ok(())
+    }
+
+    pub fn translate_mesh(&mut self, scene_id: String, mesh_id: String, delta: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let mesh_id: Uuid = parse_id(&mesh_id)?;
+
+        let delta: Vector3D = from_js(delta, "delta")?;
+
+        self.engine.translate_mesh(scene_id, mesh_id, &delta)?;
+
+        Ok(())
+    }
+
+    pub fn rotate_mesh(&mut self, scene_id: String, mesh_id: String, delta: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let mesh_id: Uuid = parse_id(&mesh_id)?;
+
+        let delta: Vector3D = from_js(delta, "delta")?;
+
+        self.engine.rotate_mesh(scene_id, mesh_id, &delta)?;
+
+        Ok(())
+    }
+
+    pub fn scale_mesh(&mut self, scene_id: String, mesh_id: String, factors: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let mesh_id: Uuid = parse_id(&mesh_id)?;
+
+        let factors: Vector3D = from_js(factors, "factors")?;
+
+        self.engine.scale_mesh(scene_id, mesh_id, &factors)?;
+
+        Ok(())
+    }
+
+    /**
+     * Shading is "flat", "gouraud" or {phong: {specular, shininess}}.
+     * Smooth shading uses the normals of the mesh, meshes without them get normals computed with the crease angle.
+     */
+    pub fn set_mesh_shading(&mut self, scene_id: String, mesh_id: String, shading: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let mesh_id: Uuid = parse_id(&mesh_id)?;
+
+        let shading: Shading = from_js(shading, "shading")?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .set_mesh_shading(mesh_id, shading)?;
+
+        Ok(())
+    }
+
+    /**
+     * Culling is "none", "back" or "front", meshes draw both sides of their faces by default, cubes only the front.
+     * The front of a face is the side its points go clockwise on the display.
+     */
+    pub fn set_mesh_culling(&mut self, scene_id: String, mesh_id: String, culling: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let mesh_id: Uuid = parse_id(&mesh_id)?;
+
+        let culling: Culling = from_js(culling, "culling")?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .set_mesh_culling(mesh_id, culling)?;
+
+        Ok(())
+    }
+
+    /**
+     * Uvs are [{x, y}], one per point of the mesh, an empty array removes them.
+     * (0, 0) is the top left corner of a texture and (1, 1) the bottom right one.
+     */
+    pub fn set_mesh_uvs(&mut self, scene_id: String, mesh_id: String, uvs: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let mesh_id: Uuid = parse_id(&mesh_id)?;
+
+        let uvs: Vec<Point2D> = from_js(uvs, "uvs")?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .set_mesh_uvs(mesh_id, uvs)?;
+
+        Ok(())
+    }
+
+    /**
+     * Texture id of the scene, or undefined to remove the texture. The texture multiplies the face colors.
+     * Sampler is {filter: "nearest" | "bilinear", wrap: "repeat" | "clamp" | "mirror"}, bilinear and repeat if undefined.
+     */
+    pub fn set_mesh_texture(&mut self, scene_id: String, mesh_id: String, texture_id: Option<String>, sampler: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let mesh_id: Uuid = parse_id(&mesh_id)?;
+        let texture_id: Option<Uuid> = parse_optional_id(texture_id)?;
+
+        let sampler: Sampler = optional_from_js(sampler, "sampler")?.unwrap_or_default();
+
+        self.engine
+            .get_scene(scene_id)?
+            .set_mesh_texture(mesh_id, texture_id, sampler)?;
+
+        Ok(())
+    }
+
+    /** in degrees, faces meeting at a sharper angle keep a crease when normals are computed, 60 by default */
+    pub fn set_mesh_crease_angle(&mut self, scene_id: String, mesh_id: String, crease_angle: f32) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let mesh_id: Uuid = parse_id(&mesh_id)?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .set_mesh_crease_angle(mesh_id, crease_angle)?;
+
+        Ok(())
+    }
+
+    pub fn add_node(&mut self, scene_id: String, parent_id: Option<String>) -> Result<String, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let parent_id: Option<Uuid> = parse_optional_id(parent_id)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .add_node(parent_id, Transform::identity())?
+            .to_string())
+    }
+
+    pub fn remove_node(&mut self, scene_id: String, node_id: String) -> Result<bool, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let node_id: Uuid = parse_id(&node_id)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .remove_node(node_id))
+    }
+
+    pub fn reparent_node(&mut self, scene_id: String, node_id: String, parent_id: Option<String>) -> Result<bool, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let node_id: Uuid = parse_id(&node_id)?;
+        let parent_id: Option<Uuid> = parse_optional_id(parent_id)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .reparent_node(node_id, parent_id))
+    }
+
+    pub fn attach_mesh(&mut self, scene_id: String, mesh_id: String, node_id: Option<String>) -> Result<bool, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let mesh_id: Uuid = parse_id(&mesh_id)?;
+        let node_id: Option<Uuid> = parse_optional_id(node_id)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .attach_mesh(mesh_id, node_id))
+    }
+
+    pub fn attach_camera(&mut self, scene_id: String, camera_id: String, node_id: Option<String>) -> Result<bool, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+        let node_id: Option<Uuid> = parse_optional_id(node_id)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .attach_camera(camera_id, node_id))
+    }
+
+    pub fn attach_light(&mut self, scene_id: String, light_id: String, node_id: Option<String>) -> Result<bool, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let light_id: Uuid = parse_id(&light_id)?;
+        let node_id: Option<Uuid> = parse_optional_id(node_id)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .attach_light(light_id, node_id))
+    }
+
+    pub fn translate_node(&mut self, scene_id: String, node_id: String, delta: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let node_id: Uuid = parse_id(&node_id)?;
+
+        let delta: Vector3D = from_js(delta, "delta")?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .translate_node(node_id, &delta)?;
+
+        Ok(())
+    }
+
+    pub fn rotate_node(&mut self, scene_id: String, node_id: String, delta: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let node_id: Uuid = parse_id(&node_id)?;
+
+        let delta: Vector3D = from_js(delta, "delta")?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .rotate_node(node_id, &delta)?;
+
+        Ok(())
+    }
+
+    pub fn scale_node(&mut self, scene_id: String, node_id: String, factors: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let node_id: Uuid = parse_id(&node_id)?;
+
+        let factors: Vector3D = from_js(factors, "factors")?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .scale_node(node_id, &factors)?;
+
+        Ok(())
+    }
+
+    /**
+     * Light is one of {ambient: {color, intensity}}, {directional: {color, intensity, direction}},
+     * {point: {color, intensity, position, attenuation}} or {spot: {color, intensity, position, direction, inner_angle, outer_angle, attenuation}},
+     * attenuation {constant, linear, quadratic} is optional. A scene without lights is rendered unlit.
+     */
+    pub fn add_light(&mut self, scene_id: String, light: JsValue) -> Result<String, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+
+        let light: Light = from_js(light, "light")?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .add_light(light)?
+            .to_string())
+    }
+
+    /** replaces the light, see add_light */
+    pub fn set_light(&mut self, scene_id: String, light_id: String, light: JsValue) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let light_id: Uuid = parse_id(&light_id)?;
+
+        let light: Light = from_js(light, "light")?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .set_light(light_id, light)?;
+
+        Ok(())
+    }
+
+    pub fn get_light(&mut self, scene_id: String, light_id: String) -> Result<JsValue, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let light_id: Uuid = parse_id(&light_id)?;
+
+        Ok(serde_wasm_bindgen::to_value(
+            self.engine
+                .get_scene(scene_id)?
+                .get_light(light_id).ok_or(RenderError::UnknownLight(light_id))?
+        )?)
+    }
+
+    pub fn remove_light(&mut self, scene_id: String, light_id: String) -> Result<bool, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let light_id: Uuid = parse_id(&light_id)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .remove_light(light_id))
+    }
+
+    /** PNG or PPM file contents, returns the id of the texture */
+    pub fn add_texture(&mut self, scene_id: String, bytes: &[u8]) -> Result<String, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+
+        let texture: Texture = Texture::decode(bytes)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .add_texture(texture)
+            .to_string())
+    }
+
+    /** 4 bytes per pixel, rows from top to bottom, like the data of a canvas ImageData */
+    pub fn add_texture_rgba(&mut self, scene_id: String, width: usize, height: usize, rgba: Vec<u8>) -> Result<String, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+
+        let texture: Texture = Texture::new(width, height, rgba)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .add_texture(texture)
+            .to_string())
+    }
+
+    /** replaces the image of the texture by PNG or PPM file contents, meshes using it show the new one */
+    pub fn set_texture(&mut self, scene_id: String, texture_id: String, bytes: &[u8]) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let texture_id: Uuid = parse_id(&texture_id)?;
+
+        let texture: Texture = Texture::decode(bytes)?;
+
+        self.engine
+            .get_scene(scene_id)?
+            .set_texture(texture_id, texture)?;
+
+        Ok(())
+    }
+
+    /** meshes using the texture go back to their face colors */
+    pub fn remove_texture(&mut self, scene_id: String, texture_id: String) -> Result<bool, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let texture_id: Uuid = parse_id(&texture_id)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .remove_texture(texture_id))
+    }
+
+    /** returns ids of the added meshes, throws if either file is not a valid OBJ/MTL file */
+    pub fn load_obj(&mut self, scene_id: String, obj: &[u8], mtl: Option<Vec<u8>>) -> Result<JsValue, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+
+        let obj: &str = bytes_to_text(obj)?;
+        let mtl: Option<&str> = match &mtl {
+            Some(mtl) => Some(bytes_to_text(mtl)?),
+            None => None
+        };
+
+        let mesh_ids: Vec<String> = self.engine
+            .get_scene(scene_id)?
+            .load_obj(obj, mtl)?
+            .iter()
+            .map(Uuid::to_string)
+            .collect();
+
+        Ok(serde_wasm_bindgen::to_value(&mesh_ids)?)
+    }
+
+    /**
+     * Accepts .gltf and .glb contents, returns ids of the added nodes, meshes, cameras and textures with the list of warnings.
+     * Imported cameras keep the display height, throws if the file is not valid glTF.
+     */
+    pub fn load_gltf(&mut self, scene_id: String, bytes: &[u8], display_width: usize, display_height: usize) -> Result<JsValue, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+
+        let import: GltfImport = self.engine
+            .get_scene(scene_id)?
+            .load_gltf(bytes, &Display::new(display_width, display_height))?;
+
+        Ok(serde_wasm_bindgen::to_value(&import)?)
+    }
+
+    /** throws if the bytes are not an ASCII or binary STL file */
+    pub fn load_stl(&mut self, scene_id: String, bytes: &[u8], color: JsValue) -> Result<String, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+
+        let color: Color = from_js(color, "color")?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .load_stl(bytes, color)?
+            .to_string())
+    }
+
+    pub fn export_stl(&mut self, scene_id: String, mesh_id: String) -> Result<Vec<u8>, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let mesh_id: Uuid = parse_id(&mesh_id)?;
+
+        Ok(self.engine
+            .get_scene(scene_id)?
+            .export_stl(mesh_id)?)
+    }
+
+    /** JSON scene file, or the compact binary one if binary is true */
+    pub fn save_scene(&self, scene_id: String, binary: bool) -> Result<Vec<u8>, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+
+        let format: SceneFileFormat = if binary {
+            SceneFileFormat::Binary
+        } else {
+            SceneFileFormat::Json
+        };
+
+        Ok(self.engine.save_scene(scene_id, format)?)
+    }
+
+    /** returns the id of the new scene, throws if the file is invalid or of another version */
+    pub fn load_scene(&mut self, bytes: &[u8]) -> Result<String, JsError> {
+        Ok(self.engine.load_scene(bytes)?.to_string())
+    }
+
+    // pub fn render(&mut self, scene_id: String, camera_id: String) -> JsValue {
+    //     let scene_id: Uuid = Uuid::parse_str(scene_id.as_str()).unwrap();
+    //     let camera_id: Uuid = Uuid::parse_str(camera_id.as_str()).unwrap();
+    //
+    //     let triangles: Vec<Triangle2D> = self.engine
+    //         .get_scene(scene_id).unwrap()
+    //         .render(camera_id).unwrap();
+    //
+    //     serde_wasm_bindgen::to_value(&triangles).unwrap()
+    // }
+    /** image as nested arrays of pixel objects, `render` with `frame_ptr` avoids converting every pixel */
+    pub fn render_new(&self, scene_id: String, camera_id: String) -> Result<JsValue, JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        Ok(serde_wasm_bindgen::to_value(&self.engine.render(scene_id, camera_id, Color::new(255, 255, 255, 1.0))?)?)
+    }
+
+    /**
+     * Renders into the frame buffer kept by the renderer, nothing is copied to JS.
+     * View the frame with `new Uint8ClampedArray(memory.buffer, renderer.frame_ptr(), renderer.frame_len())`
+     * and pass it to `new ImageData(pixels, renderer.frame_width(), renderer.frame_height())`.
+     * The view has to be created again after every call, as growing the wasm memory detaches it.
+     */
+    pub fn render(&mut self, scene_id: String, camera_id: String) -> Result<(), JsError> {
+        let scene_id: Uuid = parse_id(&scene_id)?;
+        let camera_id: Uuid = parse_id(&camera_id)?;
+
+        let (width, height): (usize, usize) = self.engine
+            .render_rgba(scene_id, camera_id, Color::new(255, 255, 255, 1.0), &mut self.frame)?;
+
+        self.frame_width = width;
+        self.frame_height = height;
+
+        Ok(())
+    }
+
+    /** address of the RGBA bytes of the last frame in the wasm memory */
+    pub fn frame_ptr(&self) -> *const u8 {
+        self.frame.as_ptr()
+    }
+
+    pub fn frame_len(&self) -> usize {
+        self.frame.len()
+    }
+
+    pub fn frame_width(&self) -> usize {
+        self.frame_width
+    }
 
-    serde_wasm_bindgen::to_value(&RENDERING_ENGINES[0].render(scene_id, camera_id, Color::new(255, 255, 255, 1.0))).unwrap()
+    pub fn frame_height(&self) -> usize {
+        self.frame_height
+    }
 }
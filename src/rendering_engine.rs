@@ -1,29 +1,44 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use uuid::Uuid;
 
 use scene::Scene;
 
 use crate::rendering_engine::engine::compositor::Image;
-use crate::rendering_engine::engine::renderer::render;
+use crate::rendering_engine::engine::renderer::{render, render_rgba};
+use crate::rendering_engine::error::RenderError;
+use crate::rendering_engine::format::image::write_image;
+use crate::rendering_engine::format::scene_file::SceneFileFormat;
+use crate::rendering_engine::scene::camera::controller::{CameraController, FlyController, OrbitController};
+use crate::rendering_engine::scene::camera::options::check_positive;
 use crate::rendering_engine::scene::camera::Camera;
 use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model_3d::matrix::Mat4;
+use crate::rendering_engine::scene::model_3d::mesh::Mesh;
+use crate::rendering_engine::scene::model_3d::point::Point;
+use crate::rendering_engine::scene::model_3d::transform::Transform;
 use crate::rendering_engine::scene::model_3d::vector::Vector;
-use crate::rendering_engine::scene::CameraID;
+use crate::rendering_engine::scene::{CameraID, MeshID, NodeID};
 
 pub mod scene;
 pub mod engine;
+pub mod format;
+pub mod error;
 
 pub type SceneId = Uuid;
 
+#[derive(Default)]
 pub struct RenderingEngine {
-    scenes: HashMap<SceneId, Scene>
+    scenes: HashMap<SceneId, Scene>,
+    camera_controllers: HashMap<(SceneId, CameraID), CameraController>,
 }
 
 impl RenderingEngine {
     pub fn new() -> RenderingEngine {
         RenderingEngine {
-            scenes: HashMap::new()
+            scenes: HashMap::new(),
+            camera_controllers: HashMap::new(),
         }
     }
 
@@ -36,35 +51,358 @@ impl RenderingEngine {
         scene_id
     }
 
-    pub fn get_scene(&mut self, scene_id: SceneId) -> Option<&mut Scene> {
-        self.scenes.get_mut(&scene_id)
+    /** adds a scene saved with Scene::save, ids stored in the file are kept */
+    pub fn load_scene(&mut self, bytes: &[u8]) -> Result<SceneId, RenderError> {
+        let scene: Scene = Scene::load(bytes)?;
+        let scene_id: SceneId = Uuid::new_v4();
+
+        self.scenes.insert(scene_id, scene);
+
+        Ok(scene_id)
     }
 
-    pub fn reposition_camera(&mut self, scene_id: SceneId, camera_id: CameraID, delta: Vector) {
-        self.scenes
-            .get_mut(&scene_id).unwrap()
-            .get_camera_mut(camera_id).unwrap()
-            .reposition(delta);
+    /** returns false if the scene does not exist, the controllers of its cameras are dropped */
+    pub fn remove_scene(&mut self, scene_id: SceneId) -> bool {
+        self.camera_controllers.retain(|(controlled_scene_id, _): &(SceneId, CameraID), _| *controlled_scene_id != scene_id);
+
+        self.scenes.remove(&scene_id).is_some()
+    }
+
+    pub fn save_scene(&self, scene_id: SceneId, format: SceneFileFormat) -> Result<Vec<u8>, RenderError> {
+        Ok(self.scene(scene_id)?.save(format))
     }
 
-    pub fn rotate_camera(&mut self, scene_id: SceneId, camera_id: CameraID, delta: &Vector) {
+    pub fn get_scene(&mut self, scene_id: SceneId) -> Result<&mut Scene, RenderError> {
         self.scenes
-            .get_mut(&scene_id).unwrap()
-            .get_camera_mut(camera_id).unwrap()
-            .rotate(delta);
+            .get_mut(&scene_id)
+            .ok_or(RenderError::UnknownScene(scene_id))
     }
 
-    pub fn move_camera_focal_length(&mut self, scene_id: SceneId, camera_id: CameraID, delta: f32) {
+    fn scene(&self, scene_id: SceneId) -> Result<&Scene, RenderError> {
         self.scenes
-            .get_mut(&scene_id).unwrap()
-            .get_camera_mut(camera_id).unwrap()
-            .move_focal_length(delta);
+            .get(&scene_id)
+            .ok_or(RenderError::UnknownScene(scene_id))
+    }
+
+    fn camera_mut(&mut self, scene_id: SceneId, camera_id: CameraID) -> Result<&mut Camera, RenderError> {
+        self.get_scene(scene_id)?
+            .get_camera_mut(camera_id)
+            .ok_or(RenderError::UnknownCamera(camera_id))
+    }
+
+    /** returns false if the camera does not exist, its controller is dropped */
+    pub fn remove_camera(&mut self, scene_id: SceneId, camera_id: CameraID) -> Result<bool, RenderError> {
+        self.camera_controllers.remove(&(scene_id, camera_id));
+
+        Ok(self.get_scene(scene_id)?.remove_camera(camera_id))
+    }
+
+    pub fn reposition_camera(&mut self, scene_id: SceneId, camera_id: CameraID, delta: Vector) -> Result<(), RenderError> {
+        self.camera_mut(scene_id, camera_id)?.reposition(delta);
+
+        Ok(())
+    }
+
+    pub fn rotate_camera(&mut self, scene_id: SceneId, camera_id: CameraID, delta: &Vector) -> Result<(), RenderError> {
+        self.camera_mut(scene_id, camera_id)?.rotate(delta);
+
+        Ok(())
+    }
+
+    pub fn move_camera_focal_length(&mut self, scene_id: SceneId, camera_id: CameraID, delta: f32) -> Result<(), RenderError> {
+        self.camera_mut(scene_id, camera_id)?.move_focal_length(delta);
+
+        Ok(())
+    }
+
+    /** None releases the camera, an orbit controller turns it to its pivot */
+    pub fn set_camera_controller(&mut self, scene_id: SceneId, camera_id: CameraID, controller: Option<CameraController>) -> Result<(), RenderError> {
+        match controller {
+            Some(controller) => {
+                if let CameraController::Orbit(orbit_controller) = controller {
+                    self.drive_camera(scene_id, camera_id, |camera: &mut Camera| orbit_controller.attach(camera))?;
+                } else {
+                    self.camera_mut(scene_id, camera_id)?;
+                }
+
+                self.camera_controllers.insert((scene_id, camera_id), controller);
+            }
+            None => {
+                self.camera_mut(scene_id, camera_id)?;
+                self.camera_controllers.remove(&(scene_id, camera_id));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_camera_controller(&self, scene_id: SceneId, camera_id: CameraID) -> Option<&CameraController> {
+        self.camera_controllers.get(&(scene_id, camera_id))
+    }
+
+    /** turns the camera to the target, the pivot of its orbit controller moves to the target */
+    pub fn camera_look_at(&mut self, scene_id: SceneId, camera_id: CameraID, target: &Point, up: &Vector) -> Result<(), RenderError> {
+        if !self.drive_camera(scene_id, camera_id, |camera: &mut Camera| camera.look_at(target, up))? {
+            return Err(RenderError::BadInput(String::from("target is at the camera center or up is parallel to the view direction")));
+        }
+
+        if let Some(CameraController::Orbit(orbit_controller)) = self.camera_controllers.get_mut(&(scene_id, camera_id)) {
+            orbit_controller.pivot = *target;
+        }
+
+        Ok(())
+    }
+
+    /** see OrbitController::orbit */
+    pub fn orbit_camera(&mut self, scene_id: SceneId, camera_id: CameraID, delta_yaw: f32, delta_pitch: f32) -> Result<(), RenderError> {
+        let orbit_controller: OrbitController = self.orbit_controller(scene_id, camera_id)?;
+
+        self.drive_camera(scene_id, camera_id, |camera: &mut Camera| orbit_controller.orbit(camera, delta_yaw, delta_pitch))
+    }
+
+    /** see OrbitController::pan */
+    pub fn pan_camera(&mut self, scene_id: SceneId, camera_id: CameraID, delta_x: f32, delta_y: f32) -> Result<(), RenderError> {
+        let mut orbit_controller: OrbitController = self.orbit_controller(scene_id, camera_id)?;
+        self.drive_camera(scene_id, camera_id, |camera: &mut Camera| orbit_controller.pan(camera, delta_x, delta_y))?;
+
+        self.camera_controllers.insert((scene_id, camera_id), CameraController::Orbit(orbit_controller));
+
+        Ok(())
+    }
+
+    /** see OrbitController::zoom */
+    pub fn zoom_camera(&mut self, scene_id: SceneId, camera_id: CameraID, factor: f32) -> Result<(), RenderError> {
+        check_positive("zoom factor", factor)?;
+
+        let orbit_controller: OrbitController = self.orbit_controller(scene_id, camera_id)?;
+
+        self.drive_camera(scene_id, camera_id, |camera: &mut Camera| orbit_controller.zoom(camera, factor))
+    }
+
+    /** see FlyController::look */
+    pub fn look_camera(&mut self, scene_id: SceneId, camera_id: CameraID, delta_yaw: f32, delta_pitch: f32) -> Result<(), RenderError> {
+        let fly_controller: FlyController = self.fly_controller(scene_id, camera_id)?;
+
+        self.drive_camera(scene_id, camera_id, |camera: &mut Camera| fly_controller.look(camera, delta_yaw, delta_pitch))
+    }
+
+    /** see FlyController::fly */
+    pub fn fly_camera(&mut self, scene_id: SceneId, camera_id: CameraID, delta: &Vector) -> Result<(), RenderError> {
+        let fly_controller: FlyController = self.fly_controller(scene_id, camera_id)?;
+
+        self.drive_camera(scene_id, camera_id, |camera: &mut Camera| fly_controller.fly(camera, delta))
+    }
+
+    fn orbit_controller(&self, scene_id: SceneId, camera_id: CameraID) -> Result<OrbitController, RenderError> {
+        match self.camera_controllers.get(&(scene_id, camera_id)) {
+            Some(CameraController::Orbit(orbit_controller)) => Ok(*orbit_controller),
+            _ => Err(RenderError::BadInput(format!("camera {} has no orbit controller", camera_id)))
+        }
+    }
+
+    fn fly_controller(&self, scene_id: SceneId, camera_id: CameraID) -> Result<FlyController, RenderError> {
+        match self.camera_controllers.get(&(scene_id, camera_id)) {
+            Some(CameraController::Fly(fly_controller)) => Ok(*fly_controller),
+            _ => Err(RenderError::BadInput(format!("camera {} has no fly controller", camera_id)))
+        }
+    }
+
+    /**
+     * Moves the camera in the world, where the controllers work.
+     * A camera attached to a node is placed back relative to the node, so it keeps following the node.
+     */
+    fn drive_camera<R>(&mut self, scene_id: SceneId, camera_id: CameraID, drive: impl FnOnce(&mut Camera) -> R) -> Result<R, RenderError> {
+        let scene: &mut Scene = self.get_scene(scene_id)?;
+
+        let node_matrix: Option<Mat4> = scene.get_camera_node(camera_id)
+            .and_then(|node_id: NodeID| scene.node_world_matrix(node_id));
+
+        let camera: &mut Camera = scene.get_camera_mut(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?;
+
+        let node_matrix: Mat4 = match node_matrix {
+            Some(node_matrix) => node_matrix,
+            None => return Ok(drive(camera))
+        };
+
+        let inverse_node_matrix: Mat4 = node_matrix
+            .inverse()
+            .ok_or_else(|| RenderError::BadInput(format!("camera {} is attached to a node scaled to zero", camera_id)))?;
+
+        let mut world_camera: Camera = camera.clone();
+        place_camera(&mut world_camera, &(node_matrix * camera.world_matrix()));
+
+        let result: R = drive(&mut world_camera);
+
+        let local_matrix: Mat4 = inverse_node_matrix * world_camera.world_matrix();
+        place_camera(&mut world_camera, &local_matrix);
+        *camera = world_camera;
+
+        Ok(result)
+    }
+
+    pub fn translate_mesh(&mut self, scene_id: SceneId, mesh_id: MeshID, delta: &Vector) -> Result<(), RenderError> {
+        self.get_scene(scene_id)?.translate_mesh(mesh_id, delta)
+    }
+
+    pub fn rotate_mesh(&mut self, scene_id: SceneId, mesh_id: MeshID, delta: &Vector) -> Result<(), RenderError> {
+        self.get_scene(scene_id)?.rotate_mesh(mesh_id, delta)
+    }
+
+    pub fn scale_mesh(&mut self, scene_id: SceneId, mesh_id: MeshID, factors: &Vector) -> Result<(), RenderError> {
+        self.get_scene(scene_id)?.scale_mesh(mesh_id, factors)
     }
 
-    pub fn render(&self, scene_id: SceneId, camera_id: CameraID, background_color: Color) -> Image {
-        let scene: &Scene = self.scenes.get(&scene_id).unwrap();
-        let camera: &Camera = scene.get_camera(camera_id).unwrap();
+    pub fn render(&self, scene_id: SceneId, camera_id: CameraID, background_color: Color) -> Result<Image, RenderError> {
+        let scene: &Scene = self.scene(scene_id)?;
+        let camera: &Camera = scene.get_camera(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?;
+        let view_matrix: Mat4 = scene.camera_view_matrix(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?;
+
+        Ok(render(camera, &view_matrix, &checked_meshes_in_world(scene)?, &scene.get_all_lights_in_world(), scene.get_all_textures(), background_color))
+    }
+
+    /**
+     * Renders into the buffer as 4 bytes per pixel, rows from top to bottom, returns the width and height of the image.
+     * The buffer is reused, so rendering every frame into the same one doesn't allocate.
+     */
+    pub fn render_rgba(&self, scene_id: SceneId, camera_id: CameraID, background_color: Color, buffer: &mut Vec<u8>) -> Result<(usize, usize), RenderError> {
+        let scene: &Scene = self.scene(scene_id)?;
+        let camera: &Camera = scene.get_camera(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?;
+        let view_matrix: Mat4 = scene.camera_view_matrix(camera_id).ok_or(RenderError::UnknownCamera(camera_id))?;
+
+        render_rgba(camera, &view_matrix, &checked_meshes_in_world(scene)?, &scene.get_all_lights_in_world(), scene.get_all_textures(), background_color, buffer);
+
+        Ok((camera.display().width, camera.display().height))
+    }
+
+    /** renders without a window, the image format is chosen by the file extension: png, ppm, rgba or raw */
+    pub fn render_to_file(&self, scene_id: SceneId, camera_id: CameraID, background_color: Color, path: &Path) -> Result<(), RenderError> {
+        write_image(&self.render(scene_id, camera_id, background_color)?, path)?;
+
+        Ok(())
+    }
+}
+
+/** meshes edited through Scene::get_mesh_mut may have become invalid, they fail the render instead of panicking in it */
+fn checked_meshes_in_world(scene: &Scene) -> Result<Vec<(&Mesh, Mat4)>, RenderError> {
+    let meshes: Vec<(&Mesh, Mat4)> = scene.get_all_meshes_in_world();
+
+    for (mesh, _) in &meshes {
+        mesh.check()?;
+    }
+
+    Ok(meshes)
+}
+
+/** moves the camera to the translation and rotation of the matrix, its scale is dropped */
+fn place_camera(camera: &mut Camera, matrix: &Mat4) {
+    let transform: Transform = Transform::from_matrix(matrix);
+    let Vector { x, y, z } = transform.translation;
+
+    camera.set_center(Point { x, y, z });
+    camera.set_orientation(transform.rotation);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::error::RenderError;
+    use crate::rendering_engine::scene::camera::controller::{CameraController, FlyController, OrbitController};
+    use crate::rendering_engine::scene::camera::display::Display;
+    use crate::rendering_engine::scene::camera::Camera;
+    use crate::rendering_engine::scene::model::color::Color;
+    use crate::rendering_engine::scene::model_3d::matrix::Mat4;
+    use crate::rendering_engine::scene::model_3d::point::Point;
+    use crate::rendering_engine::scene::model_3d::quaternion::Quaternion;
+    use crate::rendering_engine::scene::model_3d::transform::Transform;
+    use crate::rendering_engine::scene::model_3d::vector::Vector;
+    use crate::rendering_engine::scene::{CameraID, MeshID, NodeID, Scene};
+    use crate::rendering_engine::{RenderingEngine, SceneId};
+
+    const ORIGIN: Point = Point { x: 0.0, y: 0.0, z: 0.0 };
+
+    fn create_camera(engine: &mut RenderingEngine, scene_id: SceneId) -> CameraID {
+        engine.get_scene(scene_id).unwrap().add_camera(Camera::new(100.0, ORIGIN, 0.0, 0.0, 0.0, Display::new(10, 10)))
+    }
+
+    fn assert_close(actual: &Vector, expected: &Vector) {
+        assert!((*actual - *expected).length() < 1e-3, "{:?} is not {:?}", actual, expected);
+    }
+
+    /** center and view direction of the camera in the world */
+    fn world_placement(engine: &mut RenderingEngine, scene_id: SceneId, camera_id: CameraID) -> (Vector, Vector) {
+        let world_matrix: Mat4 = engine.get_scene(scene_id).unwrap().camera_view_matrix(camera_id).unwrap().inverse().unwrap();
+
+        (world_matrix.transform_point(&ORIGIN).to_vector(), world_matrix.transform_vector(&Vector::new(0.0, 0.0, 1.0)))
+    }
+
+    #[test]
+    fn controllers_move_cameras_attached_to_nodes_in_the_world() {
+        let mut engine: RenderingEngine = RenderingEngine::new();
+        let scene_id: SceneId = engine.create_scene();
+        let camera_id: CameraID = create_camera(&mut engine, scene_id);
+
+        let transform: Transform = Transform {
+            translation: Vector::new(0.0, 0.0, -10.0),
+            rotation: Quaternion::from_euler(0.0, 90.0, 0.0),
+            scale: Vector::new(2.0, 2.0, 2.0),
+        };
+        let node_id: NodeID = engine.get_scene(scene_id).unwrap().add_node(None, transform).unwrap();
+        engine.get_scene(scene_id).unwrap().attach_camera(camera_id, Some(node_id));
+
+        /* the camera stays where the node put it and turns to the pivot in the world */
+        engine.set_camera_controller(scene_id, camera_id, Some(CameraController::Orbit(OrbitController::new(ORIGIN)))).unwrap();
+
+        let (center, forward): (Vector, Vector) = world_placement(&mut engine, scene_id, camera_id);
+        assert_close(&center, &Vector::new(0.0, 0.0, -10.0));
+        assert_close(&forward.normalize(), &Vector::new(0.0, 0.0, 1.0));
+
+        engine.orbit_camera(scene_id, camera_id, 180.0, 0.0).unwrap();
+
+        let (center, forward): (Vector, Vector) = world_placement(&mut engine, scene_id, camera_id);
+        assert_close(&center, &Vector::new(0.0, 0.0, 10.0));
+        assert_close(&forward.normalize(), &Vector::new(0.0, 0.0, -1.0));
+
+        /* the camera is still attached and follows the node */
+        engine.get_scene(scene_id).unwrap().get_node_mut(node_id).unwrap().transform.translate(&Vector::new(5.0, 0.0, 0.0));
+
+        let (center, _): (Vector, Vector) = world_placement(&mut engine, scene_id, camera_id);
+        assert_close(&center, &Vector::new(5.0, 0.0, 10.0));
+    }
+
+    #[test]
+    fn controllers_are_dropped_with_their_camera_or_scene() {
+        let mut engine: RenderingEngine = RenderingEngine::new();
+        let scene_id: SceneId = engine.create_scene();
+        let other_scene_id: SceneId = engine.create_scene();
+        let camera_id: CameraID = create_camera(&mut engine, scene_id);
+        let other_camera_id: CameraID = create_camera(&mut engine, other_scene_id);
+
+        engine.set_camera_controller(scene_id, camera_id, Some(CameraController::Fly(FlyController))).unwrap();
+        engine.set_camera_controller(other_scene_id, other_camera_id, Some(CameraController::Fly(FlyController))).unwrap();
+
+        /* controllers are per scene, the camera id alone does not find one */
+        assert!(engine.get_camera_controller(other_scene_id, camera_id).is_none());
+
+        assert!(engine.remove_camera(scene_id, camera_id).unwrap());
+        assert!(!engine.remove_camera(scene_id, camera_id).unwrap());
+        assert!(engine.get_camera_controller(scene_id, camera_id).is_none());
+        assert!(engine.look_camera(scene_id, camera_id, 10.0, 0.0).is_err());
+
+        assert!(engine.remove_scene(other_scene_id));
+        assert!(!engine.remove_scene(other_scene_id));
+        assert!(engine.get_camera_controller(other_scene_id, other_camera_id).is_none());
+        assert!(engine.remove_camera(other_scene_id, other_camera_id).is_err());
+    }
+
+    #[test]
+    fn meshes_broken_after_adding_fail_the_render() {
+        let mut engine: RenderingEngine = RenderingEngine::new();
+        let scene_id: SceneId = engine.create_scene();
+        let camera_id: CameraID = create_camera(&mut engine, scene_id);
+
+        let scene: &mut Scene = engine.get_scene(scene_id).unwrap();
+        let mesh_id: MeshID = scene.add_cube(Point { x: 0.0, y: 0.0, z: 5.0 }, 1.0, 1.0, 1.0, Color::new(255, 0, 0, 1.0)).unwrap();
+        scene.get_mesh_mut(mesh_id).unwrap().points.truncate(4);
 
-        render(camera, scene.get_all_meshes(), background_color)
+        assert!(matches!(engine.render(scene_id, camera_id, Color::new(0, 0, 0, 1.0)), Err(RenderError::InvalidGeometry(_))));
     }
 }
@@ -1,6 +1,9 @@
 pub mod projector;
+pub mod clipper;
 pub mod renderer;
 pub mod rasterizer;
+pub mod shader;
 pub mod compositor;
+pub mod framebuffer;
 mod color_blender;
-pub(crate) mod model;
\ No newline at end of file
+pub mod model;
\ No newline at end of file
@@ -0,0 +1,211 @@
+use crate::rendering_engine::scene::camera::{Camera, Projection};
+use crate::rendering_engine::scene::model_3d::plane::Plane;
+use crate::rendering_engine::scene::model_3d::point::Point;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+/**
+ * Planes of the camera frustum in the camera space (x right, y up, z forward), a box for orthographic cameras.
+ * Normals point inside the frustum, so a point is visible when it is not behind any of the planes.
+ */
+pub fn create_clipping_planes(camera: &Camera) -> Vec<Plane> {
+    let half_width: f32 = camera.display().width as f32 / 2.0;
+    let half_height: f32 = camera.display().height as f32 / 2.0;
+
+    let mut planes: Vec<Plane> = vec![
+        /* near */
+        Plane::new(Vector::new(0.0, 0.0, 1.0), Point { x: 0.0, y: 0.0, z: camera.near() }),
+        /* far */
+        Plane::new(Vector::new(0.0, 0.0, -1.0), Point { x: 0.0, y: 0.0, z: camera.far() }),
+    ];
+
+    match camera.projection() {
+        Projection::Perspective => {
+            let focal_length: f32 = camera.focal_length();
+            let origin: Point = Point { x: 0.0, y: 0.0, z: 0.0 };
+
+            planes.extend([
+                /* left, x >= -z * half_width / focal_length */
+                Plane::new(Vector::new(focal_length, 0.0, half_width), origin),
+                /* right, x <= z * half_width / focal_length */
+                Plane::new(Vector::new(-focal_length, 0.0, half_width), origin),
+                /* bottom */
+                Plane::new(Vector::new(0.0, focal_length, half_height), origin),
+                /* top */
+                Plane::new(Vector::new(0.0, -focal_length, half_height), origin),
+            ]);
+        }
+        Projection::Orthographic { scale } => {
+            let half_width: f32 = half_width * scale;
+            let half_height: f32 = half_height * scale;
+
+            planes.extend([
+                /* left, x >= -half_width */
+                Plane::new(Vector::new(1.0, 0.0, 0.0), Point { x: -half_width, y: 0.0, z: 0.0 }),
+                /* right */
+                Plane::new(Vector::new(-1.0, 0.0, 0.0), Point { x: half_width, y: 0.0, z: 0.0 }),
+                /* bottom */
+                Plane::new(Vector::new(0.0, 1.0, 0.0), Point { x: 0.0, y: -half_height, z: 0.0 }),
+                /* top */
+                Plane::new(Vector::new(0.0, -1.0, 0.0), Point { x: 0.0, y: half_height, z: 0.0 }),
+            ]);
+        }
+    }
+
+    planes
+}
+
+/**
+ * Sutherland–Hodgman clipping of a convex polygon against every plane.
+ * Returns vertices of the visible part of the polygon in the same winding order, or nothing if it is not visible.
+ */
+pub fn clip(polygon: &[Point], clipping_planes: &[Plane]) -> Vec<Point> {
+    let mut polygon: Vec<Point> = polygon.to_vec();
+
+    for clipping_plane in clipping_planes {
+        if polygon.is_empty() {
+            break;
+        }
+
+        polygon = clip_by_plane(&polygon, clipping_plane);
+    }
+
+    polygon
+}
+
+/** splits a convex polygon into triangles sharing its first vertex */
+pub fn triangulate(polygon: &[Point]) -> Vec<[Point; 3]> {
+    if polygon.len() < 3 {
+        return vec![];
+    }
+
+    (1..polygon.len() - 1)
+        .map(|index: usize| [polygon[0], polygon[index], polygon[index + 1]])
+        .collect()
+}
+
+fn clip_by_plane(polygon: &[Point], clipping_plane: &Plane) -> Vec<Point> {
+    let mut clipped_polygon: Vec<Point> = Vec::with_capacity(polygon.len() + 1);
+
+    for (index, current) in polygon.iter().enumerate() {
+        let next: &Point = &polygon[(index + 1) % polygon.len()];
+
+        let current_distance: f32 = current.distance_from_plane(clipping_plane);
+        let next_distance: f32 = next.distance_from_plane(clipping_plane);
+
+        if current_distance >= 0.0 {
+            clipped_polygon.push(*current);
+        }
+
+        /* edge crosses the plane */
+        if (current_distance >= 0.0) != (next_distance >= 0.0) {
+            let t: f32 = current_distance / (current_distance - next_distance);
+
+            clipped_polygon.push(Point {
+                x: current.x + (next.x - current.x) * t,
+                y: current.y + (next.y - current.y) * t,
+                z: current.z + (next.z - current.z) * t,
+            });
+        }
+    }
+
+    clipped_polygon
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::engine::clipper::{clip, create_clipping_planes, triangulate};
+    use crate::rendering_engine::scene::camera::display::Display;
+    use crate::rendering_engine::scene::camera::{Camera, Projection};
+    use crate::rendering_engine::scene::model_3d::plane::Plane;
+    use crate::rendering_engine::scene::model_3d::point::Point;
+
+    /** camera at the origin looking along z, the frustum spans 45 degrees to every side */
+    fn create_camera(near: f32) -> Camera {
+        let mut camera: Camera = Camera::new(100.0, Point { x: 0.0, y: 0.0, z: 0.0 }, 0.0, 0.0, 0.0, Display::new(200, 200));
+        camera.set_clipping_distances(near, 1000.0);
+
+        camera
+    }
+
+    fn point(x: f32, y: f32, z: f32) -> Point {
+        Point { x, y, z }
+    }
+
+    fn assert_points_eq(actual: &[Point], expected: &[Point]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(
+                (actual.x - expected.x).abs() < 1e-3 && (actual.y - expected.y).abs() < 1e-3 && (actual.z - expected.z).abs() < 1e-3,
+                "{:?} != {:?}", actual, expected
+            );
+        }
+    }
+
+    #[test]
+    fn triangle_inside_is_kept() {
+        let planes: Vec<Plane> = create_clipping_planes(&create_camera(1.0));
+        let triangle: [Point; 3] = [point(0.0, 0.0, 10.0), point(1.0, 0.0, 10.0), point(0.0, 1.0, 10.0)];
+
+        let polygon: Vec<Point> = clip(&triangle, &planes);
+
+        assert_points_eq(&polygon, &triangle);
+        assert_eq!(triangulate(&polygon).len(), 1);
+    }
+
+    #[test]
+    fn triangle_outside_is_removed() {
+        let planes: Vec<Plane> = create_clipping_planes(&create_camera(1.0));
+
+        /* behind the camera */
+        assert!(clip(&[point(0.0, 0.0, -10.0), point(1.0, 0.0, -10.0), point(0.0, 1.0, -10.0)], &planes).is_empty());
+        /* in front of the camera, but left of the frustum */
+        assert!(clip(&[point(-20.0, 0.0, 10.0), point(-30.0, 0.0, 10.0), point(-20.0, 1.0, 10.0)], &planes).is_empty());
+        /* beyond the far plane */
+        assert!(clip(&[point(0.0, 0.0, 2000.0), point(1.0, 0.0, 2000.0), point(0.0, 1.0, 2000.0)], &planes).is_empty());
+
+        assert!(triangulate(&[]).is_empty());
+    }
+
+    #[test]
+    fn triangle_crossing_near_plane_becomes_quad() {
+        let planes: Vec<Plane> = create_clipping_planes(&create_camera(5.0));
+        /* the first vertex is in front of the near plane */
+        let triangle: [Point; 3] = [point(0.0, 0.0, 3.0), point(1.0, 0.0, 7.0), point(0.0, 1.0, 7.0)];
+
+        let polygon: Vec<Point> = clip(&triangle, &planes);
+
+        assert_points_eq(&polygon, &[point(0.5, 0.0, 5.0), point(1.0, 0.0, 7.0), point(0.0, 1.0, 7.0), point(0.0, 0.5, 5.0)]);
+
+        let triangles: Vec<[Point; 3]> = triangulate(&polygon);
+        assert_eq!(triangles.len(), 2);
+        assert_points_eq(&triangles[0], &[polygon[0], polygon[1], polygon[2]]);
+        assert_points_eq(&triangles[1], &[polygon[0], polygon[2], polygon[3]]);
+    }
+
+    #[test]
+    fn triangle_crossing_two_planes_is_retriangulated() {
+        let mut camera: Camera = create_camera(1.0);
+        camera.set_projection(Projection::Orthographic { scale: 1.0 });
+        /* the box spans -100 to 100 in x and y */
+        let planes: Vec<Plane> = create_clipping_planes(&camera);
+
+        /* the second vertex is right of the box and the third one above it */
+        let triangle: [Point; 3] = [point(0.0, 0.0, 10.0), point(200.0, 0.0, 10.0), point(0.0, 150.0, 10.0)];
+
+        let polygon: Vec<Point> = clip(&triangle, &planes);
+
+        assert_points_eq(&polygon, &[
+            point(0.0, 0.0, 10.0),
+            point(100.0, 0.0, 10.0),
+            point(100.0, 75.0, 10.0),
+            point(200.0 / 3.0, 100.0, 10.0),
+            point(0.0, 100.0, 10.0),
+        ]);
+
+        let triangles: Vec<[Point; 3]> = triangulate(&polygon);
+        assert_eq!(triangles.len(), 3);
+        assert!(triangles.iter().all(|triangle: &[Point; 3]| triangle[0] == polygon[0]));
+        assert_points_eq(&triangles[2], &[polygon[0], polygon[3], polygon[4]]);
+    }
+}
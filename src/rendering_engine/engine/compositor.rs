@@ -1,78 +1,99 @@
+use crate::rendering_engine::engine::framebuffer::Framebuffer;
 use crate::rendering_engine::engine::model::depth_pixel::DepthPixel;
+use crate::rendering_engine::engine::model::fragment::Fragment;
 use crate::rendering_engine::engine::model::pixel::Pixel;
-use crate::rendering_engine::engine::rasterizer::ZBuffer;
 use crate::rendering_engine::scene::camera::display::Display;
 use crate::rendering_engine::scene::model::color::Color;
 
-pub type Image = Vec<Vec<Pixel>>;
-
-pub fn composite(z_buffers: &Vec<ZBuffer>, display: &Display, background_color: Color) -> Image {
-    let default_pixel: Pixel = Pixel::new(
-        background_color.r,
-        background_color.g,
-        background_color.b
-    );
-
-    let mut image: Image = vec![
-        vec![default_pixel; display.width];
-        display.height
-    ];
-
-    for (row, row_pixels) in image.iter_mut().enumerate() {
-        for (col, pixel) in row_pixels.iter_mut().enumerate() {
-            let composed_pixel: Pixel = blend_pixel(row, col, &z_buffers, background_color);
-            pixel.r = composed_pixel.r;
-            pixel.g = composed_pixel.g;
-            pixel.b = composed_pixel.b;
-        }
-    }
-
-    image
+pub type Image = Framebuffer<Pixel>;
+
+/**
+ * Screen sized color and depth target.
+ * Opaque fragments are depth tested and written immediately,
+ * translucent ones are kept until `compose`, sorted back to front and blended over the opaque result.
+ */
+pub struct Compositor {
+    image: Image,
+    depth_buffer: Framebuffer<f32>,
+    translucent_fragments: Vec<Fragment>,
 }
 
-fn blend_pixel(row: usize, col: usize, z_buffers: &Vec<ZBuffer>, background_color: Color) -> Pixel {
-    let mut blended_pixel: Pixel = Pixel::new(
-        background_color.r,
-        background_color.g,
-        background_color.b,
-    );
+impl Compositor {
+    pub fn new(display: &Display, background_color: Color) -> Compositor {
+        let background_pixel: Pixel = Pixel::new(
+            background_color.r,
+            background_color.g,
+            background_color.b
+        );
 
-    let mut pixel_buffer: Vec<&DepthPixel> = Vec::with_capacity(z_buffers.len());
+        Compositor {
+            image: Framebuffer::new(display.width, display.height, background_pixel),
+            depth_buffer: Framebuffer::new(display.width, display.height, f32::INFINITY),
+            translucent_fragments: Vec::new(),
+        }
+    }
 
-    for z_buffer in z_buffers {
-        let row: isize = row as isize - z_buffer.y;
-        let col: isize = col as isize - z_buffer.x;
+    pub fn write_opaque(&mut self, col: usize, row: usize, depth: f32, color: Color) {
+        let nearest_depth: &mut f32 = self.depth_buffer.get_mut(col, row).unwrap();
 
-        if row < 0 || row >= z_buffer.buffer.len() as isize ||
-            col < 0 || col >= z_buffer.buffer[0].len() as isize {
-            continue;
+        if depth >= *nearest_depth {
+            return;
         }
 
-        let depth_pixel = z_buffer.buffer
-            .get(row as usize)
-            .map(|row_pixels| row_pixels.get(col as usize));
+        *nearest_depth = depth;
+        *self.image.get_mut(col, row).unwrap() = Pixel::new(color.r, color.g, color.b);
+    }
 
-        if let Some(Some(depth_pixel)) = depth_pixel {
-            pixel_buffer.push(&depth_pixel);
+    /** should be called after all opaque fragments are written, so hidden fragments are dropped early */
+    pub fn write_translucent(&mut self, col: usize, row: usize, depth: f32, color: Color) {
+        if depth >= *self.depth_buffer.get(col, row).unwrap() {
+            return;
         }
-    }
 
-    pixel_buffer
-        .sort_by(|left: &&DepthPixel, right: &&DepthPixel| {
-            right.depth.total_cmp(&left.depth)
+        self.translucent_fragments.push(Fragment {
+            col,
+            row,
+            depth_pixel: DepthPixel { color, depth }
         });
+    }
+
+    pub fn compose(mut self) -> Image {
+        self.blend_translucent();
 
-    pixel_buffer.iter().for_each(|depth_pixel: &&DepthPixel| {
-        let mut depth_pixel_color: Color = depth_pixel.color.clone();
+        self.image
+    }
 
-        let alpha: f32 = depth_pixel_color.a;
+    /**
+     * Writes the image into the buffer as 4 bytes per pixel with alpha 255.
+     * The buffer is cleared first, so it can be reused between frames without reallocating.
+     */
+    pub fn compose_rgba(mut self, buffer: &mut Vec<u8>) {
+        self.blend_translucent();
 
-        blended_pixel = Pixel::new(
-            ((1.0 - alpha) * blended_pixel.r as f32 + alpha * depth_pixel_color.r as f32) as u8,
-            ((1.0 - alpha) * blended_pixel.g as f32 + alpha * depth_pixel_color.g as f32) as u8,
-            ((1.0 - alpha) * blended_pixel.b as f32 + alpha * depth_pixel_color.b as f32) as u8,
-        );
-    });
+        self.image.write_rgba8(buffer);
+    }
+
+    fn blend_translucent(&mut self) {
+        /* stable sort keeps drawing order of fragments with equal depth */
+        self.translucent_fragments
+            .sort_by(|left: &Fragment, right: &Fragment| {
+                right.depth_pixel.depth.total_cmp(&left.depth_pixel.depth)
+            });
+
+        for fragment in &self.translucent_fragments {
+            let pixel: &mut Pixel = self.image.get_mut(fragment.col, fragment.row).unwrap();
+
+            *pixel = blend_pixel(*pixel, fragment.depth_pixel.color);
+        }
+    }
+}
+
+fn blend_pixel(background: Pixel, foreground: Color) -> Pixel {
+    let alpha: f32 = foreground.a;
 
-    blended_pixel
+    Pixel::new(
+        ((1.0 - alpha) * background.r as f32 + alpha * foreground.r as f32) as u8,
+        ((1.0 - alpha) * background.g as f32 + alpha * foreground.g as f32) as u8,
+        ((1.0 - alpha) * background.b as f32 + alpha * foreground.b as f32) as u8,
+    )
 }
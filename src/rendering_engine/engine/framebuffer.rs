@@ -0,0 +1,267 @@
+use serde::{Serialize, Serializer};
+
+use crate::rendering_engine::engine::model::pixel::Pixel;
+
+/**
+ * Width x height grid stored in one contiguous buffer, rows from top to bottom.
+ * Row i starts at i * stride, so views into a part of the buffer share its stride.
+ */
+#[derive(Clone, PartialEq)]
+pub struct Framebuffer<T> {
+    width: usize,
+    height: usize,
+    stride: usize,
+    data: Vec<T>,
+}
+
+/** rectangle of a framebuffer, borrowed from it */
+#[derive(Copy, Clone)]
+pub struct FramebufferView<'a, T> {
+    width: usize,
+    height: usize,
+    stride: usize,
+    /** starts with the first value of the view */
+    data: &'a [T],
+}
+
+impl<T: Clone> Framebuffer<T> {
+    /** every value is set to `value` */
+    pub fn new(width: usize, height: usize, value: T) -> Framebuffer<T> {
+        Framebuffer {
+            width,
+            height,
+            stride: width,
+            data: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Framebuffer<T> {
+    /** returns None if data does not hold exactly width x height values */
+    pub fn from_data(width: usize, height: usize, data: Vec<T>) -> Option<Framebuffer<T>> {
+        if data.len() != width * height {
+            return None;
+        }
+
+        Some(Framebuffer {
+            width,
+            height,
+            stride: width,
+            data,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn get(&self, col: usize, row: usize) -> Option<&T> {
+        if col >= self.width || row >= self.height {
+            return None;
+        }
+
+        self.data.get(row * self.stride + col)
+    }
+
+    pub fn get_mut(&mut self, col: usize, row: usize) -> Option<&mut T> {
+        if col >= self.width || row >= self.height {
+            return None;
+        }
+
+        self.data.get_mut(row * self.stride + col)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.view().rows()
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let width: usize = self.width;
+
+        self.data
+            .chunks_mut(self.stride.max(1))
+            .take(self.height)
+            .map(move |row: &mut [T]| &mut row[..width])
+    }
+
+    /** the whole framebuffer */
+    pub fn view(&self) -> FramebufferView<'_, T> {
+        FramebufferView {
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+            data: &self.data,
+        }
+    }
+
+    /** returns None if the rectangle does not fit into the framebuffer */
+    pub fn sub_view(&self, col: usize, row: usize, width: usize, height: usize) -> Option<FramebufferView<'_, T>> {
+        self.view().sub_view(col, row, width, height)
+    }
+}
+
+impl<'a, T> FramebufferView<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /** number of values between the starts of two consecutive rows */
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn get(&self, col: usize, row: usize) -> Option<&'a T> {
+        if col >= self.width || row >= self.height {
+            return None;
+        }
+
+        self.data.get(row * self.stride + col)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let (width, height, stride, data): (usize, usize, usize, &'a [T]) = (self.width, self.height, self.stride, self.data);
+
+        (0..height).map(move |row: usize| &data[row * stride..row * stride + width])
+    }
+
+    /** the position is relative to this view, returns None if the rectangle does not fit into it */
+    pub fn sub_view(&self, col: usize, row: usize, width: usize, height: usize) -> Option<FramebufferView<'a, T>> {
+        if col + width > self.width || row + height > self.height {
+            return None;
+        }
+
+        if width == 0 || height == 0 {
+            return Some(FramebufferView { width, height, stride: self.stride, data: &[] });
+        }
+
+        let start: usize = row * self.stride + col;
+        /* the last row may end before the stride does */
+        let end: usize = start + (height - 1) * self.stride + width;
+
+        Some(FramebufferView {
+            width,
+            height,
+            stride: self.stride,
+            data: &self.data[start..end],
+        })
+    }
+}
+
+impl FramebufferView<'_, Pixel> {
+    /** 4 bytes per pixel with alpha 255, rows without padding */
+    pub fn to_rgba8(self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(self.width * self.height * 4);
+        self.write_rgba8(&mut bytes);
+
+        bytes
+    }
+
+    /** 3 bytes per pixel, rows without padding */
+    pub fn to_rgb8(self) -> Vec<u8> {
+        self.rows()
+            .flatten()
+            .flat_map(|pixel: &Pixel| [pixel.r, pixel.g, pixel.b])
+            .collect()
+    }
+
+    /** like to_rgba8, the buffer is cleared first, so it can be reused between frames without reallocating */
+    pub fn write_rgba8(self, buffer: &mut Vec<u8>) {
+        buffer.clear();
+        buffer.reserve(self.width * self.height * 4);
+        buffer.extend(self.rows().flatten().flat_map(|pixel: &Pixel| [pixel.r, pixel.g, pixel.b, 255]));
+    }
+}
+
+/** rendered images are opaque, alpha of every pixel is 255 */
+impl Framebuffer<Pixel> {
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.view().to_rgba8()
+    }
+
+    pub fn to_rgb8(&self) -> Vec<u8> {
+        self.view().to_rgb8()
+    }
+
+    pub fn write_rgba8(&self, buffer: &mut Vec<u8>) {
+        self.view().write_rgba8(buffer);
+    }
+}
+
+/** serialized as an array of rows */
+impl<T: Serialize> Serialize for Framebuffer<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.rows())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::engine::framebuffer::{Framebuffer, FramebufferView};
+    use crate::rendering_engine::engine::model::pixel::Pixel;
+
+    /** value of every cell is its index, 4 x 3 */
+    fn create_framebuffer() -> Framebuffer<usize> {
+        Framebuffer::from_data(4, 3, (0..12).collect()).unwrap()
+    }
+
+    #[test]
+    fn rows_and_cells() {
+        let mut framebuffer: Framebuffer<usize> = create_framebuffer();
+
+        let rows: Vec<&[usize]> = framebuffer.rows().collect();
+        assert_eq!(rows, vec![&[0, 1, 2, 3][..], &[4, 5, 6, 7][..], &[8, 9, 10, 11][..]]);
+
+        assert_eq!(framebuffer.get(3, 2), Some(&11));
+        assert_eq!(framebuffer.get(4, 0), None);
+
+        *framebuffer.get_mut(1, 1).unwrap() = 50;
+        framebuffer.rows_mut().last().unwrap()[0] = 80;
+        assert_eq!(framebuffer.as_slice()[5], 50);
+        assert_eq!(framebuffer.as_slice()[8], 80);
+
+        assert!(Framebuffer::from_data(4, 3, vec![0; 11]).is_none());
+    }
+
+    #[test]
+    fn sub_views() {
+        let framebuffer: Framebuffer<usize> = create_framebuffer();
+
+        let view: FramebufferView<usize> = framebuffer.sub_view(1, 1, 3, 2).unwrap();
+        assert_eq!(view.stride(), 4);
+        assert_eq!(view.rows().collect::<Vec<&[usize]>>(), vec![&[5, 6, 7][..], &[9, 10, 11][..]]);
+
+        let nested: FramebufferView<usize> = view.sub_view(1, 1, 2, 1).unwrap();
+        assert_eq!(nested.get(1, 0), Some(&11));
+        assert_eq!(nested.get(0, 1), None);
+
+        assert!(framebuffer.sub_view(2, 0, 3, 1).is_none());
+        assert!(view.sub_view(0, 0, 3, 3).is_none());
+        assert_eq!(framebuffer.sub_view(4, 3, 0, 0).unwrap().rows().count(), 0);
+    }
+
+    #[test]
+    fn byte_conversions() {
+        let mut framebuffer: Framebuffer<Pixel> = Framebuffer::new(2, 2, Pixel::new(1, 2, 3));
+        *framebuffer.get_mut(1, 1).unwrap() = Pixel::new(7, 8, 9);
+
+        assert_eq!(framebuffer.to_rgb8(), vec![1, 2, 3, 1, 2, 3, 1, 2, 3, 7, 8, 9]);
+        assert_eq!(framebuffer.sub_view(1, 1, 1, 1).unwrap().to_rgba8(), vec![7, 8, 9, 255]);
+
+        let mut buffer: Vec<u8> = vec![0; 100];
+        framebuffer.write_rgba8(&mut buffer);
+        assert_eq!(buffer.len(), 16);
+        assert_eq!(&buffer[12..], &[7, 8, 9, 255]);
+    }
+}
@@ -1,3 +1,5 @@
 pub mod pixel;
 pub mod z_buffered_triangle;
-pub mod depth_pixel;
\ No newline at end of file
+pub mod depth_pixel;
+pub mod fragment;
+pub mod barycentric;
\ No newline at end of file
@@ -0,0 +1,132 @@
+use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
+use crate::rendering_engine::scene::model_3d::point::Point as Point3D;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+/** value which can be interpolated across a triangle from the values of its three vertices */
+pub trait Interpolate: Copy {
+    fn interpolate(values: &[Self; 3], weights: &[f32; 3]) -> Self;
+}
+
+/** position of a pixel inside the rasterized triangle */
+#[derive(Debug, Copy, Clone)]
+pub struct Barycentric {
+    /** perspective correct weights of the triangle vertices, they sum up to 1 */
+    pub weights: [f32; 3],
+    /** distance from the camera XY plane */
+    pub depth: f32,
+}
+
+impl Barycentric {
+    /**
+     * `screen_weights` are barycentric coordinates of the pixel in the screen space.
+     * Attributes are linear in the camera space, so the screen weights are divided by the homogeneous w of the vertices
+     * and normalized back (1 / w interpolation). Orthographic projections have w = 1, so their weights stay as they are.
+     * `distances` are the distances of the vertices from the camera XY plane, interpolated into the depth.
+     */
+    pub fn perspective_correct(screen_weights: [f32; 3], ws: [f32; 3], distances: [f32; 3]) -> Barycentric {
+        let inverse_ws: [f32; 3] = [
+            screen_weights[0] / ws[0],
+            screen_weights[1] / ws[1],
+            screen_weights[2] / ws[2],
+        ];
+
+        let sum: f32 = inverse_ws.iter().sum();
+
+        let weights: [f32; 3] = if sum == 0.0 || !sum.is_finite() {
+            screen_weights
+        } else {
+            inverse_ws.map(|inverse_w: f32| inverse_w / sum)
+        };
+
+        Barycentric {
+            weights,
+            depth: f32::interpolate(&distances, &weights)
+        }
+    }
+
+    pub fn interpolate<T: Interpolate>(&self, values: &[T; 3]) -> T {
+        T::interpolate(values, &self.weights)
+    }
+}
+
+impl Interpolate for f32 {
+    fn interpolate(values: &[Self; 3], weights: &[f32; 3]) -> Self {
+        values[0] * weights[0] + values[1] * weights[1] + values[2] * weights[2]
+    }
+}
+
+impl Interpolate for Color {
+    fn interpolate(values: &[Self; 3], weights: &[f32; 3]) -> Self {
+        let channel = |channel: fn(&Color) -> f32| -> f32 {
+            f32::interpolate(&values.map(|color: Color| channel(&color)), weights)
+        };
+
+        Color::new(
+            channel(|color: &Color| color.r as f32).round().clamp(0.0, 255.0) as u8,
+            channel(|color: &Color| color.g as f32).round().clamp(0.0, 255.0) as u8,
+            channel(|color: &Color| color.b as f32).round().clamp(0.0, 255.0) as u8,
+            channel(|color: &Color| color.a).clamp(0.0, 1.0),
+        )
+    }
+}
+
+impl Interpolate for Vector {
+    fn interpolate(values: &[Self; 3], weights: &[f32; 3]) -> Self {
+        Vector {
+            x: f32::interpolate(&values.map(|vector: Vector| vector.x), weights),
+            y: f32::interpolate(&values.map(|vector: Vector| vector.y), weights),
+            z: f32::interpolate(&values.map(|vector: Vector| vector.z), weights),
+        }
+    }
+}
+
+impl Interpolate for Point3D {
+    fn interpolate(values: &[Self; 3], weights: &[f32; 3]) -> Self {
+        Point3D {
+            x: f32::interpolate(&values.map(|point: Point3D| point.x), weights),
+            y: f32::interpolate(&values.map(|point: Point3D| point.y), weights),
+            z: f32::interpolate(&values.map(|point: Point3D| point.z), weights),
+        }
+    }
+}
+
+/** barycentric coordinates */
+impl Interpolate for [f32; 3] {
+    fn interpolate(values: &[Self; 3], weights: &[f32; 3]) -> Self {
+        [0, 1, 2].map(|i: usize| f32::interpolate(&values.map(|value: [f32; 3]| value[i]), weights))
+    }
+}
+
+impl Interpolate for Point2D {
+    fn interpolate(values: &[Self; 3], weights: &[f32; 3]) -> Self {
+        Point2D {
+            x: f32::interpolate(&values.map(|point: Point2D| point.x), weights),
+            y: f32::interpolate(&values.map(|point: Point2D| point.y), weights),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::engine::model::barycentric::Barycentric;
+
+    #[test]
+    fn interpolation_is_perspective_correct() {
+        /* halfway on the screen between a vertex at the distance 1 and one at the distance 3, perspective w is the distance */
+        let barycentric: Barycentric = Barycentric::perspective_correct([0.5, 0.5, 0.0], [1.0, 3.0, 1.0], [1.0, 3.0, 1.0]);
+
+        /* attributes are interpolated as (a / w) / (1 / w): (0.5 * 0 / 1 + 0.5 * 1 / 3) / (0.5 / 1 + 0.5 / 3) = 0.25 */
+        let attribute: f32 = barycentric.interpolate(&[0.0, 1.0, 0.0]);
+        assert!((attribute - 0.25).abs() < 1e-6, "{} is not 0.25, linear interpolation gives 0.5", attribute);
+
+        /* the point on the edge seen at the screen midpoint is at the distance 1 / (0.5 / 1 + 0.5 / 3) = 1.5 */
+        assert!((barycentric.depth - 1.5).abs() < 1e-6);
+        assert!((barycentric.weights.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+
+        /* equal ws, like the ones of orthographic projections, keep the screen weights */
+        let orthographic: Barycentric = Barycentric::perspective_correct([0.5, 0.5, 0.0], [1.0; 3], [1.0, 3.0, 1.0]);
+        assert_eq!(orthographic.weights, [0.5, 0.5, 0.0]);
+        assert_eq!(orthographic.depth, 2.0);
+    }
+}
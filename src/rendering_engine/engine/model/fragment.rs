@@ -0,0 +1,8 @@
+use crate::rendering_engine::engine::model::depth_pixel::DepthPixel;
+
+#[derive(Copy, Clone)]
+pub struct Fragment {
+    pub col: usize,
+    pub row: usize,
+    pub depth_pixel: DepthPixel,
+}
@@ -1,15 +1,19 @@
-use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::engine::shader::TriangleShader;
 
 #[derive(Debug, Clone, Copy)]
 pub struct ZBufferedVertex {
     pub x: f32,
     pub y: f32,
     pub distance: f32,
+    /** homogeneous w, see VertexDepth */
+    pub w: f32,
+    /** barycentric coordinates in the triangle before clipping, see SourceWeights */
+    pub weights: [f32; 3],
 }
 
 pub type ZBufferedVertices = [ZBufferedVertex; 3];
 
-pub struct ZBufferedTriangle {
+pub struct ZBufferedTriangle<'a> {
     pub vertices: ZBufferedVertices,
-    pub color: Color,
+    pub shader: TriangleShader<'a>,
 }
@@ -1,48 +1,54 @@
-use std::collections::HashMap;
-
+use crate::rendering_engine::engine::clipper::{clip, triangulate};
 use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
 use crate::rendering_engine::scene::model_2d::triangle::Triangle as Triangle2D;
+use crate::rendering_engine::scene::model_3d::matrix::Mat4;
 use crate::rendering_engine::scene::model_3d::plane::Plane;
-use crate::rendering_engine::scene::model_3d::plane_direction::PlaneDirection;
 use crate::rendering_engine::scene::model_3d::point::Point as Point3D;
 use crate::rendering_engine::scene::model_3d::triangle::Triangle as Triangle3D;
 
-pub fn project(camera_planes: &HashMap<PlaneDirection, Plane>, focal_length: f32, triangle: &Triangle3D) -> Triangle2D {
-    let vertices: Vec<crate::rendering_engine::scene::model_2d::point::Point> = triangle.vertices()
-        .iter()
-        .map(|vertex: &Point3D| {
-            let x_distance: f32 = vertex.distance_from_plane(&camera_planes.get(&PlaneDirection::YZ).unwrap());
-            let y_distance: f32 = vertex.distance_from_plane(&camera_planes.get(&PlaneDirection::XZ).unwrap());
-            let z_distance: f32 = vertex.distance_from_plane(&camera_planes.get(&PlaneDirection::XY).unwrap());
+#[derive(Debug, Copy, Clone)]
+pub struct VertexDepth {
+    /** distance from the camera XY plane */
+    pub distance: f32,
+    /** homogeneous w after the projection, the distance for perspective projections and 1 for orthographic ones */
+    pub w: f32,
+}
 
-            if z_distance == 0.0 {
-                return Point2D {
-                    x: vertex.x,
-                    y: vertex.y,
-                };
-            }
+pub type VertexDepths = [VertexDepth; 3];
 
-            return Point2D {
-                x: focal_length * x_distance / z_distance,
-                y: focal_length * y_distance / z_distance
-            }
-        })
-        .collect::<Vec<Point2D>>();
+/**
+ * Barycentric coordinates of the vertices of a projected triangle in the triangle before clipping,
+ * attributes of the original vertices are interpolated by them.
+ */
+pub type SourceWeights = [[f32; 3]; 3];
+
+/**
+ * Clips the triangle in the camera space by the camera frustum and projects the visible part of it.
+ * A clipped triangle becomes a convex polygon, so it may be projected as several triangles.
+ */
+pub fn project(projection_matrix: &Mat4,
+               clipping_planes: &[Plane],
+               triangle: &Triangle3D) -> Vec<(Triangle2D, VertexDepths, SourceWeights)> {
 
-    let vertices: [Point2D; 3] = [
-        Point2D {
-            x: vertices[0].x,
-            y: vertices[0].y,
-        },
-        Point2D {
-            x: vertices[1].x,
-            y: vertices[1].y,
-        },
-        Point2D {
-            x: vertices[2].x,
-            y: vertices[2].y,
-        }
-    ];
+    let visible_polygon: Vec<Point3D> = clip(&triangle.vertices(), clipping_planes);
 
-    Triangle2D { vertices }
-}
\ No newline at end of file
+    triangulate(&visible_polygon)
+        .iter()
+        .map(|vertices: &[Point3D; 3]| {
+            let projected_vertices: [[f32; 4]; 3] = vertices
+                .map(|vertex: Point3D| projection_matrix.transform_homogeneous(&vertex));
+
+            (
+                Triangle2D::new(projected_vertices.map(|[x, y, _, w]: [f32; 4]| Point2D {
+                    x: x / w,
+                    y: y / w
+                })),
+                [0, 1, 2].map(|i: usize| VertexDepth {
+                    distance: vertices[i].z,
+                    w: projected_vertices[i][3]
+                }),
+                vertices.map(|vertex: Point3D| triangle.barycentric(&vertex))
+            )
+        })
+        .collect()
+}
@@ -1,188 +1,217 @@
-use line_drawing::XiaolinWu;
-use serde::{Deserialize, Serialize};
-
-use crate::rendering_engine::engine::model::depth_pixel::DepthPixel;
+use crate::rendering_engine::engine::model::barycentric::Barycentric;
 use crate::rendering_engine::engine::model::z_buffered_triangle::{ZBufferedTriangle, ZBufferedVertex, ZBufferedVertices};
-use crate::rendering_engine::scene::model::color::Color;
-
-pub type DepthPixelBuffer = Vec<Vec<DepthPixel>>;
 
 /**
     Values:
-    x, y, width, height
+    first column, first row, last column, last row (inclusive)
  */
-type BoundingBox = (f32, f32, f32, f32);
+type BoundingBox = (isize, isize, isize, isize);
 
-#[derive(Serialize, Deserialize)]
-pub struct ZBuffer {
-    pub buffer: DepthPixelBuffer,
-    pub x: isize,
-    pub y: isize,
-}
+/**
+ * Covers every pixel of the width x height target whose center lies inside the triangle
+ * and calls `write_pixel(column, row, barycentric)` for it.
+ * Weights of the barycentric are relative to the triangle before clipping, see ZBufferedVertex::weights.
+ * Pixels lying exactly on an edge are covered only if the edge is a top or a left one,
+ * so triangles sharing an edge never cover the same pixel twice.
+ */
+pub fn rasterize<F>(z_buffered_triangle: &ZBufferedTriangle, width: usize, height: usize, mut write_pixel: F)
+    where F: FnMut(usize, usize, &Barycentric) {
 
-pub fn rasterize(z_buffered_triangle: &ZBufferedTriangle) -> ZBuffer {
-    let (x, y, width, height) = create_bounding_box(z_buffered_triangle);
-    let buffer_width: usize = width.ceil() as usize;
-    let buffer_height: usize = height.ceil() as usize;
+    let vertices: ZBufferedVertices = z_buffered_triangle.vertices;
+    let area: f32 = edge_function(&vertices[0], &vertices[1], vertices[2].x, vertices[2].y);
 
-    let mut pixel_buffer: DepthPixelBuffer =
-        vec![
-            vec![DepthPixel::default(); buffer_width + 1];
-            buffer_height + 1
-        ];
+    if area == 0.0 || width == 0 || height == 0 {
+        return;
+    }
 
-    let relative_vertices: ZBufferedVertices = z_buffered_triangle.vertices
-        .map(|z_buffered_vertex: ZBufferedVertex| {
-            ZBufferedVertex {
-                x: z_buffered_vertex.x - x,
-                y: z_buffered_vertex.y - y,
-                distance: z_buffered_vertex.distance
+    let (first_col, first_row, last_col, last_row) = create_bounding_box(&vertices);
+
+    let first_col: isize = first_col.max(0);
+    let first_row: isize = first_row.max(0);
+    let last_col: isize = last_col.min(width as isize - 1);
+    let last_row: isize = last_row.min(height as isize - 1);
+
+    /*
+     * edge opposite to the vertex i goes from the vertex i + 1 to the vertex i + 2,
+     * edges of counterclockwise triangles are reversed, so edge functions are positive inside the triangle
+     */
+    let edges: [(ZBufferedVertex, ZBufferedVertex); 3] = [(1, 2), (2, 0), (0, 1)]
+        .map(|(start, end): (usize, usize)| {
+            if area > 0.0 {
+                (vertices[start], vertices[end])
+            } else {
+                (vertices[end], vertices[start])
             }
         });
 
-    for i in 0..3 {
-        let current: usize = i;
-        let next: usize = if i == 2 {
-            0
-        } else {
-            i + 1
-        };
-
-        draw_line(
-            relative_vertices[current],
-            relative_vertices[next],
-            z_buffered_triangle.color,
-            &mut pixel_buffer
-        );
-    }
+    let area: f32 = area.abs();
+    let distances: [f32; 3] = vertices.map(|vertex: ZBufferedVertex| vertex.distance);
+    let ws: [f32; 3] = vertices.map(|vertex: ZBufferedVertex| vertex.w);
+    let source_weights: [[f32; 3]; 3] = vertices.map(|vertex: ZBufferedVertex| vertex.weights);
+    let top_left: [bool; 3] = edges.map(|(start, end): (ZBufferedVertex, ZBufferedVertex)| is_top_left(&start, &end));
 
-    let fill_lines: Vec<(ZBufferedVertex, ZBufferedVertex)> = get_fill_lines(&mut pixel_buffer);
-    fill(z_buffered_triangle.color, fill_lines, &mut pixel_buffer);
-
-    ZBuffer {
-        buffer: pixel_buffer,
-        x: x.floor() as isize,
-        y: y.floor() as isize
-    }
-}
+    for row in first_row..=last_row {
+        let y: f32 = row as f32 + 0.5;
 
-#[inline]
-fn draw_line(point0: ZBufferedVertex,
-             point1: ZBufferedVertex,
-             color: Color,
-             pixel_buffer: &mut DepthPixelBuffer) {
+        for col in first_col..=last_col {
+            let x: f32 = col as f32 + 0.5;
 
-    let line: XiaolinWu<f32, isize> = XiaolinWu::new((point0.x, point0.y), (point1.x, point1.y));
+            let weights: [f32; 3] = edges.map(|(start, end): (ZBufferedVertex, ZBufferedVertex)| edge_function(&start, &end, x, y));
 
-    let line_length: f32 = ((point0.x - point1.x).powi(2) + (point0.y - point1.y).powi(2)).sqrt();
+            let covered: bool = weights
+                .iter()
+                .zip(top_left.iter())
+                .all(|(weight, top_left): (&f32, &bool)| *weight > 0.0 || (*weight == 0.0 && *top_left));
 
-    let depth_step: f32 = (point1.distance - point0.distance) / line_length;
+            if !covered {
+                continue;
+            }
 
-    for ((x, y), opacity) in line {
-        set_pixel(x as usize, y as usize,
-                  point0.distance + depth_step,
-                  Color::new(color.r, color.g, color.b, color.a * opacity),
-                  pixel_buffer
-        );
+            let barycentric: Barycentric = Barycentric::perspective_correct(
+                weights.map(|weight: f32| weight / area),
+                ws,
+                distances
+            );
+            let barycentric: Barycentric = Barycentric {
+                weights: barycentric.interpolate(&source_weights),
+                depth: barycentric.depth
+            };
+
+            write_pixel(col as usize, row as usize, &barycentric);
+        }
     }
 }
 
-fn get_fill_lines(pixel_buffer: &mut DepthPixelBuffer) -> Vec<(ZBufferedVertex, ZBufferedVertex)> {
-    pixel_buffer
-        .iter()
-        .enumerate()
-        .map(|(row, row_pixels)| {
-            get_fill_line(row, row_pixels)
-        })
-        .collect()
+/**
+ * Doubled signed area of the triangle (start, end, (x, y)).
+ * Positive when the point lies on the right side of the edge in the bitmap system (y axis points down).
+ */
+#[inline]
+fn edge_function(start: &ZBufferedVertex, end: &ZBufferedVertex, x: f32, y: f32) -> f32 {
+    (end.x - start.x) * (y - start.y) - (end.y - start.y) * (x - start.x)
 }
 
-fn fill(
-    color: Color,
-    fill_lines: Vec<(ZBufferedVertex, ZBufferedVertex)>,
-    pixel_buffer: &mut DepthPixelBuffer) {
+/**
+ * For clockwise triangles in the bitmap system
+ * a top edge is horizontal and goes right, a left edge goes up.
+ */
+#[inline]
+fn is_top_left(start: &ZBufferedVertex, end: &ZBufferedVertex) -> bool {
+    let dx: f32 = end.x - start.x;
+    let dy: f32 = end.y - start.y;
 
-    fill_lines.iter().for_each(|fill_line: &(ZBufferedVertex, ZBufferedVertex)| {
-        draw_line(fill_line.0, fill_line.1, color, pixel_buffer);
-    });
+    (dy == 0.0 && dx > 0.0) || dy < 0.0
 }
 
-fn get_fill_line(row: usize, row_pixels: &Vec<DepthPixel>) -> (ZBufferedVertex, ZBufferedVertex) {
-    let mut left: &DepthPixel = row_pixels.first().unwrap();
-    let mut right: &DepthPixel= row_pixels.last().unwrap();
+/** range of pixels whose centers may lie inside the triangle */
+fn create_bounding_box(vertices: &ZBufferedVertices) -> BoundingBox {
+    let (min_x, min_y, max_x, max_y) = vertices
+        .iter()
+        .fold((f32::MAX, f32::MAX, f32::MIN, f32::MIN), |bounding_box: (f32, f32, f32, f32), vertex: &ZBufferedVertex| {
+            (
+                f32::min(vertex.x, bounding_box.0),
+                f32::min(vertex.y, bounding_box.1),
+                f32::max(vertex.x, bounding_box.2),
+                f32::max(vertex.y, bounding_box.3),
+            )
+        });
 
-    let mut left_column: usize = 0;
-    let mut right_column: usize = row_pixels.len() - 1;
+    (
+        (min_x - 0.5).ceil() as isize,
+        (min_y - 0.5).ceil() as isize,
+        (max_x - 0.5).floor() as isize,
+        (max_y - 0.5).floor() as isize,
+    )
+}
 
-    for (col, col_pixel) in row_pixels.iter().enumerate() {
-        if col_pixel.color.a < left.color.a {
-            break;
-        }
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::engine::model::barycentric::Barycentric;
+    use crate::rendering_engine::engine::model::z_buffered_triangle::{ZBufferedTriangle, ZBufferedVertex};
+    use crate::rendering_engine::engine::rasterizer::rasterize;
+    use crate::rendering_engine::engine::shader::TriangleShader;
+    use crate::rendering_engine::scene::camera::Projection;
+    use crate::rendering_engine::scene::model::color::Color;
+    use crate::rendering_engine::scene::model::shading::Shading;
+    use crate::rendering_engine::scene::model_3d::point::Point;
+    use crate::rendering_engine::scene::model_3d::triangle::Triangle;
+
+    const SIZE: usize = 8;
+
+    /** triangle in the bitmap system, w of 1 like an orthographic projection, so the weights are linear on the screen */
+    fn create_triangle(points: [(f32, f32); 3], distances: [f32; 3]) -> ZBufferedTriangle<'static> {
+        let origin: Point = Point { x: 0.0, y: 0.0, z: 0.0 };
+        let shader: TriangleShader = TriangleShader::new(
+            &Triangle::new([origin; 3], Color::new(0, 0, 0, 1.0)),
+            None,
+            &Shading::Flat,
+            None,
+            &Projection::Perspective,
+            &[]
+        );
 
-        if col_pixel.color.a > left.color.a {
-            left = col_pixel;
-            left_column = col;
+        ZBufferedTriangle {
+            vertices: [0, 1, 2].map(|i: usize| ZBufferedVertex {
+                x: points[i].0,
+                y: points[i].1,
+                distance: distances[i],
+                w: 1.0,
+                weights: [0, 1, 2].map(|j: usize| if i == j { 1.0 } else { 0.0 }),
+            }),
+            shader,
         }
     }
 
-    for (col, col_pixel) in row_pixels.iter().rev().enumerate() {
-        if col_pixel.color.a < right.color.a {
-            break;
-        }
+    /** how many times every pixel was written, rows from top to bottom */
+    fn coverage(triangles: &[ZBufferedTriangle]) -> [[u32; SIZE]; SIZE] {
+        let mut coverage: [[u32; SIZE]; SIZE] = [[0; SIZE]; SIZE];
 
-        if col_pixel.color.a > right.color.a {
-            right = col_pixel;
-            right_column = row_pixels.len() - col - 1;
+        for triangle in triangles {
+            rasterize(triangle, SIZE, SIZE, |col: usize, row: usize, _: &Barycentric| coverage[row][col] += 1);
         }
-    }
 
-    let left: ZBufferedVertex = ZBufferedVertex {
-        x: left_column as f32,
-        y: row as f32,
-        distance: left.depth
-    };
+        coverage
+    }
 
-    let right: ZBufferedVertex = ZBufferedVertex {
-        x: right_column as f32,
-        y: row as f32,
-        distance: right.depth
-    };
+    #[test]
+    fn shared_edge_is_covered_once() {
+        /* the diagonal goes through the centers of the pixels on it, so the top-left rule decides them */
+        let triangles: [ZBufferedTriangle; 2] = [
+            create_triangle([(0.0, 0.0), (8.0, 0.0), (8.0, 8.0)], [1.0; 3]),
+            create_triangle([(0.0, 0.0), (8.0, 8.0), (0.0, 8.0)], [1.0; 3]),
+        ];
 
-    (left, right)
-}
+        assert_eq!(coverage(&triangles), [[1; SIZE]; SIZE]);
 
-fn create_bounding_box(z_buffered_triangle: &ZBufferedTriangle) -> (f32, f32, f32, f32) {
-    let bounding_box: BoundingBox = z_buffered_triangle.vertices
-        .iter()
-        .fold((f32::MAX, f32::MAX, f32::MIN, f32::MIN), |bounding_box: BoundingBox, vertex: &ZBufferedVertex| {
-            (
-                f32::min(vertex.x, bounding_box.0),
-                f32::min(vertex.y, bounding_box.1),
-                f32::max(vertex.x, bounding_box.2),
-                f32::max(vertex.y, bounding_box.3),
-            )
-        });
+        /* only pixel centers inside the triangle are covered */
+        let upper: [[u32; SIZE]; SIZE] = coverage(&triangles[..1]);
+        assert_eq!(upper.iter().flatten().sum::<u32>(), 36);
+        assert_eq!((upper[0][7], upper[7][0]), (1, 0));
+    }
 
-    (
-        /* x */         /* y */
-        bounding_box.0, bounding_box.1,
-        /* width */
-        bounding_box.2 - bounding_box.0,
-        /* height */
-        bounding_box.3 - bounding_box.1
-    )
-}
+    #[test]
+    fn winding_does_not_matter() {
+        let clockwise: ZBufferedTriangle = create_triangle([(0.5, 0.2), (7.3, 2.0), (3.1, 7.9)], [1.0; 3]);
+        let counterclockwise: ZBufferedTriangle = create_triangle([(0.5, 0.2), (3.1, 7.9), (7.3, 2.0)], [1.0; 3]);
 
+        let covered: [[u32; SIZE]; SIZE] = coverage(&[clockwise]);
 
-/** brightness is a value from 0 to 1 */
-#[inline]
-fn set_pixel(col: usize, row: usize, depth: f32, color: Color, pixel_buffer: &mut DepthPixelBuffer) {
-    if row >= pixel_buffer.len() || col >= pixel_buffer[0].len() {
-        return;
+        assert!(covered.iter().flatten().sum::<u32>() > 0);
+        assert_eq!(covered, coverage(&[counterclockwise]));
     }
 
-    pixel_buffer[row][col] = DepthPixel {
-        color: Color::new(color.r, color.g, color.b, color.a),
-        depth
+    #[test]
+    fn depth_is_interpolated() {
+        let triangle: ZBufferedTriangle = create_triangle([(0.0, 0.0), (8.0, 0.0), (0.0, 8.0)], [10.0, 20.0, 30.0]);
+        let mut depth: Option<f32> = None;
+
+        rasterize(&triangle, SIZE, SIZE, |col: usize, row: usize, barycentric: &Barycentric| {
+            if (col, row) == (1, 1) {
+                depth = Some(barycentric.depth);
+            }
+        });
+
+        /* the center (1.5, 1.5) has the weights 0.625, 0.1875 and 0.1875 */
+        assert!((depth.unwrap() - 15.625).abs() < 1e-4);
     }
-}
\ No newline at end of file
+}
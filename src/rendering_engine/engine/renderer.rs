@@ -1,62 +1,165 @@
-use crate::rendering_engine::engine::compositor::{composite, Image};
+use std::collections::HashMap;
+
+use crate::rendering_engine::engine::clipper::create_clipping_planes;
+use crate::rendering_engine::engine::compositor::{Compositor, Image};
+use crate::rendering_engine::engine::model::barycentric::Barycentric;
 use crate::rendering_engine::engine::model::z_buffered_triangle::{ZBufferedTriangle, ZBufferedVertex};
-use crate::rendering_engine::engine::projector::project;
-use crate::rendering_engine::engine::rasterizer::{rasterize, ZBuffer};
+use crate::rendering_engine::engine::projector::{project, SourceWeights, VertexDepth, VertexDepths};
+use crate::rendering_engine::engine::rasterizer::rasterize;
+use crate::rendering_engine::engine::shader::{is_front_facing, TextureMapping, TriangleShader};
 use crate::rendering_engine::scene::camera::display::Display;
-use crate::rendering_engine::scene::camera::Camera;
+use crate::rendering_engine::scene::camera::{Camera, Projection};
+use crate::rendering_engine::scene::model::shading::Shading;
+use crate::rendering_engine::scene::light::Light;
 use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model::texture::Texture;
 use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
 use crate::rendering_engine::scene::model_2d::triangle::Triangle as Triangle2D;
+use crate::rendering_engine::scene::model_3d::matrix::{Mat3, Mat4};
 use crate::rendering_engine::scene::model_3d::mesh::Mesh;
 use crate::rendering_engine::scene::model_3d::plane::Plane;
-use crate::rendering_engine::scene::model_3d::plane_direction::PlaneDirection;
-use crate::rendering_engine::scene::model_3d::point::Point as Point3D;
 use crate::rendering_engine::scene::model_3d::triangle::Triangle as Triangle3D;
-use crate::rendering_engine::scene::MeshID;
-use std::collections::hash_map::Values;
-use std::collections::HashMap;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+use crate::rendering_engine::scene::TextureID;
 
-pub fn render(camera: &Camera, meshes: Values<MeshID, Mesh>, background_color: Color) -> Image {
-    let camera_planes: HashMap<PlaneDirection, Plane> = camera.create_planes();
-    let mut z_buffers: Vec<ZBuffer> = Vec::new();
+/**
+ * `view_matrix` moves the world space into the camera space,
+ * every mesh comes with its model matrix, which moves the mesh into the world space.
+ * Every light comes with the matrix which moves it into the world space, without lights faces keep their flat colors.
+ * Meshes refer to the textures by their ids, meshes with a missing texture keep their face colors.
+ */
+pub fn render(camera: &Camera,
+              view_matrix: &Mat4,
+              meshes: &[(&Mesh, Mat4)],
+              lights: &[(Light, Mat4)],
+              textures: &HashMap<TextureID, Texture>,
+              background_color: Color) -> Image {
+    draw(camera, view_matrix, meshes, lights, textures, background_color).compose()
+}
+
+/** same as `render`, the image is written into the buffer as RGBA bytes, see Compositor::compose_rgba */
+pub fn render_rgba(camera: &Camera,
+                   view_matrix: &Mat4,
+                   meshes: &[(&Mesh, Mat4)],
+                   lights: &[(Light, Mat4)],
+                   textures: &HashMap<TextureID, Texture>,
+                   background_color: Color,
+                   buffer: &mut Vec<u8>) {
+    draw(camera, view_matrix, meshes, lights, textures, background_color).compose_rgba(buffer);
+}
 
-    for triangles in meshes.map(|mesh: &Mesh| mesh.triangulate()) {
-        let buffers: Vec<ZBuffer> = triangles
-            .iter()
-            .map(|triangle3d: &Triangle3D| {
-                let triangle2d: Triangle2D = project(&camera_planes, camera.focal_length(), &triangle3d);
-                let z_buffered_triangle: ZBufferedTriangle = z_buffer_triangle(&triangle2d, triangle3d, camera.display(), triangle3d.color());
+fn draw(camera: &Camera,
+        view_matrix: &Mat4,
+        meshes: &[(&Mesh, Mat4)],
+        lights: &[(Light, Mat4)],
+        textures: &HashMap<TextureID, Texture>,
+        background_color: Color) -> Compositor {
+    let projection_matrix: Mat4 = camera.projection_matrix();
+    let clipping_planes: Vec<Plane> = create_clipping_planes(camera);
+    let display: &Display = camera.display();
+    let projection: Projection = camera.projection();
+
+    /* shading is done in the camera space */
+    let lights: Vec<Light> = lights
+        .iter()
+        .map(|(light, model_matrix): &(Light, Mat4)| light.transform(&(*view_matrix * *model_matrix)))
+        .collect();
+
+    let (opaque_triangles, translucent_triangles): (Vec<ZBufferedTriangle>, Vec<ZBufferedTriangle>) = meshes
+        .iter()
+        .flat_map(|(mesh, model_matrix): &(&Mesh, Mat4)| {
+            /* transforms are applied at draw time, points of the mesh stay in its local space */
+            let model_view_matrix: Mat4 = *view_matrix * *model_matrix;
 
-                rasterize(&z_buffered_triangle)
-            }).collect();
+            /* normals of smoothly shaded meshes, they are transformed by the inverse transpose to stay perpendicular under scaling */
+            let corner_normals: Option<Vec<[Vector; 3]>> = (mesh.shading != Shading::Flat && !lights.is_empty()).then(|| {
+                let normal_matrix: Mat3 = model_view_matrix.to_mat3().inverse().map_or(model_view_matrix.to_mat3(), |inverse: Mat3| inverse.transpose());
+
+                mesh.corner_normals()
+                    .iter()
+                    .map(|normals: &[Vector; 3]| normals.map(|normal: Vector| normal_matrix.transform_vector(&normal).normalize()))
+                    .collect()
+            });
+
+            /* textures are checked for alpha once per mesh */
+            let texture: Option<(&Texture, bool)> = mesh.texture
+                .filter(|_| !mesh.uvs.is_empty())
+                .and_then(|texture_id: TextureID| textures.get(&texture_id))
+                .map(|texture: &Texture| (texture, texture.is_opaque()));
+
+            mesh.triangulate()
+                .iter()
+                .enumerate()
+                .flat_map(|(face_index, triangle3d): (usize, &Triangle3D)| {
+                    let triangle3d: Triangle3D = triangle3d.transform(&model_view_matrix);
+
+                    if mesh.culling.culls(is_front_facing(&triangle3d, &projection)) {
+                        return Vec::new();
+                    }
+
+                    let normals: Option<[Vector; 3]> = corner_normals.as_ref().map(|corner_normals: &Vec<[Vector; 3]>| corner_normals[face_index]);
+                    let texture: Option<TextureMapping> = texture.map(|(texture, opaque): (&Texture, bool)| TextureMapping {
+                        texture,
+                        sampler: mesh.sampler,
+                        uvs: mesh.faces[face_index].vertices.map(|vertex: usize| mesh.uvs[vertex]),
+                        opaque
+                    });
+                    let shader: TriangleShader = TriangleShader::new(&triangle3d, normals, &mesh.shading, texture, &projection, &lights);
+
+                    project(&projection_matrix, &clipping_planes, &triangle3d)
+                        .iter()
+                        .map(|(triangle2d, depths, weights): &(Triangle2D, VertexDepths, SourceWeights)| {
+                            z_buffer_triangle(triangle2d, *depths, *weights, display, shader)
+                        })
+                        .collect::<Vec<ZBufferedTriangle>>()
+                })
+                .collect::<Vec<ZBufferedTriangle>>()
+        })
+        .partition(|z_buffered_triangle: &ZBufferedTriangle| z_buffered_triangle.shader.is_opaque());
+
+    let mut compositor: Compositor = Compositor::new(display, background_color);
+
+    /* opaque geometry first, so translucent fragments behind it are dropped by the depth test */
+    for z_buffered_triangle in &opaque_triangles {
+        rasterize(z_buffered_triangle, display.width, display.height, |col: usize, row: usize, barycentric: &Barycentric| {
+            compositor.write_opaque(col, row, barycentric.depth, z_buffered_triangle.shader.color(barycentric, &projection, &lights));
+        });
+    }
 
-        z_buffers.extend(buffers);
+    for z_buffered_triangle in &translucent_triangles {
+        rasterize(z_buffered_triangle, display.width, display.height, |col: usize, row: usize, barycentric: &Barycentric| {
+            compositor.write_translucent(col, row, barycentric.depth, z_buffered_triangle.shader.color(barycentric, &projection, &lights));
+        });
     }
 
-    composite(&z_buffers, camera.display(), background_color)
+    compositor
 }
 
-fn z_buffer_triangle(triangle2d: &Triangle2D,
-                     triangle3d: &Triangle3D,
-                     display: &Display,
-                     color: Color) -> ZBufferedTriangle {
+fn z_buffer_triangle<'a>(triangle2d: &Triangle2D,
+                         depths: VertexDepths,
+                         weights: SourceWeights,
+                         display: &Display,
+                         shader: TriangleShader<'a>) -> ZBufferedTriangle<'a> {
     let offset_width: usize = display.width / 2;
     let offset_height: usize = display.height / 2;
 
     let z_buffered_vertices: Vec<ZBufferedVertex> = triangle2d.vertices
         .iter()
-        .zip(triangle3d.vertices().iter())
-        .map(|(point2d, point3d): (&Point2D, &Point3D)| {
+        .zip(depths.iter())
+        .zip(weights.iter())
+        .map(|((point2d, depth), weights): ((&Point2D, &VertexDepth), &[f32; 3])| {
             ZBufferedVertex { /* convert from cartesian system to bitmap system */
                 x: point2d.x + offset_width as f32,
                 y: -point2d.y + offset_height as f32,
-                distance: point3d.z
+                distance: depth.distance,
+                w: depth.w,
+                weights: *weights
             }
         })
         .collect();
 
     ZBufferedTriangle {
         vertices: z_buffered_vertices.try_into().unwrap(),
-        color
+        shader
     }
 }
\ No newline at end of file
@@ -0,0 +1,177 @@
+use crate::rendering_engine::engine::model::barycentric::Barycentric;
+use crate::rendering_engine::scene::camera::Projection;
+use crate::rendering_engine::scene::light::Light;
+use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model::shading::Shading;
+use crate::rendering_engine::scene::model::texture::{Sampler, Texture};
+use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
+use crate::rendering_engine::scene::model_3d::point::Point;
+use crate::rendering_engine::scene::model_3d::triangle::Triangle;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+/**
+ * Finds the colors of the pixels of a triangle, positions and normals are in the camera space like the lights.
+ * Normals face the camera, so both sides of a face are lit the same and its winding doesn't matter.
+ */
+#[derive(Debug, Copy, Clone)]
+pub struct TriangleShader<'a> {
+    lighting: Lighting,
+    texture: Option<TextureMapping<'a>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Lighting {
+    Flat(Color),
+    /** lit colors of the vertices */
+    Gouraud([Color; 3]),
+    /** unlit color, positions and unit normals of the vertices */
+    Phong { color: Color, positions: [Point; 3], normals: [Vector; 3], specular: f32, shininess: f32 },
+}
+
+/** texture of a triangle with the texture coordinates of its vertices, `opaque` tells if the texture has no alpha */
+#[derive(Debug, Copy, Clone)]
+pub struct TextureMapping<'a> {
+    pub texture: &'a Texture,
+    pub sampler: Sampler,
+    pub uvs: [Point2D; 3],
+    pub opaque: bool,
+}
+
+impl<'a> TriangleShader<'a> {
+    /**
+     * `triangle` is in the camera space, `corner_normals` are unit normals of its vertices in the camera space,
+     * without them the triangle is shaded flat. Without lights the color of the triangle is kept.
+     * The texture color multiplies the lit color, except for Phong shading, which multiplies the color before lighting,
+     * so highlights keep the color of the light.
+     */
+    pub fn new(triangle: &Triangle,
+               corner_normals: Option<[Vector; 3]>,
+               shading: &Shading,
+               texture: Option<TextureMapping<'a>>,
+               projection: &Projection,
+               lights: &[Light]) -> TriangleShader<'a> {
+        TriangleShader {
+            lighting: Lighting::new(triangle, corner_normals, shading, projection, lights),
+            texture
+        }
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        let color_is_opaque: bool = match self.lighting {
+            Lighting::Flat(color) | Lighting::Phong { color, .. } => color.is_opaque(),
+            Lighting::Gouraud(colors) => colors[0].is_opaque(),
+        };
+
+        color_is_opaque && self.texture.is_none_or(|texture: TextureMapping| texture.opaque)
+    }
+
+    /** weights of the barycentric are relative to the triangle the shader was created for */
+    pub fn color(&self, barycentric: &Barycentric, projection: &Projection, lights: &[Light]) -> Color {
+        let texel: Option<Color> = self.texture.map(|texture: TextureMapping| {
+            texture.texture.sample(&barycentric.interpolate(&texture.uvs), &texture.sampler)
+        });
+
+        let textured = |color: Color| texel.map_or(color, |texel: Color| multiply(color, texel));
+
+        match self.lighting {
+            Lighting::Flat(color) => textured(color),
+            Lighting::Gouraud(colors) => textured(barycentric.interpolate(&colors)),
+            Lighting::Phong { color, positions, normals, specular, shininess } => {
+                let position: Point = barycentric.interpolate(&positions);
+                let normal: Vector = barycentric.interpolate(&normals).normalize();
+
+                shade_blinn_phong(textured(color), &position, &normal, &view_direction(projection, &position), lights, specular, shininess)
+            }
+        }
+    }
+}
+
+impl Lighting {
+    fn new(triangle: &Triangle, corner_normals: Option<[Vector; 3]>, shading: &Shading, projection: &Projection, lights: &[Light]) -> Lighting {
+        let color: Color = triangle.color();
+
+        if lights.is_empty() {
+            return Lighting::Flat(color);
+        }
+
+        let centroid: Point = triangle.centroid();
+        let face_normal: Vector = if is_front_facing(triangle, projection) { triangle.normal() } else { -triangle.normal() };
+
+        /* normals are turned to the side of the face the camera sees */
+        let facing = |normal: Vector| if normal.dot(&face_normal) < 0.0 { -normal } else { normal };
+        let positions: [Point; 3] = triangle.vertices();
+
+        match (shading, corner_normals) {
+            (Shading::Gouraud, Some(normals)) => Lighting::Gouraud(
+                [0, 1, 2].map(|i: usize| shade(color, &positions[i], &facing(normals[i]), lights))
+            ),
+            (Shading::Phong { specular, shininess }, Some(normals)) => Lighting::Phong {
+                color,
+                positions,
+                normals: normals.map(facing),
+                specular: *specular,
+                shininess: *shininess,
+            },
+            _ => Lighting::Flat(shade(color, &centroid, &face_normal, lights))
+        }
+    }
+}
+
+/** from the camera to the point in the camera space */
+pub fn view_direction(projection: &Projection, point: &Point) -> Vector {
+    match projection {
+        Projection::Perspective => point.to_vector(),
+        Projection::Orthographic { .. } => Vector::new(0.0, 0.0, 1.0)
+    }
+}
+
+/** the camera sees the front side of the triangle in the camera space, see Face for the winding */
+pub fn is_front_facing(triangle: &Triangle, projection: &Projection) -> bool {
+    triangle.normal().dot(&view_direction(projection, &triangle.centroid())) < 0.0
+}
+
+/** Lambert shading of a surface point, the point, the unit normal and the lights are in the same space */
+pub fn shade(color: Color, point: &Point, normal: &Vector, lights: &[Light]) -> Color {
+    let diffuse: [f32; 3] = sum(lights.iter().map(|light: &Light| light.illuminate(point, normal)));
+
+    light_color(color, diffuse, [0.0; 3])
+}
+
+/** Lambert shading with a Blinn-Phong highlight, `view_direction` goes from the camera to the point */
+pub fn shade_blinn_phong(color: Color, point: &Point, normal: &Vector, view_direction: &Vector, lights: &[Light], specular: f32, shininess: f32) -> Color {
+    let to_camera: Vector = -view_direction.normalize();
+
+    let diffuse: [f32; 3] = sum(lights.iter().map(|light: &Light| light.illuminate(point, normal)));
+
+    let highlight: [f32; 3] = sum(lights.iter().map(|light: &Light| {
+        match light.reach(point) {
+            (Some(to_light), light) if normal.dot(&to_light) > 0.0 => {
+                let half_vector: Vector = (to_light + to_camera).normalize();
+                let strength: f32 = specular * normal.dot(&half_vector).max(0.0).powf(shininess);
+
+                light.map(|channel: f32| channel * strength)
+            }
+            _ => [0.0; 3]
+        }
+    }));
+
+    light_color(color, diffuse, highlight)
+}
+
+fn sum(lights: impl Iterator<Item = [f32; 3]>) -> [f32; 3] {
+    lights.fold([0.0; 3], |sum: [f32; 3], light: [f32; 3]| [sum[0] + light[0], sum[1] + light[1], sum[2] + light[2]])
+}
+
+/** channels multiplied as fractions of 255, alphas as they are */
+fn multiply(color: Color, other: Color) -> Color {
+    let channel = |a: u8, b: u8| (a as f32 * b as f32 / 255.0).round() as u8;
+
+    Color::new(channel(color.r, other.r), channel(color.g, other.g), channel(color.b, other.b), color.a * other.a)
+}
+
+/** the color is multiplied by the diffuse light, the highlight is added on top of it */
+fn light_color(color: Color, diffuse: [f32; 3], highlight: [f32; 3]) -> Color {
+    let channel = |channel: u8, i: usize| (channel as f32 * diffuse[i] + 255.0 * highlight[i]).round().clamp(0.0, 255.0) as u8;
+
+    Color::new(channel(color.r, 0), channel(color.g, 1), channel(color.b, 2), color.a)
+}
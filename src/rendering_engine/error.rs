@@ -0,0 +1,111 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+
+use crate::rendering_engine::format::ParseError;
+use crate::rendering_engine::scene::{CameraID, LightID, MeshID, NodeID, TextureID};
+use crate::rendering_engine::SceneId;
+
+/** error of the public API, every failure a caller can cause is reported instead of panicking */
+#[derive(Debug)]
+pub enum RenderError {
+    UnknownScene(SceneId),
+    UnknownCamera(CameraID),
+    UnknownMesh(MeshID),
+    UnknownNode(NodeID),
+    UnknownLight(LightID),
+    UnknownTexture(TextureID),
+    /** malformed argument, like an id that is not a UUID or a JS object of the wrong shape */
+    BadInput(String),
+    /** points and faces that can't form a mesh */
+    InvalidGeometry(String),
+    /** file that failed to parse */
+    Parse(ParseError),
+    Io(io::Error),
+}
+
+impl Display for RenderError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::UnknownScene(scene_id) => write!(formatter, "scene {} does not exist", scene_id),
+            RenderError::UnknownCamera(camera_id) => write!(formatter, "camera {} does not exist", camera_id),
+            RenderError::UnknownMesh(mesh_id) => write!(formatter, "mesh {} does not exist", mesh_id),
+            RenderError::UnknownNode(node_id) => write!(formatter, "node {} does not exist", node_id),
+            RenderError::UnknownLight(light_id) => write!(formatter, "light {} does not exist", light_id),
+            RenderError::UnknownTexture(texture_id) => write!(formatter, "texture {} does not exist", texture_id),
+            RenderError::BadInput(message) => write!(formatter, "bad input: {}", message),
+            RenderError::InvalidGeometry(message) => write!(formatter, "invalid geometry: {}", message),
+            RenderError::Parse(error) => write!(formatter, "{}", error),
+            RenderError::Io(error) => write!(formatter, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RenderError::Parse(error) => Some(error),
+            RenderError::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<ParseError> for RenderError {
+    fn from(error: ParseError) -> RenderError {
+        RenderError::Parse(error)
+    }
+}
+
+impl From<io::Error> for RenderError {
+    fn from(error: io::Error) -> RenderError {
+        RenderError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::io;
+
+    use uuid::Uuid;
+
+    use crate::rendering_engine::error::RenderError;
+    use crate::rendering_engine::format::ParseError;
+
+    #[test]
+    fn errors_are_displayed() {
+        let id: Uuid = Uuid::nil();
+
+        assert_eq!(RenderError::UnknownScene(id).to_string(), "scene 00000000-0000-0000-0000-000000000000 does not exist");
+        assert_eq!(RenderError::UnknownCamera(id).to_string(), format!("camera {} does not exist", id));
+        assert_eq!(RenderError::UnknownMesh(id).to_string(), format!("mesh {} does not exist", id));
+        assert_eq!(RenderError::UnknownNode(id).to_string(), format!("node {} does not exist", id));
+        assert_eq!(RenderError::UnknownLight(id).to_string(), format!("light {} does not exist", id));
+        assert_eq!(RenderError::UnknownTexture(id).to_string(), format!("texture {} does not exist", id));
+        assert_eq!(RenderError::BadInput(String::from("no id")).to_string(), "bad input: no id");
+        assert_eq!(RenderError::InvalidGeometry(String::from("no faces")).to_string(), "invalid geometry: no faces");
+    }
+
+    #[test]
+    fn parse_errors_are_converted() {
+        let error: RenderError = ParseError::new(3, "unknown statement 'x'").into();
+
+        assert!(matches!(&error, RenderError::Parse(ParseError { line: 3, .. })));
+        assert_eq!(error.to_string(), "line 3: unknown statement 'x'");
+        assert_eq!(error.source().unwrap().to_string(), "line 3: unknown statement 'x'");
+
+        /* errors of whole files have no line */
+        assert_eq!(RenderError::from(ParseError::new(0, "not a GLB file")).to_string(), "not a GLB file");
+    }
+
+    #[test]
+    fn io_errors_are_converted() {
+        let error: RenderError = io::Error::new(io::ErrorKind::NotFound, "scene.json not found").into();
+
+        assert!(matches!(&error, RenderError::Io(io_error) if io_error.kind() == io::ErrorKind::NotFound));
+        assert_eq!(error.to_string(), "scene.json not found");
+        assert!(error.source().is_some());
+
+        assert!(RenderError::BadInput(String::new()).source().is_none());
+    }
+}
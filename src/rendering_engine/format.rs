@@ -0,0 +1,54 @@
+use std::fmt::{Display, Formatter};
+
+pub mod obj;
+pub mod mtl;
+pub mod gltf;
+pub mod stl;
+pub mod image;
+pub mod scene_file;
+
+/** error of a text format, line numbers start from 1, 0 means the error is not bound to a line */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line,
+            message: message.into()
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(formatter, "{}", self.message)
+        } else {
+            write!(formatter, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/** text formats may be passed as raw file contents */
+pub fn bytes_to_text(bytes: &[u8]) -> Result<&str, ParseError> {
+    std::str::from_utf8(bytes)
+        .map_err(|error: std::str::Utf8Error| ParseError::new(0, format!("file is not valid UTF-8: {}", error)))
+}
+
+/** parses every value of a line, reporting the first invalid one */
+pub(crate) fn parse_numbers(line: usize, values: &[&str]) -> Result<Vec<f32>, ParseError> {
+    values
+        .iter()
+        .map(|value: &&str| {
+            value
+                .parse::<f32>()
+                .map_err(|_| ParseError::new(line, format!("invalid number '{}'", value)))
+        })
+        .collect()
+}
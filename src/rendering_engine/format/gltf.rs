@@ -0,0 +1,1286 @@
+use std::collections::HashMap;
+use std::fmt::{Display as FmtDisplay, Formatter};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::rendering_engine::error::RenderError;
+use crate::rendering_engine::format::ParseError;
+use crate::rendering_engine::scene::camera::display::Display;
+use crate::rendering_engine::scene::camera::options::{check_clipping_distances, check_fov};
+use crate::rendering_engine::scene::camera::{Camera, DEFAULT_FAR};
+use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model::texture::{Filter, Sampler, Texture, Wrap};
+use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
+use crate::rendering_engine::scene::model_3d::face::Face;
+use crate::rendering_engine::scene::model_3d::matrix::Mat4;
+use crate::rendering_engine::scene::model_3d::mesh::Mesh;
+use crate::rendering_engine::scene::model_3d::point::Point as Point3D;
+use crate::rendering_engine::scene::model_3d::quaternion::Quaternion;
+use crate::rendering_engine::scene::model_3d::transform::Transform;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+use crate::rendering_engine::scene::{CameraID, MeshID, NodeID, TextureID};
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_JSON_CHUNK: u32 = 0x4E4F_534A;
+const GLB_BIN_CHUNK: u32 = 0x004E_4942;
+
+const MODE_TRIANGLES: u32 = 4;
+const MODE_TRIANGLE_STRIP: u32 = 5;
+const MODE_TRIANGLE_FAN: u32 = 6;
+
+/** width of imported cameras is at most this many times their height */
+const MAX_ASPECT_RATIO: f32 = 16.0;
+
+const FILTER_NEAREST: u32 = 9728;
+const WRAP_CLAMP_TO_EDGE: u32 = 33071;
+const WRAP_MIRRORED_REPEAT: u32 = 33648;
+const WRAP_REPEAT: u32 = 10497;
+
+/** something in the file the importer skipped, the rest of the file is still imported */
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GltfWarning {
+    UnsupportedExtension { name: String, required: bool },
+    /** buffers are only read from the GLB binary chunk or data URIs */
+    ExternalBuffer { buffer: usize, uri: String },
+    SkippedPrimitive { mesh: usize, primitive: usize, reason: String },
+    /**
+     * Base color texture which is an external file, an image other than PNG or PPM,
+     * or uses other texture coordinates than the first, only the base color factor is used.
+     */
+    IgnoredTexture { material: usize },
+    /** orthographic cameras and perspective ones with invalid parameters, see create_camera */
+    UnsupportedCamera { camera: usize, kind: String },
+    /** skins, animations and morph targets with the number of ignored items */
+    IgnoredFeature { feature: String, count: usize },
+}
+
+impl FmtDisplay for GltfWarning {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GltfWarning::UnsupportedExtension { name, required: true } =>
+                write!(formatter, "required extension '{}' is not supported", name),
+            GltfWarning::UnsupportedExtension { name, required: false } =>
+                write!(formatter, "extension '{}' is not supported", name),
+            GltfWarning::ExternalBuffer { buffer, uri } =>
+                write!(formatter, "buffer {} refers to external file '{}'", buffer, uri),
+            GltfWarning::SkippedPrimitive { mesh, primitive, reason } =>
+                write!(formatter, "primitive {} of mesh {} skipped: {}", primitive, mesh, reason),
+            GltfWarning::IgnoredTexture { material } =>
+                write!(formatter, "base color texture of material {} ignored", material),
+            GltfWarning::UnsupportedCamera { camera, kind } =>
+                write!(formatter, "camera {} of type '{}' is not supported", camera, kind),
+            GltfWarning::IgnoredFeature { feature, count } =>
+                write!(formatter, "{} {} ignored", count, feature),
+        }
+    }
+}
+
+pub struct GltfNode {
+    pub name: Option<String>,
+    pub transform: Transform,
+    pub children: Vec<usize>,
+    /** one mesh per primitive of the node mesh */
+    pub meshes: Vec<GltfMesh>,
+    pub camera: Option<Camera>,
+}
+
+/** primitive of a glTF mesh with the sampler of its base color texture set */
+#[derive(Clone)]
+pub struct GltfMesh {
+    pub mesh: Mesh,
+    /** index of the base color texture in GltfScene::textures */
+    pub texture: Option<usize>,
+}
+
+/** nodes keep glTF indices, only the nodes reachable from the roots belong to the scene */
+pub struct GltfScene {
+    pub nodes: Vec<GltfNode>,
+    pub roots: Vec<usize>,
+    /** decoded images of the base color textures, an image used by several textures is decoded once */
+    pub textures: Vec<Texture>,
+    pub warnings: Vec<GltfWarning>,
+}
+
+/** ids of everything a glTF file added to a scene */
+#[derive(Serialize)]
+pub struct GltfImport {
+    pub node_ids: Vec<NodeID>,
+    pub mesh_ids: Vec<MeshID>,
+    pub camera_ids: Vec<CameraID>,
+    pub texture_ids: Vec<TextureID>,
+    pub warnings: Vec<GltfWarning>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    asset: Asset,
+    #[serde(default)]
+    extensions_used: Vec<String>,
+    #[serde(default)]
+    extensions_required: Vec<String>,
+    scene: Option<usize>,
+    #[serde(default)]
+    scenes: Vec<SceneDefinition>,
+    #[serde(default)]
+    nodes: Vec<NodeDefinition>,
+    #[serde(default)]
+    meshes: Vec<MeshDefinition>,
+    #[serde(default)]
+    accessors: Vec<AccessorDefinition>,
+    #[serde(default)]
+    buffer_views: Vec<BufferViewDefinition>,
+    #[serde(default)]
+    buffers: Vec<BufferDefinition>,
+    #[serde(default)]
+    materials: Vec<MaterialDefinition>,
+    #[serde(default)]
+    cameras: Vec<CameraDefinition>,
+    #[serde(default)]
+    textures: Vec<TextureDefinition>,
+    #[serde(default)]
+    images: Vec<ImageDefinition>,
+    #[serde(default)]
+    samplers: Vec<SamplerDefinition>,
+    #[serde(default)]
+    skins: Vec<Value>,
+    #[serde(default)]
+    animations: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct SceneDefinition {
+    #[serde(default)]
+    nodes: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+struct NodeDefinition {
+    name: Option<String>,
+    #[serde(default)]
+    children: Vec<usize>,
+    mesh: Option<usize>,
+    camera: Option<usize>,
+    matrix: Option<[f32; 16]>,
+    translation: Option<[f32; 3]>,
+    rotation: Option<[f32; 4]>,
+    scale: Option<[f32; 3]>,
+}
+
+#[derive(Deserialize)]
+struct MeshDefinition {
+    primitives: Vec<PrimitiveDefinition>,
+}
+
+#[derive(Deserialize)]
+struct PrimitiveDefinition {
+    attributes: HashMap<String, usize>,
+    indices: Option<usize>,
+    material: Option<usize>,
+    #[serde(default = "default_mode")]
+    mode: u32,
+    #[serde(default)]
+    targets: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccessorDefinition {
+    buffer_view: Option<usize>,
+    #[serde(default)]
+    byte_offset: usize,
+    component_type: u32,
+    #[serde(default)]
+    normalized: bool,
+    count: usize,
+    #[serde(rename = "type")]
+    element_type: String,
+    sparse: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferViewDefinition {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferDefinition {
+    uri: Option<String>,
+    byte_length: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MaterialDefinition {
+    #[serde(default)]
+    pbr_metallic_roughness: PbrDefinition,
+    #[serde(default = "default_alpha_mode")]
+    alpha_mode: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PbrDefinition {
+    #[serde(default = "default_base_color")]
+    base_color_factor: [f32; 4],
+    base_color_texture: Option<TextureInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TextureInfo {
+    index: usize,
+    #[serde(default)]
+    tex_coord: usize,
+}
+
+#[derive(Deserialize)]
+struct TextureDefinition {
+    sampler: Option<usize>,
+    source: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImageDefinition {
+    uri: Option<String>,
+    buffer_view: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SamplerDefinition {
+    mag_filter: Option<u32>,
+    #[serde(default = "default_wrap")]
+    wrap_s: u32,
+}
+
+impl Default for PbrDefinition {
+    fn default() -> Self {
+        PbrDefinition {
+            base_color_factor: default_base_color(),
+            base_color_texture: None
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CameraDefinition {
+    #[serde(rename = "type")]
+    camera_type: String,
+    perspective: Option<PerspectiveDefinition>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PerspectiveDefinition {
+    aspect_ratio: Option<f32>,
+    yfov: f32,
+    znear: f32,
+    zfar: Option<f32>,
+}
+
+fn default_mode() -> u32 {
+    MODE_TRIANGLES
+}
+
+fn default_wrap() -> u32 {
+    WRAP_REPEAT
+}
+
+fn default_alpha_mode() -> String {
+    String::from("OPAQUE")
+}
+
+fn default_base_color() -> [f32; 4] {
+    [1.0, 1.0, 1.0, 1.0]
+}
+
+/**
+ * Parses a glTF 2.0 file, either JSON (.gltf) or binary (.glb).
+ * Buffers and images are read from the GLB binary chunk or embedded data URIs, external files are never fetched.
+ * Base color textures of PNG or PPM images are imported, other maps of the materials are ignored.
+ *
+ * glTF is right-handed while the scene is left-handed, so z coordinates are negated
+ * and the triangle winding is reversed to keep faces pointing the same way.
+ * Perspective cameras keep the display height, the width follows the aspect ratio of the camera if it has one.
+ */
+pub fn parse_gltf(bytes: &[u8], display: &Display) -> Result<GltfScene, ParseError> {
+    let (json, binary_chunk): (&[u8], Option<&[u8]>) = if bytes.starts_with(b"glTF") {
+        split_glb(bytes)?
+    } else {
+        (bytes, None)
+    };
+
+    let document: Document = serde_json::from_slice(json)
+        .map_err(|error: serde_json::Error| ParseError::new(error.line(), format!("invalid glTF JSON: {}", error)))?;
+
+    if !document.asset.version.starts_with("2.") {
+        return Err(ParseError::new(0, format!("unsupported glTF version '{}'", document.asset.version)));
+    }
+
+    validate(&document)?;
+
+    let mut warnings: Vec<GltfWarning> = Vec::new();
+
+    for name in &document.extensions_used {
+        warnings.push(GltfWarning::UnsupportedExtension {
+            name: name.clone(),
+            required: document.extensions_required.contains(name)
+        });
+    }
+
+    let buffers: Vec<Option<Vec<u8>>> = read_buffers(&document, binary_chunk, &mut warnings)?;
+
+    let mut textures: Vec<Texture> = Vec::new();
+    let mut image_textures: HashMap<usize, Option<usize>> = HashMap::new();
+
+    let materials: Vec<(Color, Option<(usize, Sampler)>)> = document.materials
+        .iter()
+        .enumerate()
+        .map(|(index, material): (usize, &MaterialDefinition)| {
+            let texture: Option<(usize, Sampler)> = material.pbr_metallic_roughness.base_color_texture
+                .as_ref()
+                .and_then(|texture_info: &TextureInfo| {
+                    let texture: Option<(usize, Sampler)> = import_texture(&document, &buffers, texture_info, &mut textures, &mut image_textures);
+
+                    if texture.is_none() {
+                        warnings.push(GltfWarning::IgnoredTexture { material: index });
+                    }
+
+                    texture
+                });
+
+            (material_color(material), texture)
+        })
+        .collect();
+
+    let meshes: Vec<Vec<GltfMesh>> = document.meshes
+        .iter()
+        .enumerate()
+        .map(|(mesh_index, mesh): (usize, &MeshDefinition)| {
+            mesh.primitives
+                .iter()
+                .enumerate()
+                .filter_map(|(primitive_index, primitive): (usize, &PrimitiveDefinition)| {
+                    read_primitive(&document, &buffers, &materials, primitive)
+                        .map_err(|reason: String| warnings.push(GltfWarning::SkippedPrimitive {
+                            mesh: mesh_index,
+                            primitive: primitive_index,
+                            reason
+                        }))
+                        .ok()
+                })
+                .collect()
+        })
+        .collect();
+
+    let cameras: Vec<Option<Camera>> = document.cameras
+        .iter()
+        .enumerate()
+        .map(|(index, camera): (usize, &CameraDefinition)| {
+            let camera: Option<Camera> = create_camera(camera, display);
+
+            if camera.is_none() {
+                warnings.push(GltfWarning::UnsupportedCamera {
+                    camera: index,
+                    kind: document.cameras[index].camera_type.clone()
+                });
+            }
+
+            camera
+        })
+        .collect();
+
+    let morph_targets: usize = document.meshes
+        .iter()
+        .flat_map(|mesh: &MeshDefinition| mesh.primitives.iter())
+        .filter(|primitive: &&PrimitiveDefinition| !primitive.targets.is_empty())
+        .count();
+
+    for (feature, count) in [("skins", document.skins.len()), ("animations", document.animations.len()), ("morph targets", morph_targets)] {
+        if count > 0 {
+            warnings.push(GltfWarning::IgnoredFeature { feature: String::from(feature), count });
+        }
+    }
+
+    let nodes: Vec<GltfNode> = document.nodes
+        .iter()
+        .map(|node: &NodeDefinition| GltfNode {
+            name: node.name.clone(),
+            transform: node_transform(node),
+            children: node.children.clone(),
+            meshes: node.mesh.map(|mesh: usize| meshes[mesh].clone()).unwrap_or_default(),
+            camera: node.camera.and_then(|camera: usize| cameras[camera].clone())
+        })
+        .collect();
+
+    let scene: Option<usize> = document.scene.or_else(|| (!document.scenes.is_empty()).then_some(0));
+
+    let roots: Vec<usize> = match scene {
+        Some(scene) => document.scenes[scene].nodes.clone(),
+        /* without scenes every node which is not a child of another one is a root */
+        None => (0..document.nodes.len())
+            .filter(|index: &usize| !document.nodes.iter().any(|node: &NodeDefinition| node.children.contains(index)))
+            .collect()
+    };
+
+    Ok(GltfScene {
+        nodes,
+        roots,
+        textures,
+        warnings
+    })
+}
+
+/** returns the JSON chunk and the binary chunk if present */
+fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), ParseError> {
+    let read_u32 = |offset: usize| -> Result<u32, ParseError> {
+        bytes
+            .get(offset..offset + 4)
+            .map(|word: &[u8]| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .ok_or_else(|| ParseError::new(0, "GLB file is truncated"))
+    };
+
+    if read_u32(0)? != GLB_MAGIC {
+        return Err(ParseError::new(0, "not a GLB file"));
+    }
+
+    let version: u32 = read_u32(4)?;
+    if version != 2 {
+        return Err(ParseError::new(0, format!("unsupported GLB version {}", version)));
+    }
+
+    let length: usize = (read_u32(8)? as usize).min(bytes.len());
+
+    let mut json: Option<&[u8]> = None;
+    let mut binary: Option<&[u8]> = None;
+    let mut offset: usize = 12;
+
+    while offset + 8 <= length {
+        let chunk_length: usize = read_u32(offset)? as usize;
+        let chunk_type: u32 = read_u32(offset + 4)?;
+        let start: usize = offset + 8;
+
+        let chunk: &[u8] = start
+            .checked_add(chunk_length)
+            .and_then(|end: usize| bytes.get(start..end))
+            .ok_or_else(|| ParseError::new(0, "GLB chunk exceeds the file length"))?;
+
+        match chunk_type {
+            GLB_JSON_CHUNK if json.is_none() => json = Some(chunk),
+            GLB_BIN_CHUNK if binary.is_none() => binary = Some(chunk),
+            _ => {}
+        }
+
+        /* chunks are aligned to 4 bytes */
+        offset = start + chunk_length.div_ceil(4) * 4;
+    }
+
+    let json: &[u8] = json.ok_or_else(|| ParseError::new(0, "GLB file has no JSON chunk"))?;
+
+    Ok((json, binary))
+}
+
+/** checks that every reference points to an existing object, so the rest of the importer can index freely */
+fn validate(document: &Document) -> Result<(), ParseError> {
+    let check = |index: usize, count: usize, what: &str| -> Result<(), ParseError> {
+        if index < count {
+            Ok(())
+        } else {
+            Err(ParseError::new(0, format!("{} index {} is out of range, {} defined", what, index, count)))
+        }
+    };
+
+    if let Some(scene) = document.scene {
+        check(scene, document.scenes.len(), "scene")?;
+    }
+
+    for scene in &document.scenes {
+        for node in &scene.nodes {
+            check(*node, document.nodes.len(), "node")?;
+        }
+    }
+
+    for node in &document.nodes {
+        for child in &node.children {
+            check(*child, document.nodes.len(), "node")?;
+        }
+
+        if let Some(mesh) = node.mesh {
+            check(mesh, document.meshes.len(), "mesh")?;
+        }
+
+        if let Some(camera) = node.camera {
+            check(camera, document.cameras.len(), "camera")?;
+        }
+    }
+
+    for primitive in document.meshes.iter().flat_map(|mesh: &MeshDefinition| mesh.primitives.iter()) {
+        for accessor in primitive.attributes.values().chain(primitive.indices.iter()) {
+            check(*accessor, document.accessors.len(), "accessor")?;
+        }
+
+        if let Some(material) = primitive.material {
+            check(material, document.materials.len(), "material")?;
+        }
+    }
+
+    for texture_info in document.materials.iter().filter_map(|material: &MaterialDefinition| material.pbr_metallic_roughness.base_color_texture.as_ref()) {
+        check(texture_info.index, document.textures.len(), "texture")?;
+    }
+
+    for texture in &document.textures {
+        if let Some(sampler) = texture.sampler {
+            check(sampler, document.samplers.len(), "sampler")?;
+        }
+
+        if let Some(source) = texture.source {
+            check(source, document.images.len(), "image")?;
+        }
+    }
+
+    for buffer_view in document.images.iter().filter_map(|image: &ImageDefinition| image.buffer_view) {
+        check(buffer_view, document.buffer_views.len(), "buffer view")?;
+    }
+
+    for accessor in &document.accessors {
+        if let Some(buffer_view) = accessor.buffer_view {
+            check(buffer_view, document.buffer_views.len(), "buffer view")?;
+        }
+    }
+
+    for buffer_view in &document.buffer_views {
+        check(buffer_view.buffer, document.buffers.len(), "buffer")?;
+    }
+
+    Ok(())
+}
+
+/** None for buffers whose data is not available */
+fn read_buffers(document: &Document,
+                binary_chunk: Option<&[u8]>,
+                warnings: &mut Vec<GltfWarning>) -> Result<Vec<Option<Vec<u8>>>, ParseError> {
+
+    document.buffers
+        .iter()
+        .enumerate()
+        .map(|(index, buffer): (usize, &BufferDefinition)| {
+            let data: Vec<u8> = match &buffer.uri {
+                None => match binary_chunk {
+                    /* only the first buffer may refer to the binary chunk */
+                    Some(chunk) if index == 0 => chunk.to_vec(),
+                    _ => return Err(ParseError::new(0, format!("buffer {} has no data", index)))
+                },
+                Some(uri) if uri.starts_with("data:") => decode_data_uri(uri)
+                    .map_err(|message: String| ParseError::new(0, format!("buffer {} {}", index, message)))?,
+                Some(uri) => {
+                    warnings.push(GltfWarning::ExternalBuffer { buffer: index, uri: uri.clone() });
+                    return Ok(None);
+                }
+            };
+
+            if data.len() < buffer.byte_length {
+                return Err(ParseError::new(0, format!("buffer {} is shorter than its byte length", index)));
+            }
+
+            Ok(Some(data))
+        })
+        .collect()
+}
+
+fn decode_data_uri(uri: &str) -> Result<Vec<u8>, String> {
+    let (_, encoded) = uri
+        .split_once(";base64,")
+        .ok_or_else(|| String::from("data URI is not base64"))?;
+
+    BASE64
+        .decode(encoded)
+        .map_err(|error: base64::DecodeError| format!("data URI is invalid: {}", error))
+}
+
+/**
+ * Index of the decoded image in `textures` and the sampler of the texture,
+ * None if the texture can't be imported, see GltfWarning::IgnoredTexture.
+ */
+fn import_texture(document: &Document,
+                  buffers: &[Option<Vec<u8>>],
+                  texture_info: &TextureInfo,
+                  textures: &mut Vec<Texture>,
+                  image_textures: &mut HashMap<usize, Option<usize>>) -> Option<(usize, Sampler)> {
+
+    /* meshes have a single set of texture coordinates, TEXCOORD_0 */
+    if texture_info.tex_coord != 0 {
+        return None;
+    }
+
+    let texture: &TextureDefinition = &document.textures[texture_info.index];
+    let image: usize = texture.source?;
+
+    let texture_index: Option<usize> = *image_textures.entry(image).or_insert_with(|| {
+        let texture: Texture = read_image(document, buffers, &document.images[image])?;
+        textures.push(texture);
+
+        Some(textures.len() - 1)
+    });
+
+    let sampler: Sampler = texture.sampler
+        .map(|sampler: usize| create_sampler(&document.samplers[sampler]))
+        .unwrap_or_default();
+
+    Some((texture_index?, sampler))
+}
+
+/** None for external files and images which fail to decode */
+fn read_image(document: &Document, buffers: &[Option<Vec<u8>>], image: &ImageDefinition) -> Option<Texture> {
+    let bytes: Vec<u8> = match (&image.uri, image.buffer_view) {
+        (Some(uri), _) if uri.starts_with("data:") => decode_data_uri(uri).ok()?,
+        (None, Some(buffer_view)) => {
+            let buffer_view: &BufferViewDefinition = &document.buffer_views[buffer_view];
+            let end: usize = buffer_view.byte_offset.checked_add(buffer_view.byte_length)?;
+
+            buffers[buffer_view.buffer].as_deref()?.get(buffer_view.byte_offset..end)?.to_vec()
+        }
+        _ => return None
+    };
+
+    Texture::decode(&bytes).ok()
+}
+
+/** the minification filter is ignored and so is the vertical wrap mode, Sampler has one for both axes */
+fn create_sampler(sampler: &SamplerDefinition) -> Sampler {
+    Sampler {
+        filter: match sampler.mag_filter {
+            Some(FILTER_NEAREST) => Filter::Nearest,
+            _ => Filter::Bilinear
+        },
+        wrap: match sampler.wrap_s {
+            WRAP_CLAMP_TO_EDGE => Wrap::Clamp,
+            WRAP_MIRRORED_REPEAT => Wrap::Mirror,
+            _ => Wrap::Repeat
+        }
+    }
+}
+
+/** linear base color factor converted to sRGB, alpha is used only by blended materials */
+fn material_color(material: &MaterialDefinition) -> Color {
+    let [r, g, b, a] = material.pbr_metallic_roughness.base_color_factor;
+
+    let alpha: f32 = if material.alpha_mode == "BLEND" {
+        a.clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+
+    Color::new(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), alpha)
+}
+
+fn linear_to_srgb(value: f32) -> u8 {
+    let value: f32 = value.clamp(0.0, 1.0);
+
+    let encoded: f32 = if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+
+    (encoded * 255.0).round() as u8
+}
+
+fn read_primitive(document: &Document,
+                  buffers: &[Option<Vec<u8>>],
+                  materials: &[(Color, Option<(usize, Sampler)>)],
+                  primitive: &PrimitiveDefinition) -> Result<GltfMesh, String> {
+
+    let position_accessor: usize = *primitive.attributes
+        .get("POSITION")
+        .ok_or_else(|| String::from("no POSITION attribute"))?;
+
+    let points: Vec<Point3D> = read_accessor(document, buffers, position_accessor, "VEC3")
+        .map_err(|error: AccessorError| error.to_string())?
+        .chunks(3)
+        .map(|xyz: &[f32]| Point3D { x: xyz[0], y: xyz[1], z: -xyz[2] })
+        .collect();
+
+    let indices: Vec<usize> = match primitive.indices {
+        Some(accessor) => read_indices(document, buffers, accessor)
+            .map_err(|error: AccessorError| error.to_string())?
+            .iter()
+            .map(|index: &u32| *index as usize)
+            .collect(),
+        None => (0..points.len()).collect()
+    };
+
+    if let Some(index) = indices.iter().find(|index: &&usize| **index >= points.len()) {
+        return Err(format!("index {} is out of range, {} vertices defined", index, points.len()));
+    }
+
+    let (color, texture): (Color, Option<(usize, Sampler)>) = primitive.material
+        .map(|material: usize| materials[material])
+        .unwrap_or((Color::new(255, 255, 255, 1.0), None));
+
+    let triangles: Vec<[usize; 3]> = match primitive.mode {
+        MODE_TRIANGLES => indices
+            .chunks_exact(3)
+            .map(|triangle: &[usize]| [triangle[0], triangle[1], triangle[2]])
+            .collect(),
+        MODE_TRIANGLE_STRIP => (0..indices.len().saturating_sub(2))
+            .map(|index: usize| if index.is_multiple_of(2) {
+                [indices[index], indices[index + 1], indices[index + 2]]
+            } else {
+                [indices[index + 1], indices[index], indices[index + 2]]
+            })
+            .collect(),
+        MODE_TRIANGLE_FAN => (1..indices.len().saturating_sub(1))
+            .map(|index: usize| [indices[0], indices[index], indices[index + 1]])
+            .collect(),
+        mode => return Err(format!("mode {} is not a triangle mode", mode))
+    };
+
+    /* mirroring z flips the winding, swapping two vertices restores it */
+    let faces: Vec<Face> = triangles
+        .iter()
+        .map(|[a, b, c]: &[usize; 3]| Face::new([*a, *c, *b], color))
+        .collect();
+
+    let mut mesh: Mesh = Mesh::new(points, faces);
+
+    if let Some(accessor) = primitive.attributes.get("NORMAL") {
+        mesh.normals = read_accessor(document, buffers, *accessor, "VEC3")
+            .map_err(|error: AccessorError| error.to_string())?
+            .chunks(3)
+            .map(|xyz: &[f32]| Vector::new(xyz[0], xyz[1], -xyz[2]))
+            .collect();
+    }
+
+    if let Some(accessor) = primitive.attributes.get("TEXCOORD_0") {
+        mesh.uvs = read_accessor(document, buffers, *accessor, "VEC2")
+            .map_err(|error: AccessorError| error.to_string())?
+            .chunks(2)
+            .map(|uv: &[f32]| Point2D { x: uv[0], y: uv[1] })
+            .collect();
+    }
+
+    /* attributes of different counts or non finite positions */
+    mesh.check().map_err(|error: RenderError| error.to_string())?;
+
+    if let Some((_, sampler)) = texture {
+        mesh.sampler = sampler;
+    }
+
+    Ok(GltfMesh {
+        mesh,
+        texture: texture.map(|(texture, _): (usize, Sampler)| texture)
+    })
+}
+
+/** reason an accessor could not be read, the primitive using it is skipped */
+#[derive(Debug, PartialEq)]
+enum AccessorError {
+    WrongType { accessor: usize, found: String, expected: &'static str },
+    Sparse { accessor: usize },
+    UnknownComponentType { accessor: usize, component_type: u32 },
+    /** indices have to be unsigned integers which are not normalized */
+    InvalidIndices { accessor: usize },
+    BufferNotLoaded { buffer: usize },
+    /** the elements don't fit into the buffer view or their offsets overflow */
+    OutOfBounds { accessor: usize },
+}
+
+impl FmtDisplay for AccessorError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccessorError::WrongType { accessor, found, expected } =>
+                write!(formatter, "accessor {} has type {}, expected {}", accessor, found, expected),
+            AccessorError::Sparse { accessor } =>
+                write!(formatter, "accessor {} is sparse", accessor),
+            AccessorError::UnknownComponentType { accessor, component_type } =>
+                write!(formatter, "accessor {} has unknown component type {}", accessor, component_type),
+            AccessorError::InvalidIndices { accessor } =>
+                write!(formatter, "accessor {} does not hold unsigned integer indices", accessor),
+            AccessorError::BufferNotLoaded { buffer } =>
+                write!(formatter, "buffer {} is not loaded", buffer),
+            AccessorError::OutOfBounds { accessor } =>
+                write!(formatter, "accessor {} exceeds its buffer view", accessor),
+        }
+    }
+}
+
+/** flattened accessor values, normalized integers are mapped to [0, 1] or [-1, 1] */
+fn read_accessor(document: &Document,
+                 buffers: &[Option<Vec<u8>>],
+                 index: usize,
+                 expected_type: &'static str) -> Result<Vec<f32>, AccessorError> {
+
+    let accessor: &AccessorDefinition = &document.accessors[index];
+
+    read_elements(document, buffers, index, expected_type, |bytes: &[u8]| read_component(bytes, accessor.component_type, accessor.normalized))
+}
+
+/** scalar accessor of unsigned bytes, shorts or ints */
+fn read_indices(document: &Document, buffers: &[Option<Vec<u8>>], index: usize) -> Result<Vec<u32>, AccessorError> {
+    let accessor: &AccessorDefinition = &document.accessors[index];
+
+    if !matches!(accessor.component_type, 5121 | 5123 | 5125) || accessor.normalized {
+        return Err(AccessorError::InvalidIndices { accessor: index });
+    }
+
+    read_elements(document, buffers, index, "SCALAR", |bytes: &[u8]| match bytes.len() {
+        1 => bytes[0] as u32,
+        2 => u16::from_le_bytes([bytes[0], bytes[1]]) as u32,
+        _ => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    })
+}
+
+/**
+ * Components of every element read by `read`, which gets the bytes of one component.
+ * Nothing is allocated before the count is checked against the buffer, so a hostile count fails instead of running out of memory.
+ */
+fn read_elements<T: Clone + Default>(document: &Document,
+                                     buffers: &[Option<Vec<u8>>],
+                                     index: usize,
+                                     expected_type: &'static str,
+                                     read: impl Fn(&[u8]) -> T) -> Result<Vec<T>, AccessorError> {
+
+    let accessor: &AccessorDefinition = &document.accessors[index];
+
+    if accessor.element_type != expected_type {
+        return Err(AccessorError::WrongType { accessor: index, found: accessor.element_type.clone(), expected: expected_type });
+    }
+
+    if accessor.sparse.is_some() {
+        return Err(AccessorError::Sparse { accessor: index });
+    }
+
+    let components: usize = match accessor.element_type.as_str() {
+        "SCALAR" => 1,
+        "VEC2" => 2,
+        "VEC3" => 3,
+        _ => 4
+    };
+
+    let component_size: usize = match accessor.component_type {
+        5120 | 5121 => 1,
+        5122 | 5123 => 2,
+        5125 | 5126 => 4,
+        component_type => return Err(AccessorError::UnknownComponentType { accessor: index, component_type })
+    };
+
+    let element_size: usize = components * component_size;
+
+    let buffer_view: &BufferViewDefinition = match accessor.buffer_view {
+        Some(buffer_view) => &document.buffer_views[buffer_view],
+        None => {
+            /* accessors without a buffer view are filled with zeros, they can't be larger than the data of the file */
+            let loaded: usize = buffers.iter().flatten().map(Vec::len).sum();
+
+            return match accessor.count.checked_mul(element_size) {
+                Some(size) if size <= loaded => Ok(vec![T::default(); accessor.count * components]),
+                _ => Err(AccessorError::OutOfBounds { accessor: index })
+            };
+        }
+    };
+
+    let buffer: &[u8] = buffers[buffer_view.buffer]
+        .as_deref()
+        .ok_or(AccessorError::BufferNotLoaded { buffer: buffer_view.buffer })?;
+
+    if accessor.count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let stride: usize = buffer_view.byte_stride.unwrap_or(element_size);
+
+    /* offsets in the file can be anything, so they are added with overflow checks */
+    let start: Option<usize> = buffer_view.byte_offset.checked_add(accessor.byte_offset);
+    let end: Option<usize> = stride
+        .checked_mul(accessor.count - 1)
+        .zip(start)
+        .and_then(|(last, start): (usize, usize)| last.checked_add(start))
+        .and_then(|last: usize| last.checked_add(element_size));
+    let view_end: Option<usize> = buffer_view.byte_offset.checked_add(buffer_view.byte_length);
+
+    let start: usize = match (start, end, view_end) {
+        (Some(start), Some(end), Some(view_end)) if end <= view_end.min(buffer.len()) => start,
+        _ => return Err(AccessorError::OutOfBounds { accessor: index })
+    };
+
+    let mut values: Vec<T> = Vec::with_capacity(accessor.count * components);
+
+    for element in 0..accessor.count {
+        for component in 0..components {
+            let offset: usize = start + element * stride + component * component_size;
+
+            values.push(read(&buffer[offset..offset + component_size]));
+        }
+    }
+
+    Ok(values)
+}
+
+fn read_component(bytes: &[u8], component_type: u32, normalized: bool) -> f32 {
+    match component_type {
+        5120 => {
+            let value: f32 = bytes[0] as i8 as f32;
+            if normalized { (value / 127.0).max(-1.0) } else { value }
+        }
+        5121 => {
+            let value: f32 = bytes[0] as f32;
+            if normalized { value / 255.0 } else { value }
+        }
+        5122 => {
+            let value: f32 = i16::from_le_bytes([bytes[0], bytes[1]]) as f32;
+            if normalized { (value / 32767.0).max(-1.0) } else { value }
+        }
+        5123 => {
+            let value: f32 = u16::from_le_bytes([bytes[0], bytes[1]]) as f32;
+            if normalized { value / 65535.0 } else { value }
+        }
+        5125 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+        _ => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
+
+/** local transform of the node converted to the left-handed space */
+fn node_transform(node: &NodeDefinition) -> Transform {
+    let transform: Transform = match node.matrix {
+        Some(columns) => {
+            let mut matrix: Mat4 = Mat4::identity();
+
+            /* glTF matrices are stored column by column */
+            for (index, value) in columns.iter().enumerate() {
+                matrix.m[index % 4][index / 4] = *value;
+            }
+
+            Transform::from_matrix(&matrix)
+        }
+        None => {
+            let [tx, ty, tz] = node.translation.unwrap_or([0.0, 0.0, 0.0]);
+            let [x, y, z, w] = node.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+            let [sx, sy, sz] = node.scale.unwrap_or([1.0, 1.0, 1.0]);
+
+            Transform {
+                translation: Vector::new(tx, ty, tz),
+                rotation: Quaternion { w, x, y, z },
+                scale: Vector::new(sx, sy, sz)
+            }
+        }
+    };
+
+    /* mirroring z: the translation is mirrored, the rotation axis is mirrored and the angle is reversed */
+    Transform {
+        translation: Vector::new(transform.translation.x, transform.translation.y, -transform.translation.z),
+        rotation: Quaternion {
+            w: transform.rotation.w,
+            x: -transform.rotation.x,
+            y: -transform.rotation.y,
+            z: transform.rotation.z
+        }.normalize(),
+        scale: transform.scale
+    }
+}
+
+/**
+ * None for cameras which can't be represented, like the ones with a field of view, clipping distances
+ * or aspect ratio CameraOptions would reject, wider than MAX_ASPECT_RATIO or taller than its inverse.
+ */
+fn create_camera(camera: &CameraDefinition, display: &Display) -> Option<Camera> {
+    if camera.camera_type != "perspective" {
+        return None;
+    }
+
+    let perspective: &PerspectiveDefinition = camera.perspective.as_ref()?;
+    let vertical_fov: f32 = perspective.yfov.to_degrees();
+    let far: f32 = perspective.zfar.unwrap_or(DEFAULT_FAR);
+
+    check_fov("yfov", vertical_fov).ok()?;
+    check_clipping_distances(perspective.znear, far).ok()?;
+
+    let height: usize = display.height;
+    let width: usize = match perspective.aspect_ratio {
+        /* the display is allocated from the width, so a huge ratio must not reach it */
+        Some(aspect_ratio) if (1.0 / MAX_ASPECT_RATIO..=MAX_ASPECT_RATIO).contains(&aspect_ratio) =>
+            ((height as f32) * aspect_ratio).round().max(1.0) as usize,
+        Some(_) => return None,
+        None => display.width
+    };
+
+    /* glTF cameras look along -z of their node, which becomes +z after mirroring */
+    let mut result: Camera = Camera::from_vertical_fov(
+        vertical_fov,
+        Point3D { x: 0.0, y: 0.0, z: 0.0 },
+        0.0,
+        0.0,
+        0.0,
+        Display::new(width, height)
+    );
+
+    result.set_clipping_distances(perspective.znear, far);
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::format::gltf::{
+        parse_gltf, read_accessor, read_indices, AccessorError, Document, GltfImport, GltfMesh, GltfNode, GltfScene, GltfWarning, GLB_BIN_CHUNK, GLB_JSON_CHUNK, GLB_MAGIC
+    };
+    use crate::rendering_engine::scene::camera::display::Display;
+    use crate::rendering_engine::scene::model::color::Color;
+    use crate::rendering_engine::scene::model::texture::{Filter, Sampler, Texture, Wrap};
+    use crate::rendering_engine::scene::model_3d::face::Face;
+    use crate::rendering_engine::scene::model_3d::mesh::Mesh;
+    use crate::rendering_engine::scene::model_3d::point::Point;
+    use crate::rendering_engine::scene::model_3d::vector::Vector;
+    use crate::rendering_engine::scene::Scene;
+
+    /** GLB with both chunks padded to 4 bytes */
+    fn create_glb(json: &str, binary: &[u8]) -> Vec<u8> {
+        let mut json: Vec<u8> = json.as_bytes().to_vec();
+        json.resize(json.len().div_ceil(4) * 4, b' ');
+
+        let mut binary: Vec<u8> = binary.to_vec();
+        binary.resize(binary.len().div_ceil(4) * 4, 0);
+
+        let length: usize = 12 + 8 + json.len() + 8 + binary.len();
+
+        let mut glb: Vec<u8> = Vec::new();
+        for word in [GLB_MAGIC, 2, length as u32, json.len() as u32, GLB_JSON_CHUNK] {
+            glb.extend_from_slice(&word.to_le_bytes());
+        }
+        glb.extend_from_slice(&json);
+        for word in [binary.len() as u32, GLB_BIN_CHUNK] {
+            glb.extend_from_slice(&word.to_le_bytes());
+        }
+        glb.extend_from_slice(&binary);
+
+        glb
+    }
+
+    /** one triangle with a red material and a camera 5 units in front of it */
+    fn create_triangle_glb(position_count: &str) -> Vec<u8> {
+        let json: String = format!(r#"{{
+            "asset": {{"version": "2.0"}},
+            "scene": 0,
+            "scenes": [{{"nodes": [0, 1]}}],
+            "nodes": [{{"mesh": 0}}, {{"camera": 0, "translation": [0, 0, 5]}}],
+            "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0}}, "indices": 1, "material": 0}}]}}],
+            "materials": [{{"pbrMetallicRoughness": {{"baseColorFactor": [1, 0, 0, 1]}}}}],
+            "cameras": [{{"type": "perspective", "perspective": {{"yfov": 1.0, "znear": 0.1, "zfar": 100}}}}],
+            "accessors": [
+                {{"bufferView": 0, "componentType": 5126, "count": {}, "type": "VEC3"}},
+                {{"bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR"}}
+            ],
+            "bufferViews": [{{"buffer": 0, "byteLength": 36}}, {{"buffer": 0, "byteOffset": 36, "byteLength": 6}}],
+            "buffers": [{{"byteLength": 42}}]
+        }}"#, position_count);
+
+        let positions: [f32; 9] = [0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0];
+        let mut binary: Vec<u8> = positions.iter().flat_map(|value: &f32| value.to_le_bytes()).collect();
+        binary.extend([0u16, 1, 2].iter().flat_map(|index: &u16| index.to_le_bytes()));
+
+        create_glb(&json, &binary)
+    }
+
+    /** one accessor over one buffer view of a 12 byte buffer */
+    fn create_document(accessor: &str, buffer_view: &str) -> (Document, Vec<Option<Vec<u8>>>) {
+        let json: String = format!(
+            r#"{{"asset": {{"version": "2.0"}}, "accessors": [{}], "bufferViews": [{}], "buffers": [{{"byteLength": 12}}]}}"#,
+            accessor, buffer_view
+        );
+
+        (serde_json::from_str(&json).unwrap(), vec![Some(vec![1, 2, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0])])
+    }
+
+    #[test]
+    fn glb_is_imported() {
+        let scene: GltfScene = parse_gltf(&create_triangle_glb("3"), &Display::new(100, 100)).unwrap();
+
+        assert!(scene.warnings.is_empty(), "{:?}", scene.warnings);
+        assert_eq!(scene.roots, vec![0, 1]);
+
+        let mesh: &Mesh = &scene.nodes[0].meshes[0].mesh;
+        /* z is mirrored and the winding swapped */
+        assert_eq!(mesh.points, vec![
+            Point { x: 0.0, y: 0.0, z: -1.0 },
+            Point { x: 1.0, y: 0.0, z: -1.0 },
+            Point { x: 0.0, y: 1.0, z: -1.0 },
+        ]);
+        assert_eq!(mesh.faces.iter().map(|face: &Face| face.vertices).collect::<Vec<[usize; 3]>>(), vec![[0, 2, 1]]);
+        assert_eq!(mesh.faces[0].color, Color::new(255, 0, 0, 1.0));
+
+        assert!(scene.nodes[1].camera.is_some());
+        assert_eq!(scene.nodes[1].transform.translation, Vector::new(0.0, 0.0, -5.0));
+    }
+
+    #[test]
+    fn hostile_accessor_skips_primitive() {
+        let scene: GltfScene = parse_gltf(&create_triangle_glb("18446744073709551615"), &Display::new(100, 100)).unwrap();
+
+        assert!(scene.nodes[0].meshes.is_empty());
+        assert_eq!(scene.warnings, vec![GltfWarning::SkippedPrimitive {
+            mesh: 0,
+            primitive: 0,
+            reason: String::from("accessor 0 exceeds its buffer view")
+        }]);
+    }
+
+    #[test]
+    fn malformed_accessors_are_rejected() {
+        let view: &str = r#"{"buffer": 0, "byteLength": 12}"#;
+        let out_of_bounds: Result<Vec<f32>, AccessorError> = Err(AccessorError::OutOfBounds { accessor: 0 });
+
+        let cases: [(&str, &str); 5] = [
+            /* more elements than the view holds */
+            (r#"{"bufferView": 0, "componentType": 5126, "count": 1000000000000, "type": "VEC3"}"#, view),
+            /* the offset of the last element overflows */
+            (r#"{"bufferView": 0, "componentType": 5126, "count": 18446744073709551615, "type": "VEC3"}"#, view),
+            (r#"{"bufferView": 0, "byteOffset": 18446744073709551615, "componentType": 5126, "count": 1, "type": "VEC3"}"#, view),
+            (r#"{"bufferView": 0, "componentType": 5126, "count": 1, "type": "VEC3"}"#, r#"{"buffer": 0, "byteOffset": 8, "byteLength": 18446744073709551615}"#),
+            /* zeros without a buffer view can't outgrow the file either */
+            (r#"{"componentType": 5126, "count": 1000000000000, "type": "VEC3"}"#, view),
+        ];
+
+        for (accessor, buffer_view) in cases {
+            let (document, buffers): (Document, Vec<Option<Vec<u8>>>) = create_document(accessor, buffer_view);
+
+            assert_eq!(read_accessor(&document, &buffers, 0, "VEC3"), out_of_bounds, "{}", accessor);
+        }
+
+        let (document, buffers): (Document, Vec<Option<Vec<u8>>>) = create_document(r#"{"componentType": 5126, "count": 1, "type": "VEC3"}"#, view);
+        assert_eq!(read_accessor(&document, &buffers, 0, "VEC3"), Ok(vec![0.0; 3]));
+        assert_eq!(read_accessor(&document, &buffers, 0, "VEC2"), Err(AccessorError::WrongType {
+            accessor: 0,
+            found: String::from("VEC3"),
+            expected: "VEC2"
+        }));
+    }
+
+    #[test]
+    fn indices_are_unsigned_integers() {
+        let view: &str = r#"{"buffer": 0, "byteLength": 12}"#;
+
+        let (document, buffers): (Document, Vec<Option<Vec<u8>>>) = create_document(r#"{"bufferView": 0, "componentType": 5121, "count": 3, "type": "SCALAR"}"#, view);
+        assert_eq!(read_indices(&document, &buffers, 0), Ok(vec![1, 2, 3]));
+
+        /* large u32 indices would lose precision as floats */
+        let (document, buffers): (Document, Vec<Option<Vec<u8>>>) = create_document(r#"{"bufferView": 0, "componentType": 5125, "count": 1, "type": "SCALAR"}"#, view);
+        assert_eq!(read_indices(&document, &buffers, 0), Ok(vec![0x0003_0201]));
+
+        for accessor in [
+            r#"{"bufferView": 0, "componentType": 5126, "count": 1, "type": "SCALAR"}"#,
+            r#"{"bufferView": 0, "componentType": 5121, "normalized": true, "count": 1, "type": "SCALAR"}"#,
+        ] {
+            let (document, buffers): (Document, Vec<Option<Vec<u8>>>) = create_document(accessor, view);
+
+            assert_eq!(read_indices(&document, &buffers, 0), Err(AccessorError::InvalidIndices { accessor: 0 }));
+        }
+    }
+
+    /**
+     * One triangle per material: a red image in a data URI sampled nearest and clamped, a green one in the binary chunk,
+     * an external image, the red image with the second texture coordinates and the red image again through another texture.
+     */
+    fn create_textured_glb() -> Vec<u8> {
+        let json: &str = r#"{
+            "asset": {"version": "2.0"},
+            "nodes": [{"mesh": 0}],
+            "meshes": [{"primitives": [
+                {"attributes": {"POSITION": 0}, "material": 0},
+                {"attributes": {"POSITION": 0}, "material": 1},
+                {"attributes": {"POSITION": 0}, "material": 2},
+                {"attributes": {"POSITION": 0}, "material": 3},
+                {"attributes": {"POSITION": 0}, "material": 4}
+            ]}],
+            "materials": [
+                {"pbrMetallicRoughness": {"baseColorTexture": {"index": 0}}},
+                {"pbrMetallicRoughness": {"baseColorTexture": {"index": 1}}},
+                {"pbrMetallicRoughness": {"baseColorTexture": {"index": 2}}},
+                {"pbrMetallicRoughness": {"baseColorTexture": {"index": 0, "texCoord": 1}}},
+                {"pbrMetallicRoughness": {"baseColorTexture": {"index": 3}}}
+            ],
+            "textures": [{"source": 0, "sampler": 0}, {"source": 1}, {"source": 2}, {"source": 0}],
+            "images": [
+                {"uri": "data:image/x-portable-pixmap;base64,UDYgMSAxIDI1NQr/AAA="},
+                {"bufferView": 1, "mimeType": "image/x-portable-pixmap"},
+                {"uri": "texture.png"}
+            ],
+            "samplers": [{"magFilter": 9728, "wrapS": 33071}],
+            "accessors": [{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3"}],
+            "bufferViews": [{"buffer": 0, "byteLength": 36}, {"buffer": 0, "byteOffset": 36, "byteLength": 14}],
+            "buffers": [{"byteLength": 50}]
+        }"#;
+
+        let positions: [f32; 9] = [0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0];
+        let mut binary: Vec<u8> = positions.iter().flat_map(|value: &f32| value.to_le_bytes()).collect();
+        binary.extend(b"P6 1 1 255\n\x00\xff\x00");
+
+        create_glb(json, &binary)
+    }
+
+    #[test]
+    fn base_color_textures_are_imported() {
+        let glb: Vec<u8> = create_textured_glb();
+        let scene: GltfScene = parse_gltf(&glb, &Display::new(100, 100)).unwrap();
+
+        /* the red image is decoded once for both textures using it */
+        assert_eq!(scene.textures, vec![
+            Texture::new(1, 1, vec![255, 0, 0, 255]).unwrap(),
+            Texture::new(1, 1, vec![0, 255, 0, 255]).unwrap(),
+        ]);
+        assert_eq!(scene.warnings, vec![GltfWarning::IgnoredTexture { material: 2 }, GltfWarning::IgnoredTexture { material: 3 }]);
+
+        let meshes: &[GltfMesh] = &scene.nodes[0].meshes;
+        assert_eq!(meshes.iter().map(|mesh: &GltfMesh| mesh.texture).collect::<Vec<Option<usize>>>(), vec![Some(0), Some(1), None, None, Some(0)]);
+        assert_eq!(meshes[0].mesh.sampler, Sampler { filter: Filter::Nearest, wrap: Wrap::Clamp });
+        assert_eq!(meshes[1].mesh.sampler, Sampler::default());
+
+        let mut scene: Scene = Scene::new();
+        let import: GltfImport = scene.load_gltf(&glb, &Display::new(100, 100)).unwrap();
+
+        assert_eq!(import.texture_ids.len(), 2);
+        assert_eq!(scene.get_mesh(import.mesh_ids[1]).unwrap().texture, Some(import.texture_ids[1]));
+        assert_eq!(scene.get_mesh(import.mesh_ids[2]).unwrap().texture, None);
+    }
+
+    #[test]
+    fn invalid_cameras_are_skipped() {
+        let json: &str = r#"{
+            "asset": {"version": "2.0"},
+            "nodes": [{"camera": 0}, {"camera": 1}, {"camera": 2}, {"camera": 3}, {"camera": 4}, {"camera": 5}],
+            "cameras": [
+                {"type": "perspective", "perspective": {"aspectRatio": 2, "yfov": 1.0, "znear": 0.1}},
+                {"type": "perspective", "perspective": {"aspectRatio": 1e30, "yfov": 1.0, "znear": 0.1}},
+                {"type": "perspective", "perspective": {"aspectRatio": -1, "yfov": 1.0, "znear": 0.1}},
+                {"type": "perspective", "perspective": {"yfov": 4.0, "znear": 0.1}},
+                {"type": "perspective", "perspective": {"yfov": 1.0, "znear": 0}},
+                {"type": "perspective", "perspective": {"yfov": 1.0, "znear": 10, "zfar": 5}}
+            ]
+        }"#;
+
+        let scene: GltfScene = parse_gltf(json.as_bytes(), &Display::new(100, 100)).unwrap();
+
+        assert_eq!(scene.nodes[0].camera.as_ref().unwrap().display().width, 200);
+        assert!(scene.nodes[1..].iter().all(|node: &GltfNode| node.camera.is_none()));
+        assert_eq!(scene.warnings, (1..6).map(|camera: usize| GltfWarning::UnsupportedCamera {
+            camera,
+            kind: String::from("perspective")
+        }).collect::<Vec<GltfWarning>>());
+    }
+}
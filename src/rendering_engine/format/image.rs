@@ -0,0 +1,179 @@
+use std::io;
+use std::path::Path;
+
+use crate::rendering_engine::engine::compositor::Image;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    /** binary PPM (P6) */
+    Ppm,
+    /** 4 bytes per pixel, rows from top to bottom, without a header */
+    Rgba,
+}
+
+impl ImageFormat {
+    /** format by the file extension: png, ppm, rgba or raw */
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        let extension: String = path.extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "png" => Some(ImageFormat::Png),
+            "ppm" => Some(ImageFormat::Ppm),
+            "rgba" | "raw" => Some(ImageFormat::Rgba),
+            _ => None
+        }
+    }
+}
+
+pub fn encode_image(image: &Image, format: ImageFormat) -> io::Result<Vec<u8>> {
+    let width: usize = image.width();
+    let height: usize = image.height();
+
+    match format {
+        ImageFormat::Png => {
+            let mut bytes: Vec<u8> = Vec::new();
+
+            let mut encoder: png::Encoder<&mut Vec<u8>> = png::Encoder::new(&mut bytes, width as u32, height as u32);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+
+            let mut writer: png::Writer<&mut Vec<u8>> = encoder.write_header()?;
+            writer.write_image_data(&image.to_rgba8())?;
+            writer.finish()?;
+
+            Ok(bytes)
+        }
+        ImageFormat::Ppm => {
+            let mut bytes: Vec<u8> = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+
+            bytes.extend(image.to_rgb8());
+
+            Ok(bytes)
+        }
+        ImageFormat::Rgba => Ok(image.to_rgba8())
+    }
+}
+
+/** decoded PNG as width, height and 4 bytes per pixel, any color type and bit depth is converted */
+pub fn decode_png(bytes: &[u8]) -> io::Result<(usize, usize, Vec<u8>)> {
+    let mut decoder: png::Decoder<&[u8]> = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+    let mut reader: png::Reader<&[u8]> = decoder.read_info()?;
+    let mut buffer: Vec<u8> = vec![0; reader.output_buffer_size()];
+    let info: png::OutputInfo = reader.next_frame(&mut buffer)?;
+
+    buffer.truncate(info.buffer_size());
+
+    let rgba: Vec<u8> = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer.chunks_exact(3).flat_map(|rgb: &[u8]| [rgb[0], rgb[1], rgb[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => buffer.chunks_exact(2).flat_map(|ga: &[u8]| [ga[0], ga[0], ga[0], ga[1]]).collect(),
+        png::ColorType::Grayscale => buffer.iter().flat_map(|gray: &u8| [*gray, *gray, *gray, 255]).collect(),
+        /* palettes are expanded by the transformations */
+        png::ColorType::Indexed => return Err(io::Error::new(io::ErrorKind::InvalidData, "indexed PNG was not expanded"))
+    };
+
+    Ok((info.width as usize, info.height as usize, rgba))
+}
+
+/** decoded binary (P6) or plain (P3) PPM as width, height and 4 bytes per pixel, samples are scaled to 8 bits */
+pub fn decode_ppm(bytes: &[u8]) -> io::Result<(usize, usize, Vec<u8>)> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, format!("PPM {}", message));
+
+    let binary: bool = match bytes.get(0..2) {
+        Some(b"P6") => true,
+        Some(b"P3") => false,
+        _ => return Err(invalid("signature is not P6 or P3"))
+    };
+
+    let mut position: usize = 2;
+    let mut header: [usize; 3] = [0; 3];
+
+    for value in header.iter_mut() {
+        *value = read_ppm_number(bytes, &mut position).ok_or_else(|| invalid("header is incomplete"))?;
+    }
+
+    let [width, height, max_value]: [usize; 3] = header;
+
+    if width == 0 || height == 0 || !(1..=65535).contains(&max_value) {
+        return Err(invalid("header is invalid"));
+    }
+
+    let sample_count: usize = width.checked_mul(height)
+        .and_then(|pixels: usize| pixels.checked_mul(3))
+        .ok_or_else(|| invalid("image is too large"))?;
+
+    let samples: Vec<usize> = if binary {
+        /* a single whitespace separates the header from the samples, which take 2 bytes above the value of 255 */
+        let sample_size: usize = if max_value > 255 { 2 } else { 1 };
+        let data: &[u8] = bytes.get(position + 1..).unwrap_or_default();
+
+        if data.len() / sample_size < sample_count {
+            return Err(invalid("pixel data is truncated"));
+        }
+
+        data.chunks_exact(sample_size)
+            .take(sample_count)
+            .map(|sample: &[u8]| sample.iter().fold(0, |value: usize, byte: &u8| value << 8 | *byte as usize))
+            .collect()
+    } else {
+        (0..sample_count)
+            .map(|_| read_ppm_number(bytes, &mut position))
+            .collect::<Option<Vec<usize>>>()
+            .ok_or_else(|| invalid("pixel data is truncated"))?
+    };
+
+    let scale = |sample: usize| ((sample.min(max_value) * 255 + max_value / 2) / max_value) as u8;
+
+    let rgba: Vec<u8> = samples
+        .chunks_exact(3)
+        .flat_map(|rgb: &[usize]| [scale(rgb[0]), scale(rgb[1]), scale(rgb[2]), 255])
+        .collect();
+
+    Ok((width, height, rgba))
+}
+
+/** skips whitespace and comments before the number, None at the end of the data or if there is no number */
+fn read_ppm_number(bytes: &[u8], position: &mut usize) -> Option<usize> {
+    loop {
+        match bytes.get(*position)? {
+            b'#' => {
+                while bytes.get(*position).is_some_and(|byte: &u8| *byte != b'\n') {
+                    *position += 1;
+                }
+            }
+            byte if byte.is_ascii_whitespace() => *position += 1,
+            _ => break
+        }
+    }
+
+    let start: usize = *position;
+
+    while bytes.get(*position).is_some_and(u8::is_ascii_digit) {
+        *position += 1;
+    }
+
+    std::str::from_utf8(&bytes[start..*position]).ok()?.parse().ok()
+}
+
+/** PNG or PPM recognized by its signature, see decode_png and decode_ppm */
+pub fn decode_image(bytes: &[u8]) -> io::Result<(usize, usize, Vec<u8>)> {
+    if bytes.starts_with(b"\x89PNG") {
+        decode_png(bytes)
+    } else if bytes.starts_with(b"P6") || bytes.starts_with(b"P3") {
+        decode_ppm(bytes)
+    } else {
+        Err(io::Error::new(io::ErrorKind::InvalidData, "unknown image format, expected PNG or PPM"))
+    }
+}
+
+/** the format is chosen by the file extension */
+pub fn write_image(image: &Image, path: &Path) -> io::Result<()> {
+    let format: ImageFormat = ImageFormat::from_path(path).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("unknown image format of '{}', expected png, ppm, rgba or raw", path.display()))
+    })?;
+
+    std::fs::write(path, encode_image(image, format)?)
+}
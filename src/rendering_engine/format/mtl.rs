@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use crate::rendering_engine::format::{parse_numbers, ParseError};
+use crate::rendering_engine::scene::model::color::Color;
+
+/** part of a Wavefront material the renderer can use */
+#[derive(Copy, Clone)]
+pub struct Material {
+    pub color: Color,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material {
+            color: Color::new(255, 255, 255, 1.0)
+        }
+    }
+}
+
+/**
+ * Parses a Wavefront MTL file into materials by their names.
+ * Diffuse color (Kd) becomes the material color, dissolve (d) or transparency (Tr) becomes its alpha.
+ * Unsupported statements are ignored.
+ */
+pub fn parse_mtl(source: &str) -> Result<HashMap<String, Material>, ParseError> {
+    let mut materials: HashMap<String, Material> = HashMap::new();
+    let mut current: Option<(String, Material)> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number: usize = index + 1;
+        let line: &str = line.split('#').next().unwrap_or("").trim();
+
+        let mut tokens = line.split_whitespace();
+        let keyword: &str = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue
+        };
+        let values: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if values.is_empty() {
+                return Err(ParseError::new(line_number, "material without a name"));
+            }
+
+            if let Some((name, material)) = current.take() {
+                materials.insert(name, material);
+            }
+
+            current = Some((values.join(" "), Material::default()));
+            continue;
+        }
+
+        let material: &mut Material = match current.as_mut() {
+            Some((_, material)) => material,
+            None if is_supported(keyword) => {
+                return Err(ParseError::new(line_number, format!("'{}' before any 'newmtl'", keyword)));
+            }
+            None => continue
+        };
+
+        match keyword {
+            "Kd" => {
+                let rgb: Vec<f32> = parse_numbers(line_number, &values)?;
+
+                if rgb.len() != 3 {
+                    return Err(ParseError::new(line_number, "'Kd' expects 3 values"));
+                }
+
+                material.color = Color::new(to_channel(rgb[0]), to_channel(rgb[1]), to_channel(rgb[2]), material.color.a);
+            }
+            "d" | "Tr" => {
+                let value: f32 = *parse_numbers(line_number, &values)?
+                    .first()
+                    .ok_or_else(|| ParseError::new(line_number, format!("'{}' expects a value", keyword)))?;
+
+                let opacity: f32 = if keyword == "d" { value } else { 1.0 - value };
+
+                material.color.a = opacity.clamp(0.0, 1.0);
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((name, material)) = current {
+        materials.insert(name, material);
+    }
+
+    Ok(materials)
+}
+
+fn is_supported(keyword: &str) -> bool {
+    matches!(keyword, "Kd" | "d" | "Tr")
+}
+
+fn to_channel(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
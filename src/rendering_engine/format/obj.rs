@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+
+use crate::rendering_engine::format::mtl::Material;
+use crate::rendering_engine::format::{parse_numbers, ParseError};
+use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
+use crate::rendering_engine::scene::model_3d::face::Face;
+use crate::rendering_engine::scene::model_3d::mesh::Mesh;
+use crate::rendering_engine::scene::model_3d::point::Point as Point3D;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+/** color of faces without a material or with a material missing from the library */
+pub const DEFAULT_COLOR: Color = Color { r: 200, g: 200, b: 200, a: 1.0 };
+
+/** indices of position, texture coordinate and normal of a face corner, already resolved to zero based ones */
+type CornerIndices = (usize, Option<usize>, Option<usize>);
+
+/** mesh being read, corners sharing all three indices become one mesh point */
+#[derive(Default)]
+struct MeshBuilder {
+    points: Vec<Point3D>,
+    uvs: Vec<Option<Point2D>>,
+    normals: Vec<Option<Vector>>,
+    faces: Vec<Face>,
+    point_indices: HashMap<CornerIndices, usize>,
+}
+
+impl MeshBuilder {
+    fn add_corner(&mut self, corner: CornerIndices, positions: &[Point3D], uvs: &[Point2D], normals: &[Vector]) -> usize {
+        if let Some(point_index) = self.point_indices.get(&corner) {
+            return *point_index;
+        }
+
+        let (position, uv, normal) = corner;
+        let point_index: usize = self.points.len();
+
+        self.points.push(positions[position]);
+        self.uvs.push(uv.map(|uv: usize| uvs[uv]));
+        self.normals.push(normal.map(|normal: usize| normals[normal]));
+        self.point_indices.insert(corner, point_index);
+
+        point_index
+    }
+
+    /** None if no faces were read */
+    fn build(self) -> Option<Mesh> {
+        if self.faces.is_empty() {
+            return None;
+        }
+
+        let mut mesh: Mesh = Mesh::new(self.points, self.faces);
+
+        /* attributes are kept only if some corner has them, missing ones are filled with defaults */
+        if self.uvs.iter().any(Option::is_some) {
+            mesh.uvs = self.uvs
+                .iter()
+                .map(|uv: &Option<Point2D>| uv.unwrap_or(Point2D { x: 0.0, y: 0.0 }))
+                .collect();
+        }
+
+        if self.normals.iter().any(Option::is_some) {
+            mesh.normals = self.normals
+                .iter()
+                .map(|normal: &Option<Vector>| normal.unwrap_or(Vector::zero()))
+                .collect();
+        }
+
+        Some(mesh)
+    }
+}
+
+/**
+ * Parses a Wavefront OBJ file, every object (o) or group (g) becomes a separate mesh.
+ * Faces with more than three corners are split into triangles sharing the first corner,
+ * their color comes from the material chosen by 'usemtl'.
+ * Statements which don't affect triangles (lines, points, smoothing groups, material libraries) are ignored.
+ * OBJ is right handed like glTF, z is mirrored into the left handed space of the renderer the same way as for glTF.
+ */
+pub fn parse_obj(source: &str, materials: &HashMap<String, Material>) -> Result<Vec<Mesh>, ParseError> {
+    let mut positions: Vec<Point3D> = Vec::new();
+    let mut uvs: Vec<Point2D> = Vec::new();
+    let mut normals: Vec<Vector> = Vec::new();
+
+    let mut meshes: Vec<Mesh> = Vec::new();
+    let mut builder: MeshBuilder = MeshBuilder::default();
+    let mut color: Color = DEFAULT_COLOR;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number: usize = index + 1;
+        let line: &str = line.split('#').next().unwrap_or("").trim();
+
+        let mut tokens = line.split_whitespace();
+        let keyword: &str = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue
+        };
+        let values: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => {
+                let coordinates: Vec<f32> = parse_numbers(line_number, &values)?;
+
+                if coordinates.len() < 3 {
+                    return Err(ParseError::new(line_number, "vertex expects 3 coordinates"));
+                }
+
+                positions.push(Point3D { x: coordinates[0], y: coordinates[1], z: -coordinates[2] });
+            }
+            "vt" => {
+                let coordinates: Vec<f32> = parse_numbers(line_number, &values)?;
+
+                if coordinates.is_empty() {
+                    return Err(ParseError::new(line_number, "texture coordinate expects at least 1 value"));
+                }
+
+                /* v goes up from the bottom of the image in OBJ, textures have (0, 0) at the top left corner */
+                uvs.push(Point2D { x: coordinates[0], y: 1.0 - coordinates.get(1).copied().unwrap_or(0.0) });
+            }
+            "vn" => {
+                let coordinates: Vec<f32> = parse_numbers(line_number, &values)?;
+
+                if coordinates.len() < 3 {
+                    return Err(ParseError::new(line_number, "normal expects 3 coordinates"));
+                }
+
+                normals.push(Vector::new(coordinates[0], coordinates[1], -coordinates[2]).normalize());
+            }
+            "f" => {
+                if values.len() < 3 {
+                    return Err(ParseError::new(line_number, "face expects at least 3 corners"));
+                }
+
+                let corners: Vec<usize> = values
+                    .iter()
+                    .map(|corner: &&str| {
+                        let indices: CornerIndices = parse_corner(line_number, corner, positions.len(), uvs.len(), normals.len())?;
+
+                        Ok(builder.add_corner(indices, &positions, &uvs, &normals))
+                    })
+                    .collect::<Result<Vec<usize>, ParseError>>()?;
+
+                /* mirroring z flips the winding, swapping two corners restores it */
+                for index in 1..corners.len() - 1 {
+                    builder.faces.push(Face::new([corners[0], corners[index + 1], corners[index]], color));
+                }
+            }
+            "o" | "g" => {
+                meshes.extend(std::mem::take(&mut builder).build());
+            }
+            "usemtl" => {
+                color = materials
+                    .get(&values.join(" "))
+                    .map(|material: &Material| material.color)
+                    .unwrap_or(DEFAULT_COLOR);
+            }
+            "vp" | "l" | "p" | "s" | "mtllib" | "cstype" | "deg" | "curv" | "curv2" | "surf" | "parm" | "end" => {}
+            _ => return Err(ParseError::new(line_number, format!("unknown statement '{}'", keyword)))
+        }
+    }
+
+    meshes.extend(builder.build());
+
+    Ok(meshes)
+}
+
+/** names of material libraries referenced by 'mtllib' statements */
+pub fn material_libraries(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter_map(|line: &str| line.trim().strip_prefix("mtllib "))
+        .flat_map(|names: &str| names.split_whitespace().map(String::from).collect::<Vec<String>>())
+        .collect()
+}
+
+/** 'v', 'v/vt', 'v//vn' or 'v/vt/vn', negative indices count from the last element read so far */
+fn parse_corner(line: usize,
+                corner: &str,
+                positions_count: usize,
+                uvs_count: usize,
+                normals_count: usize) -> Result<CornerIndices, ParseError> {
+
+    let mut parts = corner.split('/');
+
+    let position: usize = match parts.next() {
+        Some(position) if !position.is_empty() => resolve_index(line, position, positions_count, "vertex")?,
+        _ => return Err(ParseError::new(line, format!("face corner '{}' has no vertex index", corner)))
+    };
+
+    let uv: Option<usize> = match parts.next() {
+        Some(uv) if !uv.is_empty() => Some(resolve_index(line, uv, uvs_count, "texture coordinate")?),
+        _ => None
+    };
+
+    let normal: Option<usize> = match parts.next() {
+        Some(normal) if !normal.is_empty() => Some(resolve_index(line, normal, normals_count, "normal")?),
+        _ => None
+    };
+
+    if parts.next().is_some() {
+        return Err(ParseError::new(line, format!("face corner '{}' has too many indices", corner)));
+    }
+
+    Ok((position, uv, normal))
+}
+
+fn resolve_index(line: usize, value: &str, count: usize, kind: &str) -> Result<usize, ParseError> {
+    let index: i64 = value
+        .parse::<i64>()
+        .map_err(|_| ParseError::new(line, format!("invalid {} index '{}'", kind, value)))?;
+
+    let resolved: i64 = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(ParseError::new(line, format!("{} index {} is out of range, {} defined so far", kind, index, count)));
+    }
+
+    Ok(resolved as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
+
+    use crate::rendering_engine::format::gltf::{parse_gltf, GltfScene};
+    use crate::rendering_engine::format::mtl::{parse_mtl, Material};
+    use crate::rendering_engine::format::obj::{parse_obj, DEFAULT_COLOR};
+    use crate::rendering_engine::format::ParseError;
+    use crate::rendering_engine::scene::camera::display::Display;
+    use crate::rendering_engine::scene::model::color::Color;
+    use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
+    use crate::rendering_engine::scene::model_3d::face::Face;
+    use crate::rendering_engine::scene::model_3d::mesh::Mesh;
+    use crate::rendering_engine::scene::model_3d::point::Point as Point3D;
+    use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+    /** the only mesh of the file */
+    fn parse_mesh(source: &str) -> Mesh {
+        let mut meshes: Vec<Mesh> = parse_obj(source, &HashMap::new()).unwrap();
+        assert_eq!(meshes.len(), 1);
+
+        meshes.remove(0)
+    }
+
+    fn face_vertices(mesh: &Mesh) -> Vec<[usize; 3]> {
+        mesh.faces.iter().map(|face: &Face| face.vertices).collect()
+    }
+
+    fn parse_error(source: &str) -> ParseError {
+        match parse_obj(source, &HashMap::new()) {
+            Ok(_) => panic!("{:?} was parsed", source),
+            Err(error) => error
+        }
+    }
+
+    #[test]
+    fn quad_is_split_into_two_triangles() {
+        /* counterclockwise seen from +z, the front side faces +z in the right handed OBJ space */
+        let mesh: Mesh = parse_mesh("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n");
+
+        assert_eq!(face_vertices(&mesh), vec![[0, 2, 1], [0, 3, 2]]);
+
+        /* which is -z after mirroring */
+        for face in &mesh.faces {
+            let [a, b, c]: [Point3D; 3] = face.vertices.map(|index: usize| mesh.points[index]);
+            assert!((b - a).cross(&(c - a)).z < 0.0);
+        }
+    }
+
+    #[test]
+    fn negative_indices_count_from_the_end() {
+        let relative: Mesh = parse_mesh("v 5 5 5\nv 0 0 1\nv 1 0 1\nv 0 1 1\nf -3 -2 -1\n");
+        let absolute: Mesh = parse_mesh("v 5 5 5\nv 0 0 1\nv 1 0 1\nv 0 1 1\nf 2 3 4\n");
+
+        assert_eq!(relative.points, absolute.points);
+        assert_eq!(face_vertices(&relative), face_vertices(&absolute));
+        assert_eq!(relative.points[0], Point3D { x: 0.0, y: 0.0, z: -1.0 });
+    }
+
+    #[test]
+    fn corner_forms() {
+        let source: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0 1\nvn 0 0 2\n";
+
+        let full: Mesh = parse_mesh(&format!("{}f 1/1/1 2/2/1 3/3/1\n", source));
+        let uvs: Vec<(f32, f32)> = full.uvs.iter().map(|uv: &Point2D| (uv.x, uv.y)).collect();
+        /* v is flipped to the top left origin of textures */
+        assert_eq!(uvs, vec![(0.0, 1.0), (1.0, 1.0), (0.0, 0.0)]);
+        /* normalized and mirrored */
+        assert_eq!(full.normals, vec![Vector::new(0.0, 0.0, -1.0); 3]);
+
+        let uvs_only: Mesh = parse_mesh(&format!("{}f 1/1 2/2 3/3\n", source));
+        assert_eq!(uvs_only.uvs.len(), 3);
+        assert!(uvs_only.normals.is_empty());
+
+        let normals_only: Mesh = parse_mesh(&format!("{}f 1//1 2//1 3//1\n", source));
+        assert!(normals_only.uvs.is_empty());
+        assert_eq!(normals_only.normals.len(), 3);
+
+        /* the same position with different texture coordinates becomes two points */
+        let split: Mesh = parse_mesh(&format!("{}f 1/1 2/2 3/3\nf 1/2 3/3 2/2\n", source));
+        assert_eq!(split.points.len(), 4);
+        assert_eq!(face_vertices(&split), vec![[0, 2, 1], [3, 1, 2]]);
+    }
+
+    #[test]
+    fn material_colors_faces() {
+        let materials: HashMap<String, Material> = parse_mtl("newmtl red\nKd 1 0 0\nd 0.5\n").unwrap();
+        let source: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nusemtl red\nf 1 2 3\nusemtl missing\nf 1 2 3\n";
+
+        let meshes: Vec<Mesh> = parse_obj(source, &materials).unwrap();
+        let colors: Vec<Color> = meshes[0].faces.iter().map(|face: &Face| face.color).collect();
+
+        assert_eq!(colors, vec![DEFAULT_COLOR, Color::new(255, 0, 0, 0.5), DEFAULT_COLOR]);
+    }
+
+    #[test]
+    fn errors_have_line_numbers() {
+        let malformed: ParseError = parse_error("v 0 0 0\nv 1 x 0\n");
+        assert_eq!(malformed.line, 2);
+        assert!(malformed.message.contains("'x'"), "{}", malformed.message);
+
+        assert_eq!(parse_error("v 0 0 0\n\n# comment\nv 1 0\n").line, 4);
+
+        let out_of_range: ParseError = parse_error("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nf 1 2 4\n");
+        assert_eq!(out_of_range.line, 5);
+        assert!(out_of_range.message.contains("out of range"), "{}", out_of_range.message);
+
+        assert_eq!(parse_error("v 0 0 0\nv 1 0 0\nf 1 2 -3\n").line, 3);
+        assert_eq!(parse_error("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1/1 2 3\n").line, 4);
+    }
+
+    #[test]
+    fn obj_matches_gltf() {
+        let positions: [f32; 9] = [0.0, 0.0, 1.0, 1.0, 0.0, 2.0, 0.0, 1.0, 3.0];
+        let bytes: Vec<u8> = positions.iter().flat_map(|value: &f32| value.to_le_bytes()).collect();
+
+        let gltf: String = format!(
+            r#"{{
+                "asset": {{"version": "2.0"}},
+                "buffers": [{{"byteLength": 36, "uri": "data:application/octet-stream;base64,{}"}}],
+                "bufferViews": [{{"buffer": 0, "byteLength": 36}}],
+                "accessors": [{{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3"}}],
+                "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0}}}}]}}],
+                "nodes": [{{"mesh": 0}}]
+            }}"#,
+            BASE64.encode(&bytes)
+        );
+
+        let scene: GltfScene = parse_gltf(gltf.as_bytes(), &Display::new(100, 100)).unwrap();
+        let gltf_mesh: &Mesh = &scene.nodes[0].meshes[0].mesh;
+
+        let obj_mesh: Mesh = parse_mesh("v 0 0 1\nv 1 0 2\nv 0 1 3\nf 1 2 3\n");
+
+        assert_eq!(obj_mesh.points, gltf_mesh.points);
+        assert_eq!(face_vertices(&obj_mesh), face_vertices(gltf_mesh));
+    }
+}
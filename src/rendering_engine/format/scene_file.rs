@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rendering_engine::format::ParseError;
+use crate::rendering_engine::scene::Scene;
+
+/**
+ * Version written to new files.
+ * Version 2 added the camera projection, version 3 the lights, version 4 the mesh shading, version 5 the mesh culling,
+ * version 6 the textures and version 7 the nodes of the lights. JSON files of older versions are still read, fields added since have defaults,
+ * bincode is not self-describing, so binary files have to be of the current version.
+ */
+pub const SCENE_FILE_VERSION: u32 = 7;
+
+const OLDEST_JSON_VERSION: u32 = 1;
+
+const JSON_FORMAT_NAME: &str = "simple-3D-renderer scene";
+const BINARY_MAGIC: &[u8; 8] = b"S3DSCENE";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SceneFileFormat {
+    /** readable, for files kept in version control */
+    Json,
+    /** magic bytes, little endian version and the bincode encoded scene */
+    Binary,
+}
+
+#[derive(Serialize)]
+struct JsonSceneFile<'a> {
+    format: &'a str,
+    version: u32,
+    scene: &'a Scene,
+}
+
+/** read before the scene, so files of other versions fail with a version error instead of a field error */
+#[derive(Deserialize)]
+struct JsonHeader {
+    format: Option<String>,
+    version: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct JsonSceneContent {
+    scene: Scene,
+}
+
+pub fn write_scene(scene: &Scene, format: SceneFileFormat) -> Vec<u8> {
+    match format {
+        SceneFileFormat::Json => {
+            let file: JsonSceneFile = JsonSceneFile {
+                format: JSON_FORMAT_NAME,
+                version: SCENE_FILE_VERSION,
+                scene
+            };
+
+            serde_json::to_vec_pretty(&file).unwrap()
+        }
+        SceneFileFormat::Binary => {
+            let mut bytes: Vec<u8> = BINARY_MAGIC.to_vec();
+
+            bytes.extend_from_slice(&SCENE_FILE_VERSION.to_le_bytes());
+            bytes.extend(bincode::serialize(scene).unwrap());
+
+            bytes
+        }
+    }
+}
+
+/** the format is detected from the content */
+pub fn read_scene(bytes: &[u8]) -> Result<Scene, ParseError> {
+    let scene: Scene = if bytes.starts_with(BINARY_MAGIC) {
+        read_binary(&bytes[BINARY_MAGIC.len()..])?
+    } else {
+        read_json(bytes)?
+    };
+
+    scene
+        .check_references()
+        .map_err(|message: String| ParseError::new(0, format!("inconsistent scene: {}", message)))?;
+
+    Ok(scene)
+}
+
+fn read_json(bytes: &[u8]) -> Result<Scene, ParseError> {
+    let json_error = |error: serde_json::Error| ParseError::new(error.line(), format!("invalid scene JSON: {}", error));
+
+    let header: JsonHeader = serde_json::from_slice(bytes).map_err(json_error)?;
+
+    if header.format.as_deref() != Some(JSON_FORMAT_NAME) {
+        return Err(ParseError::new(0, format!("not a scene file, expected format \"{}\"", JSON_FORMAT_NAME)));
+    }
+
+    let version: u32 = header.version.ok_or_else(|| ParseError::new(0, "scene file has no version"))?;
+    check_version(version, OLDEST_JSON_VERSION)?;
+
+    let content: JsonSceneContent = serde_json::from_slice(bytes).map_err(json_error)?;
+
+    Ok(content.scene)
+}
+
+fn read_binary(bytes: &[u8]) -> Result<Scene, ParseError> {
+    let version: [u8; 4] = bytes
+        .get(..4)
+        .and_then(|version: &[u8]| version.try_into().ok())
+        .ok_or_else(|| ParseError::new(0, "binary scene file is truncated"))?;
+
+    check_version(u32::from_le_bytes(version), SCENE_FILE_VERSION)?;
+
+    bincode::deserialize(&bytes[4..])
+        .map_err(|error: bincode::Error| ParseError::new(0, format!("invalid binary scene: {}", error)))
+}
+
+fn check_version(version: u32, oldest_version: u32) -> Result<(), ParseError> {
+    if version > SCENE_FILE_VERSION {
+        Err(ParseError::new(0, format!(
+            "scene file version {} is newer than the supported version {}, update the renderer", version, SCENE_FILE_VERSION
+        )))
+    } else if version < oldest_version {
+        Err(ParseError::new(0, format!(
+            "scene file version {} is no longer supported, the current version is {}", version, SCENE_FILE_VERSION
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::format::scene_file::{SceneFileFormat, SCENE_FILE_VERSION};
+    use crate::rendering_engine::scene::camera::display::Display;
+    use crate::rendering_engine::scene::camera::{Camera, Projection};
+    use crate::rendering_engine::scene::model::color::Color;
+    use crate::rendering_engine::scene::model::texture::{Filter, Sampler, Texture, Wrap};
+    use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
+    use crate::rendering_engine::scene::model_3d::point::Point;
+    use crate::rendering_engine::scene::model_3d::transform::Transform;
+    use crate::rendering_engine::scene::model_3d::vector::Vector;
+    use crate::rendering_engine::scene::{CameraID, MeshID, NodeID, Scene, TextureID};
+
+    fn create_scene() -> (Scene, CameraID, MeshID, NodeID) {
+        let mut scene: Scene = Scene::new();
+
+        let camera_id: CameraID = scene.add_camera(Camera::new(800.0, Point { x: 1.0, y: 2.0, z: 3.0 }, 10.0, 20.0, 0.0, Display::new(320, 240)));
+        let mesh_id: MeshID = scene.add_cube(Point { x: 0.0, y: 0.0, z: 300.0 }, 100.0, 100.0, 100.0, Color::new(255, 0, 0, 0.5)).unwrap();
+        let node_id: NodeID = scene.add_node(None, Transform::from_translation(Vector::new(5.0, 0.0, 0.0))).unwrap();
+
+        scene.attach_mesh(mesh_id, Some(node_id));
+
+        (scene, camera_id, mesh_id, node_id)
+    }
+
+    fn assert_restored(restored: &Scene, original: &Scene, camera_id: CameraID, mesh_id: MeshID, node_id: NodeID) {
+        let camera: &Camera = restored.get_camera(camera_id).unwrap();
+        assert_eq!(camera.center(), original.get_camera(camera_id).unwrap().center());
+        assert_eq!(camera.orientation(), original.get_camera(camera_id).unwrap().orientation());
+        assert_eq!(camera.display().width, 320);
+
+        let mesh = restored.get_mesh(mesh_id).unwrap();
+        assert_eq!(mesh.points, original.get_mesh(mesh_id).unwrap().points);
+        assert_eq!(mesh.transform, original.get_mesh(mesh_id).unwrap().transform);
+        assert_eq!(mesh.faces[0].color.a, 0.5);
+
+        assert_eq!(restored.get_mesh_node(mesh_id), Some(node_id));
+        assert_eq!(restored.node_world_matrix(node_id), original.node_world_matrix(node_id));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let (scene, camera_id, mesh_id, node_id) = create_scene();
+
+        let restored: Scene = Scene::load(&scene.save(SceneFileFormat::Json)).unwrap();
+
+        assert_restored(&restored, &scene, camera_id, mesh_id, node_id);
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let (scene, camera_id, mesh_id, node_id) = create_scene();
+
+        let bytes: Vec<u8> = scene.save(SceneFileFormat::Binary);
+        let restored: Scene = Scene::load(&bytes).unwrap();
+
+        assert!(bytes.len() < scene.save(SceneFileFormat::Json).len());
+        assert_restored(&restored, &scene, camera_id, mesh_id, node_id);
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let (scene, ..) = create_scene();
+
+        let json: String = String::from_utf8(scene.save(SceneFileFormat::Json)).unwrap();
+        let newer: String = json.replacen(&format!("\"version\": {}", SCENE_FILE_VERSION), &format!("\"version\": {}", SCENE_FILE_VERSION + 1), 1);
+        assert!(Scene::load(newer.as_bytes()).err().unwrap().message.contains("newer"));
+
+        let mut binary: Vec<u8> = scene.save(SceneFileFormat::Binary);
+        binary[8..12].copy_from_slice(&(SCENE_FILE_VERSION - 1).to_le_bytes());
+        assert!(Scene::load(&binary).err().unwrap().message.contains("no longer supported"));
+
+        let older: String = json.replacen(&format!("\"version\": {}", SCENE_FILE_VERSION), "\"version\": 0", 1);
+        assert!(Scene::load(older.as_bytes()).err().unwrap().message.contains("no longer supported"));
+    }
+
+    #[test]
+    fn version_1_json_gets_perspective_cameras() {
+        let (mut scene, camera_id, ..) = create_scene();
+        scene.get_camera_mut(camera_id).unwrap().set_projection(Projection::Orthographic { scale: 2.0 });
+
+        let mut json: serde_json::Value = serde_json::from_slice(&scene.save(SceneFileFormat::Json)).unwrap();
+        json["version"] = serde_json::Value::from(1);
+        json["scene"]["cameras"][camera_id.to_string()].as_object_mut().unwrap().remove("projection").unwrap();
+
+        let restored: Scene = Scene::load(json.to_string().as_bytes()).unwrap();
+        assert_eq!(restored.get_camera(camera_id).unwrap().projection(), Projection::Perspective);
+
+        let saved: Scene = Scene::load(&scene.save(SceneFileFormat::Binary)).unwrap();
+        assert_eq!(saved.get_camera(camera_id).unwrap().projection(), Projection::Orthographic { scale: 2.0 });
+    }
+
+    #[test]
+    fn textures_are_saved_and_checked() {
+        let (mut scene, _, mesh_id, _) = create_scene();
+        let sampler: Sampler = Sampler { filter: Filter::Nearest, wrap: Wrap::Mirror };
+
+        let texture_id: TextureID = scene.add_texture(Texture::new(1, 1, vec![1, 2, 3, 255]).unwrap());
+        scene.set_mesh_uvs(mesh_id, vec![Point2D { x: 0.0, y: 0.0 }; 8]).unwrap();
+        scene.set_mesh_texture(mesh_id, Some(texture_id), sampler).unwrap();
+
+        let restored: Scene = Scene::load(&scene.save(SceneFileFormat::Binary)).unwrap();
+        assert_eq!(restored.get_texture(texture_id), scene.get_texture(texture_id));
+        assert_eq!(restored.get_mesh(mesh_id).unwrap().texture, Some(texture_id));
+        assert_eq!(restored.get_mesh(mesh_id).unwrap().sampler, sampler);
+
+        let mut json: serde_json::Value = serde_json::from_slice(&scene.save(SceneFileFormat::Json)).unwrap();
+        json["scene"]["textures"][texture_id.to_string()]["rgba"].as_array_mut().unwrap().pop();
+        assert!(Scene::load(json.to_string().as_bytes()).err().unwrap().message.contains("inconsistent"));
+    }
+
+    #[test]
+    fn invalid_files_are_rejected() {
+        assert!(Scene::load(b"{\"version\": 1}").is_err());
+        assert!(Scene::load(b"S3DSCENE\x01\x00\x00\x00garbage").is_err());
+
+        let (scene, _, mesh_id, _) = create_scene();
+        let json: String = String::from_utf8(scene.save(SceneFileFormat::Json)).unwrap();
+        /* the last occurrence is the attachment of the mesh to the node */
+        let attachment: usize = json.rfind(&mesh_id.to_string()).unwrap();
+        let dangling: String = format!("{}{}{}", &json[..attachment], uuid::Uuid::new_v4(), &json[attachment + 36..]);
+
+        assert!(Scene::load(dangling.as_bytes()).err().unwrap().message.contains("inconsistent"));
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+
+use crate::rendering_engine::format::{bytes_to_text, parse_numbers, ParseError};
+use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model_3d::face::Face;
+use crate::rendering_engine::scene::model_3d::mesh::Mesh;
+use crate::rendering_engine::scene::model_3d::point::Point;
+use crate::rendering_engine::scene::model_3d::triangle::{Triangle, TriangleVertices};
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+const HEADER_SIZE: usize = 80;
+const TRIANGLE_SIZE: usize = 50;
+const HEADER: &[u8] = b"binary STL exported by simple-3D-renderer";
+
+/**
+ * Parses an ASCII or a binary STL file into a single mesh of the given color.
+ * Equal vertices of different triangles are merged into one point, so faces share their indices.
+ * Stored facet normals are ignored, STL files don't define them reliably.
+ *
+ * STL is right-handed like glTF and OBJ while the scene is left-handed, so z coordinates are negated
+ * and the triangle winding is reversed to keep faces pointing the same way.
+ */
+pub fn parse_stl(bytes: &[u8], color: Color) -> Result<Mesh, ParseError> {
+    let triangles: Vec<TriangleVertices> = if is_binary(bytes) {
+        parse_binary(bytes)?
+    } else if bytes.trim_ascii_start().starts_with(b"solid") {
+        parse_ascii(bytes_to_text(bytes)?)?
+    } else {
+        return Err(ParseError::new(0, "not an STL file"));
+    };
+
+    let triangles: Vec<TriangleVertices> = triangles.into_iter().map(mirror).collect();
+
+    Ok(merge_vertices(&triangles, color))
+}
+
+/** binary STL of the mesh points, the mesh transform is not applied, z is mirrored back like in parse_stl */
+pub fn write_stl(mesh: &Mesh) -> Vec<u8> {
+    let triangles: Vec<Triangle> = mesh.triangulate();
+
+    let mut bytes: Vec<u8> = Vec::with_capacity(HEADER_SIZE + 4 + triangles.len() * TRIANGLE_SIZE);
+
+    bytes.extend_from_slice(HEADER);
+    bytes.resize(HEADER_SIZE, 0);
+    bytes.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+
+    for triangle in &triangles {
+        let [a, b, c] = mirror(triangle.vertices());
+        let normal: Vector = (b - a).cross(&(c - a)).normalize();
+
+        for value in [normal.x, normal.y, normal.z] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        for vertex in [a, b, c] {
+            for value in [vertex.x, vertex.y, vertex.z] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        /* attribute byte count, unused */
+        bytes.extend_from_slice(&[0, 0]);
+    }
+
+    bytes
+}
+
+/**
+ * ASCII files start with "solid" too, binary ones are recognized by the size
+ * which must match the triangle count stored after the header.
+ * The size is computed with overflow checks, on 32 bit targets a huge count could otherwise wrap around to the file size.
+ */
+fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.len() < HEADER_SIZE + 4 {
+        return false;
+    }
+
+    let count: usize = read_u32(bytes, HEADER_SIZE) as usize;
+
+    count
+        .checked_mul(TRIANGLE_SIZE)
+        .and_then(|size: usize| size.checked_add(HEADER_SIZE + 4))
+        == Some(bytes.len())
+}
+
+/** the size of the file has to be checked by is_binary, so every triangle is within the bytes */
+fn parse_binary(bytes: &[u8]) -> Result<Vec<TriangleVertices>, ParseError> {
+    let count: usize = read_u32(bytes, HEADER_SIZE) as usize;
+
+    let triangles: Vec<TriangleVertices> = (0..count)
+        .map(|index: usize| {
+            /* the normal takes the first 12 bytes of a triangle */
+            let start: usize = HEADER_SIZE + 4 + index * TRIANGLE_SIZE + 12;
+
+            let vertex = |offset: usize| -> Point {
+                Point {
+                    x: read_f32(bytes, start + offset),
+                    y: read_f32(bytes, start + offset + 4),
+                    z: read_f32(bytes, start + offset + 8)
+                }
+            };
+
+            [vertex(0), vertex(12), vertex(24)]
+        })
+        .collect();
+
+    if let Some(index) = triangles.iter().position(|triangle: &TriangleVertices| {
+        triangle.iter().any(|vertex: &Point| !vertex.x.is_finite() || !vertex.y.is_finite() || !vertex.z.is_finite())
+    }) {
+        return Err(ParseError::new(0, format!("triangle {} has a non finite coordinate", index)));
+    }
+
+    Ok(triangles)
+}
+
+fn parse_ascii(source: &str) -> Result<Vec<TriangleVertices>, ParseError> {
+    let mut triangles: Vec<TriangleVertices> = Vec::new();
+    let mut vertices: Vec<Point> = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number: usize = index + 1;
+
+        let mut tokens = line.split_whitespace();
+        let keyword: &str = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue
+        };
+        let values: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "vertex" => {
+                let coordinates: Vec<f32> = parse_numbers(line_number, &values)?;
+
+                if coordinates.len() != 3 {
+                    return Err(ParseError::new(line_number, "vertex expects 3 coordinates"));
+                }
+
+                vertices.push(Point { x: coordinates[0], y: coordinates[1], z: coordinates[2] });
+            }
+            "endloop" => {
+                if vertices.len() != 3 {
+                    return Err(ParseError::new(line_number, format!("facet has {} vertices, expected 3", vertices.len())));
+                }
+
+                triangles.push([vertices[0], vertices[1], vertices[2]]);
+                vertices.clear();
+            }
+            "solid" | "facet" | "outer" | "endfacet" | "endsolid" => {}
+            _ => return Err(ParseError::new(line_number, format!("unknown statement '{}'", keyword)))
+        }
+    }
+
+    if !vertices.is_empty() {
+        return Err(ParseError::new(0, "last facet is not closed"));
+    }
+
+    Ok(triangles)
+}
+
+/** mirrors z between the right-handed file and the left-handed scene, swapping two vertices restores the winding */
+fn mirror([a, b, c]: TriangleVertices) -> TriangleVertices {
+    [a, c, b].map(|vertex: Point| Point { z: -vertex.z, ..vertex })
+}
+
+fn merge_vertices(triangles: &[TriangleVertices], color: Color) -> Mesh {
+    let mut points: Vec<Point> = Vec::new();
+    let mut point_indices: HashMap<[u32; 3], usize> = HashMap::new();
+
+    let faces: Vec<Face> = triangles
+        .iter()
+        .map(|triangle: &TriangleVertices| {
+            let vertices: [usize; 3] = triangle.map(|vertex: Point| {
+                /* 0.0 is added to turn -0.0 into 0.0, they are the same position */
+                let key: [u32; 3] = [(vertex.x + 0.0).to_bits(), (vertex.y + 0.0).to_bits(), (vertex.z + 0.0).to_bits()];
+
+                *point_indices.entry(key).or_insert_with(|| {
+                    points.push(vertex);
+                    points.len() - 1
+                })
+            });
+
+            Face::new(vertices, color)
+        })
+        .collect();
+
+    Mesh::new(points, faces)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+fn read_f32(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_bits(read_u32(bytes, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::format::stl::{parse_stl, write_stl};
+    use crate::rendering_engine::scene::model::color::Color;
+    use crate::rendering_engine::scene::model_3d::mesh::Mesh;
+    use crate::rendering_engine::scene::model_3d::point::Point;
+    use crate::rendering_engine::scene::model_3d::triangle::{Triangle, TriangleVertices};
+    use crate::rendering_engine::scene::Scene;
+
+    const COLOR: Color = Color { r: 10, g: 20, b: 30, a: 1.0 };
+
+    const SQUARE: &str = "solid square
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 0 1 0
+      vertex 1 1 0
+    endloop
+  endfacet
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 1 0 0
+    endloop
+  endfacet
+endsolid square
+";
+
+    fn vertices(mesh: &Mesh) -> Vec<TriangleVertices> {
+        mesh.triangulate()
+            .iter()
+            .map(Triangle::vertices)
+            .collect()
+    }
+
+    #[test]
+    fn ascii_vertices_are_merged() {
+        let mesh: Mesh = parse_stl(SQUARE.as_bytes(), COLOR).unwrap();
+
+        assert_eq!(mesh.points.len(), 4);
+        assert_eq!(mesh.faces.len(), 2);
+        assert_eq!(mesh.faces[0].vertices[0], mesh.faces[1].vertices[0]);
+        assert_eq!(mesh.faces[0].vertices[1], mesh.faces[1].vertices[2]);
+        /* z is mirrored and the winding swapped */
+        assert_eq!(vertices(&mesh)[1], [
+            Point { x: 0.0, y: 0.0, z: 0.0 },
+            Point { x: 1.0, y: 0.0, z: 0.0 },
+            Point { x: 1.0, y: 1.0, z: 0.0 }
+        ]);
+    }
+
+    #[test]
+    fn ascii_round_trip() {
+        let mesh: Mesh = parse_stl(b"solid a\nouter loop\nvertex 0 0 1\nvertex 0 1 2\nvertex 1 1 3\nendloop\nendsolid a\n", COLOR).unwrap();
+        assert_eq!(vertices(&mesh)[0], [
+            Point { x: 0.0, y: 0.0, z: -1.0 },
+            Point { x: 1.0, y: 1.0, z: -3.0 },
+            Point { x: 0.0, y: 1.0, z: -2.0 }
+        ]);
+
+        let bytes: Vec<u8> = write_stl(&mesh);
+
+        /* the export is back in the space and the winding of the file, after the 12 bytes of the normal */
+        let written: Vec<f32> = bytes[96..132]
+            .chunks(4)
+            .map(|value: &[u8]| f32::from_le_bytes([value[0], value[1], value[2], value[3]]))
+            .collect();
+        assert_eq!(written, vec![0.0, 0.0, 1.0, 0.0, 1.0, 2.0, 1.0, 1.0, 3.0]);
+
+        let restored: Mesh = parse_stl(&bytes, COLOR).unwrap();
+
+        assert_eq!(vertices(&restored), vertices(&mesh));
+        assert_eq!(restored.points.len(), mesh.points.len());
+    }
+
+    #[test]
+    fn cube_round_trip() {
+        let mut scene: Scene = Scene::new();
+        let mesh_id = scene.add_cube(Point { x: 0.0, y: 0.0, z: 0.0 }, 2.0, 4.0, 6.0, COLOR).unwrap();
+        let mesh: &Mesh = scene.get_mesh(mesh_id).unwrap();
+
+        let bytes: Vec<u8> = scene.export_stl(mesh_id).unwrap();
+        assert_eq!(bytes.len(), 80 + 4 + 50 * mesh.faces.len());
+
+        let restored: Mesh = parse_stl(&bytes, COLOR).unwrap();
+
+        assert_eq!(vertices(&restored), vertices(mesh));
+        assert_eq!(restored.points.len(), 8);
+        assert_eq!(write_stl(&restored), bytes);
+    }
+
+    #[test]
+    fn binary_header_starting_with_solid() {
+        let mesh: Mesh = parse_stl(SQUARE.as_bytes(), COLOR).unwrap();
+
+        let mut bytes: Vec<u8> = write_stl(&mesh);
+        bytes[..5].copy_from_slice(b"solid");
+
+        assert_eq!(vertices(&parse_stl(&bytes, COLOR).unwrap()), vertices(&mesh));
+    }
+
+    #[test]
+    fn malformed_files_are_rejected() {
+        assert!(parse_stl(b"not an stl", COLOR).is_err());
+        assert_eq!(parse_stl(b"solid a\nouter loop\nvertex 0 0\n", COLOR).err().unwrap().line, 3);
+        assert!(parse_stl(b"solid a\nouter loop\nvertex 0 0 0\nendloop\n", COLOR).is_err());
+    }
+
+    #[test]
+    fn huge_triangle_count_is_not_binary() {
+        /* 84 + 171_798_692 * 50 wraps around to 92 with 32 bit sizes */
+        for count in [171_798_692u32, u32::MAX] {
+            let mut bytes: Vec<u8> = vec![0; 92];
+            bytes[80..84].copy_from_slice(&count.to_le_bytes());
+
+            assert!(parse_stl(&bytes, COLOR).is_err());
+        }
+    }
+}
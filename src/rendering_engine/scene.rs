@@ -1,36 +1,157 @@
 use std::collections::hash_map::Values;
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use camera::Camera;
 use model_3d::mesh::Mesh;
 use model_3d::point::Point as Point3D;
 
+use crate::rendering_engine::error::RenderError;
+use crate::rendering_engine::format::gltf::{parse_gltf, GltfImport, GltfMesh, GltfNode, GltfScene};
+use crate::rendering_engine::format::mtl::{parse_mtl, Material};
+use crate::rendering_engine::format::obj::parse_obj;
+use crate::rendering_engine::format::scene_file::{read_scene, write_scene, SceneFileFormat};
+use crate::rendering_engine::format::stl::{parse_stl, write_stl};
+use crate::rendering_engine::format::ParseError;
+use crate::rendering_engine::scene::camera::display::Display;
+use crate::rendering_engine::scene::light::Light;
 use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model::culling::Culling;
+use crate::rendering_engine::scene::model::shading::Shading;
+use crate::rendering_engine::scene::model::texture::{Sampler, Texture};
+use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
 use crate::rendering_engine::scene::model_3d::face::Face;
+use crate::rendering_engine::scene::model_3d::matrix::Mat4;
+use crate::rendering_engine::scene::model_3d::transform::Transform;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+use crate::rendering_engine::scene::node::Node;
 
 pub mod camera;
+pub mod light;
 pub mod model_2d;
 pub mod model_3d;
 pub mod model;
+pub mod node;
 
 pub type CameraID = Uuid;
+pub type LightID = Uuid;
 pub type MeshID = Uuid;
+pub type NodeID = Uuid;
+pub type TextureID = Uuid;
 
+#[derive(Default, Serialize, Deserialize)]
 pub struct Scene {
     cameras: HashMap<CameraID, Camera>,
-    meshes: HashMap<MeshID, Mesh>
+    meshes: HashMap<MeshID, Mesh>,
+    nodes: HashMap<NodeID, Node>,
+    /* nodes which meshes, cameras and lights are attached to, detached ones are placed in the world directly */
+    mesh_nodes: HashMap<MeshID, NodeID>,
+    camera_nodes: HashMap<CameraID, NodeID>,
+    #[serde(default)]
+    light_nodes: HashMap<LightID, NodeID>,
+    #[serde(default)]
+    lights: HashMap<LightID, Light>,
+    /* meshes refer to textures by their ids, so one image can be shared */
+    #[serde(default)]
+    textures: HashMap<TextureID, Texture>,
 }
 
 impl Scene {
     pub fn new() -> Scene {
         Scene {
             cameras: HashMap::new(),
-            meshes: HashMap::new()
+            meshes: HashMap::new(),
+            nodes: HashMap::new(),
+            mesh_nodes: HashMap::new(),
+            camera_nodes: HashMap::new(),
+            light_nodes: HashMap::new(),
+            lights: HashMap::new(),
+            textures: HashMap::new(),
         }
     }
 
+    /** whole scene with all ids, see SceneFileFormat */
+    pub fn save(&self, format: SceneFileFormat) -> Vec<u8> {
+        write_scene(self, format)
+    }
+
+    /** reads a scene saved in either format, files of other versions are rejected */
+    pub fn load(bytes: &[u8]) -> Result<Scene, ParseError> {
+        read_scene(bytes)
+    }
+
+    /** checks that ids refer to existing objects and faces to existing points, describes the first problem */
+    pub(crate) fn check_references(&self) -> Result<(), String> {
+        for (mesh_id, mesh) in &self.meshes {
+            mesh.check().map_err(|error: RenderError| format!("mesh {}: {}", mesh_id, error))?;
+
+            if let Some(texture_id) = mesh.texture.filter(|texture_id: &TextureID| !self.textures.contains_key(texture_id)) {
+                return Err(format!("mesh {} uses texture {}, which does not exist", mesh_id, texture_id));
+            }
+        }
+
+        for (texture_id, texture) in &self.textures {
+            texture.check().map_err(|message: String| format!("{}, id {}", message, texture_id))?;
+        }
+
+        for (mesh_id, node_id) in &self.mesh_nodes {
+            if !self.meshes.contains_key(mesh_id) || !self.nodes.contains_key(node_id) {
+                return Err(format!("mesh {} is attached to node {}, one of them does not exist", mesh_id, node_id));
+            }
+        }
+
+        for (camera_id, node_id) in &self.camera_nodes {
+            if !self.cameras.contains_key(camera_id) || !self.nodes.contains_key(node_id) {
+                return Err(format!("camera {} is attached to node {}, one of them does not exist", camera_id, node_id));
+            }
+        }
+
+        for (light_id, node_id) in &self.light_nodes {
+            if !self.lights.contains_key(light_id) || !self.nodes.contains_key(node_id) {
+                return Err(format!("light {} is attached to node {}, one of them does not exist", light_id, node_id));
+            }
+        }
+
+        for (node_id, node) in &self.nodes {
+            if let Some(parent) = node.parent() {
+                if !self.nodes.get(&parent).is_some_and(|parent: &Node| parent.children().contains(node_id)) {
+                    return Err(format!("node {} is not a child of its parent {}", node_id, parent));
+                }
+            }
+
+            for child in node.children() {
+                if self.nodes.get(child).and_then(Node::parent) != Some(*node_id) {
+                    return Err(format!("node {} is not the parent of its child {}", node_id, child));
+                }
+            }
+
+            /* every step goes to an existing parent, more steps than nodes means a cycle */
+            let mut ancestor: Option<NodeID> = node.parent();
+            for _ in 0..=self.nodes.len() {
+                ancestor = match ancestor {
+                    Some(ancestor) => self.nodes[&ancestor].parent(),
+                    None => break
+                };
+            }
+
+            if ancestor.is_some() {
+                return Err(format!("node {} is its own ancestor", node_id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /** ids sorted, so the order is the same for every run */
+    pub fn get_camera_ids(&self) -> Vec<CameraID> {
+        let mut camera_ids: Vec<CameraID> = self.cameras.keys().copied().collect();
+        camera_ids.sort();
+
+        camera_ids
+    }
+
     pub fn get_camera(&self, camera_id: CameraID) -> Option<&Camera> {
         self.cameras.get(&camera_id)
     }
@@ -43,6 +164,22 @@ impl Scene {
         self.meshes.get(&mesh_id)
     }
 
+    /** the cached corner normals of the mesh are dropped, as anything of it may change */
+    pub fn get_mesh_mut(&mut self, mesh_id: MeshID) -> Option<&mut Mesh> {
+        let mesh: &mut Mesh = self.meshes.get_mut(&mesh_id)?;
+        mesh.invalidate_corner_normals();
+
+        Some(mesh)
+    }
+
+    /** ids sorted, so the order is the same for every run */
+    pub fn get_mesh_ids(&self) -> Vec<MeshID> {
+        let mut mesh_ids: Vec<MeshID> = self.meshes.keys().copied().collect();
+        mesh_ids.sort();
+
+        mesh_ids
+    }
+
     pub fn get_all_meshes(&self) -> Values<'_, MeshID, Mesh> {
         self.meshes.values()
     }
@@ -55,50 +192,854 @@ impl Scene {
         camera_id
     }
 
-    pub fn add_mesh(&mut self, points: Vec<Point3D>, faces: Vec<Face>) -> MeshID {
-        let object_id: MeshID = Uuid::new_v4();
+    /** the camera is detached from its node, returns false if the camera does not exist */
+    pub fn remove_camera(&mut self, camera_id: CameraID) -> bool {
+        self.camera_nodes.remove(&camera_id);
+
+        self.cameras.remove(&camera_id).is_some()
+    }
+
+    /** fails with RenderError::BadInput if a parameter of the light is invalid, see Light::check */
+    pub fn add_light(&mut self, light: Light) -> Result<LightID, RenderError> {
+        light.check()?;
+
+        let light_id: LightID = Uuid::new_v4();
+
+        self.lights.insert(light_id, light);
+
+        Ok(light_id)
+    }
+
+    /** replaces the light, which may change its kind */
+    pub fn set_light(&mut self, light_id: LightID, light: Light) -> Result<(), RenderError> {
+        light.check()?;
+
+        let existing: &mut Light = self.lights.get_mut(&light_id).ok_or(RenderError::UnknownLight(light_id))?;
+        *existing = light;
+
+        Ok(())
+    }
+
+    /** the light is detached from its node, returns false if the light does not exist */
+    pub fn remove_light(&mut self, light_id: LightID) -> bool {
+        self.light_nodes.remove(&light_id);
+
+        self.lights.remove(&light_id).is_some()
+    }
+
+    pub fn get_light(&self, light_id: LightID) -> Option<&Light> {
+        self.lights.get(&light_id)
+    }
+
+    /** ids sorted, so the order is the same for every run */
+    pub fn get_light_ids(&self) -> Vec<LightID> {
+        let mut light_ids: Vec<LightID> = self.lights.keys().copied().collect();
+        light_ids.sort();
+
+        light_ids
+    }
+
+    /** in the order of their ids, so the lights are summed up the same way for every render */
+    pub fn get_all_lights(&self) -> Vec<Light> {
+        self.get_light_ids()
+            .iter()
+            .map(|light_id: &LightID| self.lights[light_id])
+            .collect()
+    }
+
+    pub fn add_texture(&mut self, texture: Texture) -> TextureID {
+        let texture_id: TextureID = Uuid::new_v4();
+
+        self.textures.insert(texture_id, texture);
+
+        texture_id
+    }
+
+    /** replaces the image, meshes using the texture show the new one */
+    pub fn set_texture(&mut self, texture_id: TextureID, texture: Texture) -> Result<(), RenderError> {
+        let existing: &mut Texture = self.textures.get_mut(&texture_id).ok_or(RenderError::UnknownTexture(texture_id))?;
+        *existing = texture;
+
+        Ok(())
+    }
+
+    /** meshes using the texture lose it and show their face colors, returns false if the texture does not exist */
+    pub fn remove_texture(&mut self, texture_id: TextureID) -> bool {
+        if self.textures.remove(&texture_id).is_none() {
+            return false;
+        }
+
+        self.meshes
+            .values_mut()
+            .filter(|mesh: &&mut Mesh| mesh.texture == Some(texture_id))
+            .for_each(|mesh: &mut Mesh| mesh.texture = None);
+
+        true
+    }
+
+    pub fn get_texture(&self, texture_id: TextureID) -> Option<&Texture> {
+        self.textures.get(&texture_id)
+    }
+
+    pub fn get_all_textures(&self) -> &HashMap<TextureID, Texture> {
+        &self.textures
+    }
+
+    /** fails if a point is not finite or a face refers to a point that does not exist */
+    pub fn add_mesh(&mut self, points: Vec<Point3D>, faces: Vec<Face>) -> Result<MeshID, RenderError> {
+        self.insert_mesh(Mesh::new(points, faces))
+    }
+
+    /** fails with RenderError::InvalidGeometry if the mesh is not valid, see Mesh::check */
+    pub fn insert_mesh(&mut self, mesh: Mesh) -> Result<MeshID, RenderError> {
+        mesh.check()?;
 
-        self.meshes.insert(object_id, Mesh { points, faces });
+        Ok(self.insert_checked_mesh(mesh))
+    }
+
+    fn insert_checked_mesh(&mut self, mesh: Mesh) -> MeshID {
+        let mesh_id: MeshID = Uuid::new_v4();
+
+        self.meshes.insert(mesh_id, mesh);
+
+        mesh_id
+    }
+
+    /**
+     * Adds a mesh for every object or group of the OBJ file.
+     * Colors are taken from the MTL materials, faces without a known material get the default color.
+     * Nothing is added if either file fails to parse.
+     */
+    pub fn load_obj(&mut self, obj: &str, mtl: Option<&str>) -> Result<Vec<MeshID>, ParseError> {
+        let materials: HashMap<String, Material> = match mtl {
+            Some(mtl) => parse_mtl(mtl)?,
+            None => HashMap::new()
+        };
+
+        let meshes: Vec<Mesh> = parse_obj(obj, &materials)?;
+
+        for mesh in &meshes {
+            check_parsed_mesh(mesh)?;
+        }
+
+        Ok(meshes
+            .into_iter()
+            .map(|mesh: Mesh| self.insert_checked_mesh(mesh))
+            .collect())
+    }
+
+    /** adds the ASCII or binary STL file as one mesh with shared points */
+    pub fn load_stl(&mut self, bytes: &[u8], color: Color) -> Result<MeshID, ParseError> {
+        let mesh: Mesh = parse_stl(bytes, color)?;
+        check_parsed_mesh(&mesh)?;
+
+        Ok(self.insert_checked_mesh(mesh))
+    }
+
+    /** binary STL of the mesh in its local space */
+    pub fn export_stl(&self, mesh_id: MeshID) -> Result<Vec<u8>, RenderError> {
+        self.meshes
+            .get(&mesh_id)
+            .map(write_stl)
+            .ok_or(RenderError::UnknownMesh(mesh_id))
+    }
+
+    /**
+     * Adds the nodes of a glTF file reachable from its scene, with meshes and cameras attached to them,
+     * and the base color textures of the meshes.
+     * Cameras get the height of the given display, see parse_gltf.
+     * Nothing is added if the file fails to parse, skipped parts are reported as warnings.
+     */
+    pub fn load_gltf(&mut self, bytes: &[u8], display: &Display) -> Result<GltfImport, ParseError> {
+        let gltf: GltfScene = parse_gltf(bytes, display)?;
+
+        let mut import: GltfImport = GltfImport {
+            node_ids: Vec::new(),
+            mesh_ids: Vec::new(),
+            camera_ids: Vec::new(),
+            texture_ids: Vec::new(),
+            warnings: gltf.warnings
+        };
+
+        for texture in gltf.textures {
+            import.texture_ids.push(self.add_texture(texture));
+        }
+
+        let mut nodes: Vec<Option<GltfNode>> = gltf.nodes.into_iter().map(Some).collect();
+        let mut pending: Vec<(usize, Option<NodeID>)> = gltf.roots
+            .iter()
+            .rev()
+            .map(|root: &usize| (*root, None))
+            .collect();
+
+        while let Some((index, parent)) = pending.pop() {
+            /* a node is taken once, so malformed files with shared or cyclic children can't loop */
+            let node: GltfNode = match nodes[index].take() {
+                Some(node) => node,
+                None => continue
+            };
+
+            let node_id: NodeID = self.add_node(parent, node.transform).unwrap();
+            import.node_ids.push(node_id);
+
+            for GltfMesh { mut mesh, texture } in node.meshes {
+                mesh.texture = texture.map(|texture: usize| import.texture_ids[texture]);
+                /* invalid primitives were skipped by parse_gltf */
+                let mesh_id: MeshID = self.insert_checked_mesh(mesh);
+
+                self.attach_mesh(mesh_id, Some(node_id));
+                import.mesh_ids.push(mesh_id);
+            }
+
+            if let Some(camera) = node.camera {
+                let camera_id: CameraID = self.add_camera(camera);
+
+                self.attach_camera(camera_id, Some(node_id));
+                import.camera_ids.push(camera_id);
+            }
+
+            pending.extend(node.children.iter().rev().map(|child: &usize| (*child, Some(node_id))));
+        }
+
+        Ok(import)
+    }
+
+    pub fn translate_mesh(&mut self, mesh_id: MeshID, delta: &Vector) -> Result<(), RenderError> {
+        self.meshes
+            .get_mut(&mesh_id)
+            .ok_or(RenderError::UnknownMesh(mesh_id))?
+            .transform
+            .translate(delta);
+
+        Ok(())
+    }
+
+    /** angles of delta are in degrees: x is pitch, y is yaw, z is roll, the mesh rotates around its origin */
+    pub fn rotate_mesh(&mut self, mesh_id: MeshID, delta: &Vector) -> Result<(), RenderError> {
+        self.meshes
+            .get_mut(&mesh_id)
+            .ok_or(RenderError::UnknownMesh(mesh_id))?
+            .transform
+            .rotate(delta);
+
+        Ok(())
+    }
+
+    pub fn scale_mesh(&mut self, mesh_id: MeshID, factors: &Vector) -> Result<(), RenderError> {
+        self.meshes
+            .get_mut(&mesh_id)
+            .ok_or(RenderError::UnknownMesh(mesh_id))?
+            .transform
+            .scale(factors);
+
+        Ok(())
+    }
+
+    pub fn set_mesh_shading(&mut self, mesh_id: MeshID, shading: Shading) -> Result<(), RenderError> {
+        shading.check()?;
+
+        self.meshes
+            .get_mut(&mesh_id)
+            .ok_or(RenderError::UnknownMesh(mesh_id))?
+            .shading = shading;
+
+        Ok(())
+    }
+
+    pub fn set_mesh_culling(&mut self, mesh_id: MeshID, culling: Culling) -> Result<(), RenderError> {
+        self.meshes
+            .get_mut(&mesh_id)
+            .ok_or(RenderError::UnknownMesh(mesh_id))?
+            .culling = culling;
+
+        Ok(())
+    }
+
+    /** one texture coordinate per point of the mesh, or none to remove them, see Texture */
+    pub fn set_mesh_uvs(&mut self, mesh_id: MeshID, uvs: Vec<Point2D>) -> Result<(), RenderError> {
+        let mesh: &mut Mesh = self.meshes.get_mut(&mesh_id).ok_or(RenderError::UnknownMesh(mesh_id))?;
+
+        if !uvs.is_empty() && uvs.len() != mesh.points.len() {
+            return Err(RenderError::InvalidGeometry(format!(
+                "{} texture coordinates for {} points", uvs.len(), mesh.points.len()
+            )));
+        }
+
+        if let Some(index) = uvs.iter().position(|uv: &Point2D| !(uv.x.is_finite() && uv.y.is_finite())) {
+            return Err(RenderError::InvalidGeometry(format!("texture coordinate {} is not finite", index)));
+        }
+
+        mesh.uvs = uvs;
+
+        Ok(())
+    }
+
+    /** None removes the texture, the mesh is textured only while it has texture coordinates */
+    pub fn set_mesh_texture(&mut self, mesh_id: MeshID, texture_id: Option<TextureID>, sampler: Sampler) -> Result<(), RenderError> {
+        if let Some(texture_id) = texture_id {
+            self.textures.get(&texture_id).ok_or(RenderError::UnknownTexture(texture_id))?;
+        }
+
+        let mesh: &mut Mesh = self.meshes.get_mut(&mesh_id).ok_or(RenderError::UnknownMesh(mesh_id))?;
+        mesh.texture = texture_id;
+        mesh.sampler = sampler;
+
+        Ok(())
+    }
+
+    /** in degrees, between 0 and 180, see Mesh::corner_normals */
+    pub fn set_mesh_crease_angle(&mut self, mesh_id: MeshID, crease_angle: f32) -> Result<(), RenderError> {
+        if !(0.0..=180.0).contains(&crease_angle) {
+            return Err(RenderError::BadInput(format!("crease angle {} is not between 0 and 180 degrees", crease_angle)));
+        }
+
+        self.meshes
+            .get_mut(&mesh_id)
+            .ok_or(RenderError::UnknownMesh(mesh_id))?
+            .set_crease_angle(crease_angle);
+
+        Ok(())
+    }
+
+    /** fails if the parent does not exist */
+    pub fn add_node(&mut self, parent: Option<NodeID>, transform: Transform) -> Result<NodeID, RenderError> {
+        if let Some(parent) = parent {
+            self.nodes.get_mut(&parent).ok_or(RenderError::UnknownNode(parent))?;
+        }
+
+        let node_id: NodeID = Uuid::new_v4();
+
+        self.nodes.insert(node_id, Node::new(parent, transform));
+
+        if let Some(parent) = parent {
+            self.nodes.get_mut(&parent).unwrap().add_child(node_id);
+        }
 
-        object_id
+        Ok(node_id)
     }
 
-    pub fn add_cube(&mut self, position: Point3D, width: f32, height: f32, length: f32, color: Color) -> MeshID {
+    pub fn get_node(&self, node_id: NodeID) -> Option<&Node> {
+        self.nodes.get(&node_id)
+    }
+
+    pub fn get_node_mut(&mut self, node_id: NodeID) -> Option<&mut Node> {
+        self.nodes.get_mut(&node_id)
+    }
+
+    /**
+     * Children of the removed node are moved to its parent keeping their local transforms,
+     * meshes, cameras and lights attached to it become detached.
+     * Returns false if the node does not exist.
+     */
+    pub fn remove_node(&mut self, node_id: NodeID) -> bool {
+        let node: Node = match self.nodes.remove(&node_id) {
+            Some(node) => node,
+            None => return false
+        };
+
+        if let Some(parent) = node.parent() {
+            self.nodes.get_mut(&parent).unwrap().remove_child(node_id);
+        }
+
+        for child in node.children() {
+            self.nodes.get_mut(child).unwrap().set_parent(node.parent());
+
+            if let Some(parent) = node.parent() {
+                self.nodes.get_mut(&parent).unwrap().add_child(*child);
+            }
+        }
+
+        self.mesh_nodes.retain(|_, attached_node_id: &mut NodeID| *attached_node_id != node_id);
+        self.camera_nodes.retain(|_, attached_node_id: &mut NodeID| *attached_node_id != node_id);
+        self.light_nodes.retain(|_, attached_node_id: &mut NodeID| *attached_node_id != node_id);
+
+        true
+    }
+
+    /**
+     * Moves the node under another parent, or makes it a root one if the parent is None.
+     * The local transform is kept, so the node follows the new parent from now on.
+     * Returns false if any of the nodes does not exist or the new parent is the node itself or one of its descendants.
+     */
+    pub fn reparent_node(&mut self, node_id: NodeID, parent: Option<NodeID>) -> bool {
+        let old_parent: Option<NodeID> = match self.nodes.get(&node_id) {
+            Some(node) => node.parent(),
+            None => return false
+        };
+
+        let mut ancestor: Option<NodeID> = parent;
+
+        while let Some(ancestor_id) = ancestor {
+            if ancestor_id == node_id {
+                return false;
+            }
+
+            ancestor = match self.nodes.get(&ancestor_id) {
+                Some(ancestor_node) => ancestor_node.parent(),
+                None => return false
+            };
+        }
+
+        if let Some(old_parent) = old_parent {
+            self.nodes.get_mut(&old_parent).unwrap().remove_child(node_id);
+        }
+
+        if let Some(parent) = parent {
+            self.nodes.get_mut(&parent).unwrap().add_child(node_id);
+        }
+
+        self.nodes.get_mut(&node_id).unwrap().set_parent(parent);
+
+        true
+    }
+
+    pub fn translate_node(&mut self, node_id: NodeID, delta: &Vector) -> Result<(), RenderError> {
+        self.nodes
+            .get_mut(&node_id)
+            .ok_or(RenderError::UnknownNode(node_id))?
+            .transform
+            .translate(delta);
+
+        Ok(())
+    }
+
+    pub fn rotate_node(&mut self, node_id: NodeID, delta: &Vector) -> Result<(), RenderError> {
+        self.nodes
+            .get_mut(&node_id)
+            .ok_or(RenderError::UnknownNode(node_id))?
+            .transform
+            .rotate(delta);
+
+        Ok(())
+    }
+
+    pub fn scale_node(&mut self, node_id: NodeID, factors: &Vector) -> Result<(), RenderError> {
+        self.nodes
+            .get_mut(&node_id)
+            .ok_or(RenderError::UnknownNode(node_id))?
+            .transform
+            .scale(factors);
+
+        Ok(())
+    }
+
+    /**
+     * The mesh transform becomes relative to the node, None detaches the mesh.
+     * Returns false if the mesh or the node does not exist.
+     */
+    pub fn attach_mesh(&mut self, mesh_id: MeshID, node_id: Option<NodeID>) -> bool {
+        if !self.meshes.contains_key(&mesh_id) {
+            return false;
+        }
+
+        match node_id {
+            Some(node_id) if self.nodes.contains_key(&node_id) => {
+                self.mesh_nodes.insert(mesh_id, node_id);
+            }
+            Some(_) => return false,
+            None => {
+                self.mesh_nodes.remove(&mesh_id);
+            }
+        }
+
+        true
+    }
+
+    /**
+     * The camera position and orientation become relative to the node, None detaches the camera.
+     * Returns false if the camera or the node does not exist.
+     */
+    pub fn attach_camera(&mut self, camera_id: CameraID, node_id: Option<NodeID>) -> bool {
+        if !self.cameras.contains_key(&camera_id) {
+            return false;
+        }
+
+        match node_id {
+            Some(node_id) if self.nodes.contains_key(&node_id) => {
+                self.camera_nodes.insert(camera_id, node_id);
+            }
+            Some(_) => return false,
+            None => {
+                self.camera_nodes.remove(&camera_id);
+            }
+        }
+
+        true
+    }
+
+    /**
+     * The light position and direction become relative to the node, None detaches the light.
+     * Returns false if the light or the node does not exist.
+     */
+    pub fn attach_light(&mut self, light_id: LightID, node_id: Option<NodeID>) -> bool {
+        if !self.lights.contains_key(&light_id) {
+            return false;
+        }
+
+        match node_id {
+            Some(node_id) if self.nodes.contains_key(&node_id) => {
+                self.light_nodes.insert(light_id, node_id);
+            }
+            Some(_) => return false,
+            None => {
+                self.light_nodes.remove(&light_id);
+            }
+        }
+
+        true
+    }
+
+    pub fn get_mesh_node(&self, mesh_id: MeshID) -> Option<NodeID> {
+        self.mesh_nodes.get(&mesh_id).copied()
+    }
+
+    pub fn get_camera_node(&self, camera_id: CameraID) -> Option<NodeID> {
+        self.camera_nodes.get(&camera_id).copied()
+    }
+
+    pub fn get_light_node(&self, light_id: LightID) -> Option<NodeID> {
+        self.light_nodes.get(&light_id).copied()
+    }
+
+    /** node space to world space */
+    pub fn node_world_matrix(&self, node_id: NodeID) -> Option<Mat4> {
+        let node: &Node = self.nodes.get(&node_id)?;
+        let local_matrix: Mat4 = node.transform.matrix();
+
+        match node.parent() {
+            Some(parent) => Some(self.node_world_matrix(parent)? * local_matrix),
+            None => Some(local_matrix)
+        }
+    }
+
+    /** world space to the camera space, takes the node the camera is attached to into account */
+    pub fn camera_view_matrix(&self, camera_id: CameraID) -> Option<Mat4> {
+        let camera: &Camera = self.cameras.get(&camera_id)?;
+
+        let node_matrix: Mat4 = match self.camera_nodes.get(&camera_id) {
+            Some(node_id) => self.node_world_matrix(*node_id)?,
+            None => return Some(camera.view_matrix())
+        };
+
+        /* singular for zero scaled nodes, the camera can't see anything meaningful then */
+        Some((node_matrix * camera.world_matrix()).inverse().unwrap_or(camera.view_matrix()))
+    }
+
+    /**
+     * Every mesh with its model matrix, which moves the mesh points from its local space to the world space.
+     * In the order of their ids, so fragments at equal depths are resolved the same way for every render.
+     */
+    pub fn get_all_meshes_in_world(&self) -> Vec<(&Mesh, Mat4)> {
+        let node_matrices: HashMap<NodeID, Mat4> = self.node_world_matrices();
+
+        self.get_mesh_ids()
+            .iter()
+            .map(|mesh_id: &MeshID| {
+                let mesh: &Mesh = &self.meshes[mesh_id];
+                let model_matrix: Mat4 = match self.mesh_nodes.get(mesh_id) {
+                    Some(node_id) => node_matrices[node_id] * mesh.transform.matrix(),
+                    None => mesh.transform.matrix()
+                };
+
+                (mesh, model_matrix)
+            })
+            .collect()
+    }
+
+    /** every light in the order of get_all_lights with the matrix which moves it to the world space */
+    pub fn get_all_lights_in_world(&self) -> Vec<(Light, Mat4)> {
+        let node_matrices: HashMap<NodeID, Mat4> = self.node_world_matrices();
+
+        self.get_light_ids()
+            .iter()
+            .map(|light_id: &LightID| {
+                let model_matrix: Mat4 = match self.light_nodes.get(light_id) {
+                    Some(node_id) => node_matrices[node_id],
+                    None => Mat4::identity()
+                };
+
+                (self.lights[light_id], model_matrix)
+            })
+            .collect()
+    }
+
+    /** world matrices of all nodes, computed from the roots down so every parent is combined once */
+    fn node_world_matrices(&self) -> HashMap<NodeID, Mat4> {
+        let mut world_matrices: HashMap<NodeID, Mat4> = HashMap::with_capacity(self.nodes.len());
+
+        let mut pending: Vec<(NodeID, Mat4)> = self.nodes
+            .iter()
+            .filter(|(_, node): &(&NodeID, &Node)| node.parent().is_none())
+            .map(|(node_id, _): (&NodeID, &Node)| (*node_id, Mat4::identity()))
+            .collect();
+
+        while let Some((node_id, parent_matrix)) = pending.pop() {
+            let node: &Node = &self.nodes[&node_id];
+            let world_matrix: Mat4 = parent_matrix * node.transform.matrix();
+
+            world_matrices.insert(node_id, world_matrix);
+
+            pending.extend(node.children().iter().map(|child: &NodeID| (*child, world_matrix)));
+        }
+
+        world_matrices
+    }
+
+    /**
+     * Cube points are centered around the mesh origin, the position becomes the mesh translation.
+     * Fails if a size is not positive.
+     */
+    pub fn add_cube(&mut self, position: Point3D, width: f32, height: f32, length: f32, color: Color) -> Result<MeshID, RenderError> {
+        if [width, height, length].iter().any(|size: &f32| !(size.is_finite() && *size > 0.0)) {
+            return Err(RenderError::InvalidGeometry(format!(
+                "cube size {} x {} x {} is not positive", width, height, length
+            )));
+        }
+
         let width: f32 = width / 2.0;
         let height: f32 = height / 2.0;
         let length: f32 = length / 2.0;
 
-        let Point3D { x, y, z } = position;
-
         let points: Vec<Point3D> = vec![
             /* front face */
-            Point3D { x: x - width, y: y - height, z: z - length }, /* 0 bottom left */
-            Point3D { x: x - width, y: y + height, z: z - length }, /* 1 top left */
-            Point3D { x: x + width, y: y + height, z: z - length }, /* 2 top right */
-            Point3D { x: x + width, y: y - height, z: z - length }, /* 3 bottom right */
+            Point3D { x: -width, y: -height, z: -length }, /* 0 bottom left */
+            Point3D { x: -width, y: height, z: -length }, /* 1 top left */
+            Point3D { x: width, y: height, z: -length }, /* 2 top right */
+            Point3D { x: width, y: -height, z: -length }, /* 3 bottom right */
 
             /* back face */
-            Point3D { x: x - width, y: y - height, z: z + length }, /* 4 bottom left */
-            Point3D { x: x - width, y: y + height, z: z + length }, /* 5 top left */
-            Point3D { x: x + width, y: y + height, z: z + length }, /* 6 top right */
-            Point3D { x: x + width, y: y - height, z: z + length }, /* 7 bottom right */
+            Point3D { x: -width, y: -height, z: length }, /* 4 bottom left */
+            Point3D { x: -width, y: height, z: length }, /* 5 top left */
+            Point3D { x: width, y: height, z: length }, /* 6 top right */
+            Point3D { x: width, y: -height, z: length }, /* 7 bottom right */
         ];
 
         let faces: Vec<Face> = vec![
             /* front face */
             Face::new([0, 1, 2], color), Face::new([0, 2, 3], color),
             /* back face */
-            Face::new([4, 5, 6], color), Face::new([4, 6, 7], color),
+            Face::new([4, 6, 5], color), Face::new([4, 7, 6], color),
             /* left face */
-            Face::new([0, 1, 4], color), Face::new([1, 5, 4], color),
+            Face::new([0, 4, 1], color), Face::new([1, 4, 5], color),
             /* top face */
-            Face::new([1, 2, 5], color), Face::new([2, 5, 6], color),
-            /* left face */
+            Face::new([1, 5, 2], color), Face::new([2, 5, 6], color),
+            /* right face */
             Face::new([2, 6, 3], color), Face::new([3, 6, 7], color),
             /* bottom face */
-            Face::new([0, 4, 7], color), Face::new([0, 7, 3], color),
+            Face::new([0, 7, 4], color), Face::new([0, 3, 7], color),
+        ];
+
+        let mesh_id: MeshID = self.add_mesh(points, faces)?;
+        self.set_mesh_culling(mesh_id, Culling::Back)?;
+        self.translate_mesh(mesh_id, &position.to_vector())?;
+
+        Ok(mesh_id)
+    }
+}
+
+/** numbers like nan parse in every format, so parsed meshes are checked like the ones added by hand */
+fn check_parsed_mesh(mesh: &Mesh) -> Result<(), ParseError> {
+    mesh.check().map_err(|error: RenderError| ParseError::new(0, error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::error::RenderError;
+    use crate::rendering_engine::scene::camera::display::Display;
+    use crate::rendering_engine::scene::camera::Camera;
+    use crate::rendering_engine::scene::light::{Attenuation, Light};
+    use crate::rendering_engine::scene::model::color::Color;
+    use crate::rendering_engine::scene::model::culling::Culling;
+    use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
+    use crate::rendering_engine::scene::model_3d::face::Face;
+    use crate::rendering_engine::scene::model_3d::matrix::Mat4;
+    use crate::rendering_engine::scene::model_3d::mesh::Mesh;
+    use crate::rendering_engine::scene::model_3d::point::Point;
+    use crate::rendering_engine::scene::model_3d::quaternion::Quaternion;
+    use crate::rendering_engine::scene::model_3d::transform::Transform;
+    use crate::rendering_engine::scene::model_3d::triangle::Triangle;
+    use crate::rendering_engine::scene::model_3d::vector::Vector;
+    use crate::rendering_engine::scene::{CameraID, LightID, MeshID, NodeID, Scene};
+
+    const ORIGIN: Point = Point { x: 0.0, y: 0.0, z: 0.0 };
+
+    fn create_node(scene: &mut Scene, parent: Option<NodeID>, x: f32, y: f32, z: f32) -> NodeID {
+        scene.add_node(parent, Transform::from_translation(Vector::new(x, y, z))).unwrap()
+    }
+
+    /** mesh with a single triangle at its origin */
+    fn create_mesh(scene: &mut Scene) -> MeshID {
+        scene.add_mesh(vec![ORIGIN; 3], vec![Face::new([0, 1, 2], Color::new(0, 0, 0, 1.0))]).unwrap()
+    }
+
+    fn assert_point_eq(actual: Point, expected: Point) {
+        assert!((actual - expected).length() < 1e-4, "{:?} != {:?}", actual, expected);
+    }
+
+    /** where the origin of the node ends up in the world */
+    fn world_position(scene: &Scene, node_id: NodeID) -> Point {
+        scene.node_world_matrix(node_id).unwrap().transform_point(&ORIGIN)
+    }
+
+    #[test]
+    fn cube_faces_point_outwards() {
+        let mut scene: Scene = Scene::new();
+        let mesh_id: MeshID = scene.add_cube(Point { x: 0.0, y: 0.0, z: 0.0 }, 2.0, 4.0, 6.0, Color::new(0, 0, 0, 1.0)).unwrap();
+        let mesh: &Mesh = scene.get_mesh(mesh_id).unwrap();
+
+        assert_eq!(mesh.culling, Culling::Back);
+
+        /* the cube is centered at the origin, so the normals point away from it */
+        assert!(mesh.triangulate()
+            .iter()
+            .all(|triangle: &Triangle| triangle.normal().dot(&triangle.centroid().to_vector()) > 0.0));
+    }
+
+    #[test]
+    fn world_transforms_propagate_to_children() {
+        let mut scene: Scene = Scene::new();
+        let parent: NodeID = create_node(&mut scene, None, 10.0, 0.0, 0.0);
+        let child: NodeID = create_node(&mut scene, Some(parent), 0.0, 0.0, 5.0);
+        let mesh_id: MeshID = create_mesh(&mut scene);
+        assert!(scene.attach_mesh(mesh_id, Some(child)));
+
+        /* yaw of 90 degrees turns +z into +x, the child translation is rotated by the parent */
+        scene.rotate_node(parent, &Vector::new(0.0, 90.0, 0.0)).unwrap();
+        assert_point_eq(world_position(&scene, child), Point { x: 15.0, y: 0.0, z: 0.0 });
+
+        scene.translate_node(parent, &Vector::new(0.0, 1.0, 0.0)).unwrap();
+        assert_point_eq(world_position(&scene, child), Point { x: 15.0, y: 1.0, z: 0.0 });
+
+        let meshes: Vec<(&Mesh, Mat4)> = scene.get_all_meshes_in_world();
+        assert_eq!(meshes.len(), 1);
+        assert_point_eq(meshes[0].1.transform_point(&ORIGIN), Point { x: 15.0, y: 1.0, z: 0.0 });
+    }
+
+    #[test]
+    fn reparenting_moves_node() {
+        let mut scene: Scene = Scene::new();
+        let first: NodeID = create_node(&mut scene, None, 10.0, 0.0, 0.0);
+        let second: NodeID = create_node(&mut scene, None, 0.0, 20.0, 0.0);
+        let child: NodeID = create_node(&mut scene, Some(first), 1.0, 0.0, 0.0);
+        let grandchild: NodeID = create_node(&mut scene, Some(child), 0.0, 0.0, 1.0);
+
+        assert_point_eq(world_position(&scene, grandchild), Point { x: 11.0, y: 0.0, z: 1.0 });
+
+        /* the local transform is kept, so the world one follows the new parent */
+        assert!(scene.reparent_node(child, Some(second)));
+        assert_point_eq(world_position(&scene, child), Point { x: 1.0, y: 20.0, z: 0.0 });
+        assert_point_eq(world_position(&scene, grandchild), Point { x: 1.0, y: 20.0, z: 1.0 });
+
+        assert!(scene.get_node(first).unwrap().children().is_empty());
+        assert_eq!(scene.get_node(second).unwrap().children(), &[child]);
+        assert_eq!(scene.get_node(child).unwrap().parent(), Some(second));
+
+        assert!(scene.reparent_node(child, None));
+        assert!(scene.get_node(second).unwrap().children().is_empty());
+        assert_point_eq(world_position(&scene, child), Point { x: 1.0, y: 0.0, z: 0.0 });
+    }
+
+    #[test]
+    fn reparenting_rejects_cycles() {
+        let mut scene: Scene = Scene::new();
+        let root: NodeID = create_node(&mut scene, None, 0.0, 0.0, 0.0);
+        let child: NodeID = create_node(&mut scene, Some(root), 0.0, 0.0, 0.0);
+        let grandchild: NodeID = create_node(&mut scene, Some(child), 0.0, 0.0, 0.0);
+
+        assert!(!scene.reparent_node(root, Some(grandchild)));
+        assert!(!scene.reparent_node(child, Some(child)));
+        assert!(!scene.reparent_node(child, Some(NodeID::new_v4())));
+
+        /* nothing changed */
+        assert_eq!(scene.get_node(root).unwrap().parent(), None);
+        assert_eq!(scene.get_node(root).unwrap().children(), &[child]);
+        assert_eq!(scene.get_node(child).unwrap().parent(), Some(root));
+    }
+
+    #[test]
+    fn ids_are_stable_across_reparenting() {
+        let mut scene: Scene = Scene::new();
+        let first: NodeID = create_node(&mut scene, None, 10.0, 0.0, 0.0);
+        let second: NodeID = create_node(&mut scene, None, 0.0, 20.0, 0.0);
+        let node: NodeID = create_node(&mut scene, Some(first), 0.0, 0.0, 0.0);
+
+        let mesh_id: MeshID = create_mesh(&mut scene);
+        let camera_id: CameraID = scene.add_camera(Camera::new(100.0, ORIGIN, 0.0, 0.0, 0.0, Display::new(10, 10)));
+        assert!(scene.attach_mesh(mesh_id, Some(node)));
+        assert!(scene.attach_camera(camera_id, Some(node)));
+
+        assert!(scene.reparent_node(node, Some(second)));
+
+        assert_eq!(scene.get_mesh_node(mesh_id), Some(node));
+        assert_eq!(scene.get_camera_node(camera_id), Some(node));
+        assert!(scene.get_mesh(mesh_id).is_some());
+
+        /* the camera moved with the node, its position is the origin of its view */
+        let view: Mat4 = scene.camera_view_matrix(camera_id).unwrap();
+        assert_point_eq(view.transform_point(&Point { x: 0.0, y: 20.0, z: 0.0 }), ORIGIN);
+    }
+
+    #[test]
+    fn lights_follow_their_nodes() {
+        let mut scene: Scene = Scene::new();
+        let parent: NodeID = create_node(&mut scene, None, 0.0, 10.0, 0.0);
+        let node: NodeID = create_node(&mut scene, Some(parent), 5.0, 0.0, 0.0);
+        scene.get_node_mut(node).unwrap().transform.rotation = Quaternion::from_euler(0.0, 90.0, 0.0);
+
+        let light_id: LightID = scene.add_light(Light::Spot {
+            color: Color::new(255, 255, 255, 1.0),
+            intensity: 1.0,
+            position: Point { x: 0.0, y: 0.0, z: 1.0 },
+            direction: Vector::new(0.0, 0.0, 1.0),
+            inner_angle: 10.0,
+            outer_angle: 20.0,
+            attenuation: Attenuation::NONE,
+        }).unwrap();
+
+        assert!(scene.attach_light(light_id, Some(node)));
+        assert!(!scene.attach_light(light_id, Some(NodeID::new_v4())));
+        assert!(!scene.attach_light(LightID::new_v4(), Some(node)));
+        assert_eq!(scene.get_light_node(light_id), Some(node));
+
+        /* the node turns the light from the z axis to the x axis */
+        let (light, model_matrix): (Light, Mat4) = scene.get_all_lights_in_world()[0];
+        match light.transform(&model_matrix) {
+            Light::Spot { position, direction, .. } => {
+                assert_point_eq(position, Point { x: 6.0, y: 10.0, z: 0.0 });
+                assert!((direction - Vector::new(1.0, 0.0, 0.0)).length() < 1e-4, "{:?}", direction);
+            }
+            light => panic!("{:?} is not a spot light", light)
+        }
+
+        /* removing the node leaves the light in the world */
+        assert!(scene.remove_node(node));
+        assert_eq!(scene.get_light_node(light_id), None);
+        assert_eq!(scene.get_all_lights_in_world()[0].1, Mat4::identity());
+
+        assert!(scene.attach_light(light_id, Some(parent)));
+        assert!(scene.remove_light(light_id));
+        assert_eq!(scene.get_light_node(light_id), None);
+        assert!(scene.check_references().is_ok());
+    }
+
+    #[test]
+    fn invalid_meshes_are_rejected() {
+        let mut scene: Scene = Scene::new();
+
+        let mut short_uvs: Mesh = Mesh::new(vec![ORIGIN; 3], vec![Face::new([0, 1, 2], Color::new(0, 0, 0, 1.0))]);
+        short_uvs.uvs = vec![Point2D { x: 0.0, y: 0.0 }];
+
+        let meshes: [Mesh; 3] = [
+            Mesh::new(vec![ORIGIN; 3], vec![Face::new([0, 1, 3], Color::new(0, 0, 0, 1.0))]),
+            Mesh::new(vec![Point { x: f32::NAN, y: 0.0, z: 0.0 }], Vec::new()),
+            short_uvs,
         ];
 
-        self.add_mesh(points, faces)
+        for mesh in meshes {
+            assert!(matches!(scene.insert_mesh(mesh), Err(RenderError::InvalidGeometry(_))));
+        }
+
+        assert!(scene.get_all_meshes().next().is_none());
+        assert!(scene.load_obj("v nan 0 0\nv 0 1 0\nv 1 0 0\nf 1 2 3\n", None).is_err());
     }
 }
@@ -1,24 +1,47 @@
-use std::collections::HashMap;
-
 use serde::{Deserialize, Serialize};
 
 use crate::rendering_engine::scene::camera::display::Display;
+use crate::rendering_engine::scene::camera::sensor::Sensor;
 use crate::rendering_engine::scene::model_3d::axis::Axis;
-use crate::rendering_engine::scene::model_3d::plane::Plane;
-use crate::rendering_engine::scene::model_3d::plane_direction::PlaneDirection;
+use crate::rendering_engine::scene::model_3d::matrix::{Mat3, Mat4};
 use crate::rendering_engine::scene::model_3d::point::Point;
+use crate::rendering_engine::scene::model_3d::quaternion::Quaternion;
 use crate::rendering_engine::scene::model_3d::vector::Vector;
 
+pub mod controller;
 pub mod display;
+pub mod options;
+pub mod sensor;
+
+pub const DEFAULT_NEAR: f32 = 1.0;
+pub const DEFAULT_FAR: f32 = 100_000.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize)]
+/* externally tagged, bincode can't read internally tagged enums */
+#[serde(rename_all = "snake_case")]
+pub enum Projection {
+    /** distant objects are smaller, the field of view is given by the focal length */
+    #[default]
+    Perspective,
+    /** parallel projection, scale is the size of a pixel in world units */
+    Orthographic { scale: f32 },
+}
 
+#[derive(Clone)]
 #[derive(Serialize, Deserialize)]
 pub struct Camera {
     focal_length: f32,
     center: Point,
-    pitch_angle: f32, /* x axis */
-    yaw_angle: f32, /* y axis */
-    roll_angle: f32, /* z axis */
-    display: Display
+    orientation: Quaternion,
+    display: Display,
+    /* distances of the clipping planes from the camera, geometry outside of them is not rendered */
+    #[serde(default = "default_near")]
+    near: f32,
+    #[serde(default = "default_far")]
+    far: f32,
+    #[serde(default)]
+    projection: Projection,
 }
 
 impl Camera {
@@ -30,81 +53,247 @@ impl Camera {
                display: Display) -> Camera {
 
         Camera {
-            focal_length, center, pitch_angle, yaw_angle, roll_angle, display
+            focal_length,
+            center,
+            orientation: Quaternion::from_euler(pitch_angle, yaw_angle, roll_angle),
+            display,
+            near: DEFAULT_NEAR,
+            far: DEFAULT_FAR,
+            projection: Projection::Perspective
         }
     }
 
-    pub fn reposition(&mut self, mut delta: Vector) {
-        delta.rotate(Axis::X, self.pitch_angle);
-        delta.rotate(Axis::Y, self.yaw_angle);
-        delta.rotate(Axis::Z, self.roll_angle);
+    /** vertical_fov is the angle between the top and the bottom edge of the display in degrees */
+    pub fn from_vertical_fov(vertical_fov: f32,
+                             center: Point,
+                             pitch_angle: f32,
+                             yaw_angle: f32,
+                             roll_angle: f32,
+                             display: Display) -> Camera {
 
-        self.center.x += delta.x;
-        self.center.y += delta.y;
-        self.center.z += delta.z;
+        let focal_length: f32 = fov_to_focal_length(display.height, vertical_fov);
+
+        Camera::new(focal_length, center, pitch_angle, yaw_angle, roll_angle, display)
     }
 
+    /** horizontal_fov is the angle between the left and the right edge of the display in degrees */
+    pub fn from_horizontal_fov(horizontal_fov: f32,
+                               center: Point,
+                               pitch_angle: f32,
+                               yaw_angle: f32,
+                               roll_angle: f32,
+                               display: Display) -> Camera {
+
+        let focal_length: f32 = fov_to_focal_length(display.width, horizontal_fov);
+
+        Camera::new(focal_length, center, pitch_angle, yaw_angle, roll_angle, display)
+    }
+
+    /** lens_focal_length is in millimeters, the display is fitted into the sensor, see Sensor::pixels_per_millimeter */
+    pub fn from_lens(lens_focal_length: f32,
+                     sensor: &Sensor,
+                     center: Point,
+                     pitch_angle: f32,
+                     yaw_angle: f32,
+                     roll_angle: f32,
+                     display: Display) -> Camera {
+
+        let focal_length: f32 = lens_focal_length * sensor.pixels_per_millimeter(&display);
+
+        Camera::new(focal_length, center, pitch_angle, yaw_angle, roll_angle, display)
+    }
+
+    /** delta is given in the camera space, z moves the camera forward */
+    pub fn reposition(&mut self, delta: Vector) {
+        self.center = self.center + self.orientation.rotate_vector(&delta);
+    }
+
+    /**
+     * Angles of delta are in degrees: x is pitch, y is yaw, z is roll.
+     * Yaw turns the camera around the world y axis, pitch and roll around its own x and z axes,
+     * so the horizon does not tilt while looking around.
+     */
     pub fn rotate(&mut self, delta: &Vector) {
-        self.pitch_angle += delta.x;
-        self.yaw_angle += delta.y;
-        self.roll_angle += delta.z;
+        self.orientation = (
+            Quaternion::from_axis(Axis::Y, delta.y)
+                * self.orientation
+                * Quaternion::from_axis(Axis::X, delta.x)
+                * Quaternion::from_axis(Axis::Z, delta.z)
+        ).normalize();
+    }
 
-        self.pitch_angle %= 360.0;
-        self.yaw_angle %= 360.0;
-        self.roll_angle %= 360.0;
+    /**
+     * Turns the camera to the target, `up` is the direction which should point up on the display.
+     * Returns false and keeps the orientation if the target is the camera center or `up` is parallel to the view direction.
+     */
+    pub fn look_at(&mut self, target: &Point, up: &Vector) -> bool {
+        let forward: Vector = (*target - self.center).normalize();
+
+        if forward.length() == 0.0 || up.normalize().cross(&forward).length() < 1e-6 {
+            return false;
+        }
+
+        /* rows of the view rotation are the camera axes in the world space, its transpose turns the camera to them */
+        let view_rotation: Mat3 = Mat4::look_at(&self.center, target, up).to_mat3();
+        self.orientation = Quaternion::from_mat3(&view_rotation.transpose());
+
+        true
+    }
+
+    pub fn set_center(&mut self, center: Point) {
+        self.center = center;
+    }
+
+    pub fn set_orientation(&mut self, orientation: Quaternion) {
+        self.orientation = orientation.normalize();
+    }
+
+    /** unit vector in the view direction */
+    pub fn forward(&self) -> Vector {
+        self.orientation.rotate_vector(&Vector::new(0.0, 0.0, 1.0))
     }
 
     pub fn move_focal_length(&mut self, delta: f32) {
         self.focal_length += delta;
     }
 
-    pub fn create_planes(&self) -> HashMap<PlaneDirection, Plane> {
-        let mut yz_plane_normal: Vector = Vector { x: 1.0, y: 0.0, z: 0.0 };
-        let mut xz_plane_normal: Vector = Vector { x: 0.0, y: 1.0, z: 0.0 };
-        let mut xy_plane_normal: Vector = Vector { x: 0.0, y: 0.0, z: 1.0 };
+    /** in pixels */
+    pub fn set_focal_length(&mut self, focal_length: f32) {
+        self.focal_length = focal_length;
+    }
+
+    /** in degrees, the horizontal field of view follows from the display aspect ratio */
+    pub fn set_vertical_fov(&mut self, vertical_fov: f32) {
+        self.focal_length = fov_to_focal_length(self.display.height, vertical_fov);
+    }
+
+    /** in degrees, the vertical field of view follows from the display aspect ratio */
+    pub fn set_horizontal_fov(&mut self, horizontal_fov: f32) {
+        self.focal_length = fov_to_focal_length(self.display.width, horizontal_fov);
+    }
+
+    /** lens_focal_length is in millimeters, see Camera::from_lens */
+    pub fn set_lens(&mut self, lens_focal_length: f32, sensor: &Sensor) {
+        self.focal_length = lens_focal_length * sensor.pixels_per_millimeter(&self.display);
+    }
 
-        yz_plane_normal.rotate(Axis::Y, self.yaw_angle);
-        yz_plane_normal.rotate(Axis::Z, self.roll_angle);
-        yz_plane_normal.rotate(Axis::X, self.pitch_angle);
+    /**
+     * Replaces the display, for example when the canvas is resized, position and orientation are kept.
+     * The focal length and the orthographic scale are adjusted to the display height,
+     * so the vertical field of view and the visible height of orthographic views stay the same.
+     */
+    pub fn set_display(&mut self, display: Display) {
+        if self.display.height > 0 && display.height > 0 {
+            let ratio: f32 = display.height as f32 / self.display.height as f32;
 
-        xz_plane_normal.rotate(Axis::X, self.pitch_angle);
-        xz_plane_normal.rotate(Axis::Z, self.roll_angle);
-        xz_plane_normal.rotate(Axis::Y, self.yaw_angle);
+            self.focal_length *= ratio;
 
-        xy_plane_normal.rotate(Axis::X, self.pitch_angle);
-        xy_plane_normal.rotate(Axis::Y, self.yaw_angle);
-        xy_plane_normal.rotate(Axis::Z, self.roll_angle);
+            if let Projection::Orthographic { scale } = &mut self.projection {
+                *scale /= ratio;
+            }
+        }
 
-        let mut planes: HashMap<PlaneDirection, Plane> = HashMap::with_capacity(3);
+        self.display = display;
+    }
 
-        planes.insert(PlaneDirection::YZ, Plane::new(yz_plane_normal, self.center));
-        planes.insert(PlaneDirection::XZ, Plane::new(xz_plane_normal, self.center));
-        planes.insert(PlaneDirection::XY, Plane::new(xy_plane_normal, self.center));
+    /** near should be positive and less than far, otherwise nothing is visible */
+    pub fn set_clipping_distances(&mut self, near: f32, far: f32) {
+        self.near = near;
+        self.far = far;
+    }
 
-        planes
+    /** camera space to world space */
+    pub fn world_matrix(&self) -> Mat4 {
+        Mat4::translation(&self.center.to_vector()) * Mat4::rotation(&self.orientation)
     }
 
+    /** world space to camera space, inverse of the world matrix */
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::rotation(&self.orientation.conjugate()) * Mat4::translation(&-self.center.to_vector())
+    }
+
+    /** orthographic projections ignore the focal length and keep it for switching back */
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn projection_matrix(&self) -> Mat4 {
+        match self.projection {
+            Projection::Perspective => Mat4::perspective(self.focal_length, self.near, self.far),
+            Projection::Orthographic { scale } => Mat4::orthographic(scale, self.near, self.far)
+        }
+    }
+
+    /** in pixels */
     pub fn focal_length(&self) -> f32 {
         self.focal_length
     }
 
+    /** in degrees */
+    pub fn vertical_fov(&self) -> f32 {
+        focal_length_to_fov(self.display.height, self.focal_length)
+    }
+
+    /** in degrees */
+    pub fn horizontal_fov(&self) -> f32 {
+        focal_length_to_fov(self.display.width, self.focal_length)
+    }
+
+    /** focal length in millimeters of a lens giving the same field of view on the sensor */
+    pub fn lens_focal_length(&self, sensor: &Sensor) -> f32 {
+        self.focal_length / sensor.pixels_per_millimeter(&self.display)
+    }
+
     pub fn center(&self) -> &Point {
         &self.center
     }
 
+    pub fn orientation(&self) -> &Quaternion {
+        &self.orientation
+    }
+
     pub fn pitch_angle(&self) -> f32 {
-        self.pitch_angle
+        self.orientation.to_euler().0
     }
 
     pub fn yaw_angle(&self) -> f32 {
-        self.yaw_angle
+        self.orientation.to_euler().1
     }
 
     pub fn roll_angle(&self) -> f32 {
-        self.roll_angle
+        self.orientation.to_euler().2
     }
 
     pub fn display(&self) -> &Display {
         &self.display
     }
+
+    pub fn near(&self) -> f32 {
+        self.near
+    }
+
+    pub fn far(&self) -> f32 {
+        self.far
+    }
+}
+
+/** focal length in pixels at which the angle spans the extent of the display in pixels */
+fn fov_to_focal_length(extent: usize, fov: f32) -> f32 {
+    (extent as f32 / 2.0) / (fov.to_radians() / 2.0).tan()
+}
+
+fn focal_length_to_fov(extent: usize, focal_length: f32) -> f32 {
+    (2.0 * (extent as f32 / 2.0).atan2(focal_length)).to_degrees()
+}
+
+fn default_near() -> f32 {
+    DEFAULT_NEAR
+}
+
+fn default_far() -> f32 {
+    DEFAULT_FAR
 }
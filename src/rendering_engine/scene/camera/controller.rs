@@ -0,0 +1,212 @@
+use crate::rendering_engine::scene::camera::{Camera, Projection};
+use crate::rendering_engine::scene::model_3d::point::Point;
+use crate::rendering_engine::scene::model_3d::quaternion::Quaternion;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+/** in degrees, yaw is undefined when looking straight up or down, so the controllers stop just before */
+pub const MAX_PITCH: f32 = 89.0;
+
+pub const WORLD_UP: Vector = Vector { x: 0.0, y: 1.0, z: 0.0 };
+
+/**
+ * Drives a camera by higher level gestures than Camera::reposition and Camera::rotate.
+ * The controllers keep the horizon level, the roll of the camera is dropped when one takes over.
+ * Positions are in the world, RenderingEngine moves a camera attached to a node through the node.
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CameraController {
+    Orbit(OrbitController),
+    Fly(FlyController),
+}
+
+/** orbits, pans and zooms the camera around the pivot, the camera keeps looking at it */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrbitController {
+    pub pivot: Point,
+}
+
+/** first person camera, flies in the view direction */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FlyController;
+
+impl OrbitController {
+    pub fn new(pivot: Point) -> OrbitController {
+        OrbitController { pivot }
+    }
+
+    /** turns the camera to the pivot, keeping its distance, called when the controller takes over the camera */
+    pub fn attach(&self, camera: &mut Camera) {
+        let offset: Vector = self.pivot - *camera.center();
+
+        let (yaw, pitch): (f32, f32) = if offset.length() > 0.0 {
+            yaw_and_pitch(&offset)
+        } else {
+            yaw_and_pitch(&camera.forward())
+        };
+
+        self.place(camera, yaw, pitch, offset.length());
+    }
+
+    /** in degrees, positive yaw moves the camera to the right around the pivot, positive pitch moves it up */
+    pub fn orbit(&self, camera: &mut Camera, delta_yaw: f32, delta_pitch: f32) {
+        let (yaw, pitch): (f32, f32) = yaw_and_pitch(&camera.forward());
+
+        self.place(camera, yaw - delta_yaw, pitch + delta_pitch, self.distance(camera));
+    }
+
+    /**
+     * Moves the camera and the pivot in the display plane, x right and y up.
+     * Deltas are in pixels measured at the pivot, so the pivot follows a dragging cursor.
+     */
+    pub fn pan(&mut self, camera: &mut Camera, delta_x: f32, delta_y: f32) {
+        let pixel_size: f32 = match camera.projection() {
+            Projection::Perspective => self.distance(camera) / camera.focal_length(),
+            Projection::Orthographic { scale } => scale
+        };
+
+        let right: Vector = camera.orientation().rotate_vector(&Vector::new(1.0, 0.0, 0.0));
+        let up: Vector = camera.orientation().rotate_vector(&Vector::new(0.0, 1.0, 0.0));
+        let offset: Vector = (right * delta_x + up * delta_y) * pixel_size;
+
+        self.pivot = self.pivot + offset;
+        camera.set_center(*camera.center() + offset);
+    }
+
+    /**
+     * Factors below 1 zoom in.
+     * Perspective cameras dolly towards the pivot, stopping at the near plane, orthographic ones change their scale.
+     */
+    pub fn zoom(&self, camera: &mut Camera, factor: f32) {
+        match camera.projection() {
+            Projection::Perspective => {
+                let (yaw, pitch): (f32, f32) = yaw_and_pitch(&camera.forward());
+                let distance: f32 = (self.distance(camera) * factor).max(camera.near());
+
+                self.place(camera, yaw, pitch, distance);
+            }
+            Projection::Orthographic { scale } => {
+                camera.set_projection(Projection::Orthographic { scale: scale * factor });
+            }
+        }
+    }
+
+    fn distance(&self, camera: &Camera) -> f32 {
+        (*camera.center() - self.pivot).length()
+    }
+
+    fn place(&self, camera: &mut Camera, yaw: f32, pitch: f32, distance: f32) {
+        camera.set_orientation(level_orientation(yaw, pitch));
+        camera.set_center(self.pivot + camera.forward() * -distance);
+    }
+}
+
+impl FlyController {
+    /** in degrees, positive yaw turns right, positive pitch looks up */
+    pub fn look(&self, camera: &mut Camera, delta_yaw: f32, delta_pitch: f32) {
+        let (yaw, pitch): (f32, f32) = yaw_and_pitch(&camera.forward());
+
+        camera.set_orientation(level_orientation(yaw + delta_yaw, pitch - delta_pitch));
+    }
+
+    /** x moves right, y up along the world y axis and z forward in the view direction */
+    pub fn fly(&self, camera: &mut Camera, delta: &Vector) {
+        let right: Vector = camera.orientation().rotate_vector(&Vector::new(1.0, 0.0, 0.0));
+        let offset: Vector = right * delta.x + WORLD_UP * delta.y + camera.forward() * delta.z;
+
+        camera.set_center(*camera.center() + offset);
+    }
+}
+
+/** angles of Quaternion::from_euler turning the z axis into the direction, positive pitch looks down */
+fn yaw_and_pitch(direction: &Vector) -> (f32, f32) {
+    let direction: Vector = direction.normalize();
+
+    (
+        direction.x.atan2(direction.z).to_degrees(),
+        (-direction.y).clamp(-1.0, 1.0).asin().to_degrees()
+    )
+}
+
+fn level_orientation(yaw: f32, pitch: f32) -> Quaternion {
+    Quaternion::from_euler(pitch.clamp(-MAX_PITCH, MAX_PITCH), yaw, 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::scene::camera::controller::{FlyController, OrbitController, MAX_PITCH, WORLD_UP};
+    use crate::rendering_engine::scene::camera::display::Display;
+    use crate::rendering_engine::scene::camera::{Camera, Projection};
+    use crate::rendering_engine::scene::model_3d::point::Point;
+    use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+    fn create_camera(center: Point) -> Camera {
+        Camera::new(100.0, center, 0.0, 0.0, 0.0, Display::new(200, 100))
+    }
+
+    fn assert_close(actual: &Vector, expected: &Vector) {
+        assert!((*actual - *expected).length() < 1e-3, "{:?} is not {:?}", actual, expected);
+    }
+
+    #[test]
+    fn look_at_turns_to_the_target() {
+        let mut camera: Camera = create_camera(Point { x: 0.0, y: 0.0, z: 0.0 });
+
+        assert!(camera.look_at(&Point { x: 10.0, y: 0.0, z: 0.0 }, &WORLD_UP));
+        assert_close(&camera.forward(), &Vector::new(1.0, 0.0, 0.0));
+        assert_close(&camera.orientation().rotate_vector(&Vector::new(0.0, 1.0, 0.0)), &WORLD_UP);
+
+        assert!(!camera.look_at(&Point { x: 0.0, y: 5.0, z: 0.0 }, &WORLD_UP));
+        assert_close(&camera.forward(), &Vector::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn orbit_keeps_the_distance_to_the_pivot() {
+        let mut camera: Camera = create_camera(Point { x: 0.0, y: 0.0, z: -50.0 });
+        let pivot: Point = Point { x: 0.0, y: 0.0, z: 50.0 };
+        let controller: OrbitController = OrbitController::new(pivot);
+
+        controller.attach(&mut camera);
+        controller.orbit(&mut camera, 90.0, 0.0);
+        /* moved to the right of the pivot, looking left */
+        assert_close(&(*camera.center() - pivot), &Vector::new(100.0, 0.0, 0.0));
+        assert_close(&camera.forward(), &Vector::new(-1.0, 0.0, 0.0));
+
+        controller.orbit(&mut camera, 0.0, 200.0);
+        assert!(((*camera.center() - pivot).length() - 100.0).abs() < 1e-3);
+        assert!((camera.forward().y + MAX_PITCH.to_radians().sin()).abs() < 1e-3);
+
+        controller.zoom(&mut camera, 0.5);
+        assert!(((*camera.center() - pivot).length() - 50.0).abs() < 1e-3);
+        assert_close(&(pivot - *camera.center()).normalize(), &camera.forward());
+    }
+
+    #[test]
+    fn pan_follows_the_cursor_at_the_pivot() {
+        let mut camera: Camera = create_camera(Point { x: 0.0, y: 0.0, z: 0.0 });
+        let mut controller: OrbitController = OrbitController::new(Point { x: 0.0, y: 0.0, z: 200.0 });
+
+        /* a pixel is 2 units wide 200 units in front of a camera with the focal length of 100 */
+        controller.pan(&mut camera, 10.0, -5.0);
+        assert_close(&controller.pivot.to_vector(), &Vector::new(20.0, -10.0, 200.0));
+        assert_close(&camera.center().to_vector(), &Vector::new(20.0, -10.0, 0.0));
+
+        camera.set_projection(Projection::Orthographic { scale: 0.5 });
+        controller.zoom(&mut camera, 4.0);
+        assert_eq!(camera.projection(), Projection::Orthographic { scale: 2.0 });
+    }
+
+    #[test]
+    fn fly_clamps_the_pitch() {
+        let mut camera: Camera = create_camera(Point { x: 0.0, y: 0.0, z: 0.0 });
+        let controller: FlyController = FlyController;
+
+        controller.look(&mut camera, 90.0, 0.0);
+        assert_close(&camera.forward(), &Vector::new(1.0, 0.0, 0.0));
+
+        controller.fly(&mut camera, &Vector::new(0.0, 1.0, 10.0));
+        assert_close(&camera.center().to_vector(), &Vector::new(10.0, 1.0, 0.0));
+
+        controller.look(&mut camera, 0.0, 120.0);
+        assert!((camera.forward().y - MAX_PITCH.to_radians().sin()).abs() < 1e-3);
+    }
+}
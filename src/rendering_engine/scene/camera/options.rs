@@ -0,0 +1,226 @@
+use serde::Deserialize;
+
+use crate::rendering_engine::error::RenderError;
+use crate::rendering_engine::scene::camera::display::Display;
+use crate::rendering_engine::scene::camera::sensor::Sensor;
+use crate::rendering_engine::scene::camera::{Camera, Projection, DEFAULT_FAR, DEFAULT_NEAR};
+use crate::rendering_engine::scene::model_3d::point::Point;
+
+pub const DEFAULT_RESOLUTION: usize = 800;
+
+/**
+ * Parameters of a new camera, every field is optional.
+ * The field of view is set by one of: the focal length in pixels, the vertical or horizontal field of view in degrees,
+ * or the lens focal length in millimeters on the sensor, full frame by default.
+ * Without any of them the focal length is the display width plus height.
+ * Angles are in degrees, like the ones of Camera::rotate.
+ * The projection is "perspective" by default or {"orthographic": {"scale": world units per pixel}}.
+ */
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraOptions {
+    pub width: usize,
+    pub height: usize,
+    pub position: Point,
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+    pub focal_length: Option<f32>,
+    pub vertical_fov: Option<f32>,
+    pub horizontal_fov: Option<f32>,
+    pub lens_focal_length: Option<f32>,
+    pub sensor: Option<Sensor>,
+    pub near: f32,
+    pub far: f32,
+    pub projection: Projection,
+}
+
+impl Default for CameraOptions {
+    fn default() -> Self {
+        CameraOptions {
+            width: DEFAULT_RESOLUTION,
+            height: DEFAULT_RESOLUTION,
+            position: Point { x: 0.0, y: 0.0, z: 0.0 },
+            pitch: 0.0,
+            yaw: 0.0,
+            roll: 0.0,
+            focal_length: None,
+            vertical_fov: None,
+            horizontal_fov: None,
+            lens_focal_length: None,
+            sensor: None,
+            near: DEFAULT_NEAR,
+            far: DEFAULT_FAR,
+            projection: Projection::Perspective,
+        }
+    }
+}
+
+impl CameraOptions {
+    /** fails with RenderError::BadInput naming the first invalid option */
+    pub fn to_camera(&self) -> Result<Camera, RenderError> {
+        let bad_input = |message: String| Err(RenderError::BadInput(message));
+
+        if self.width == 0 || self.height == 0 {
+            return bad_input(format!("resolution {}x{} is empty", self.width, self.height));
+        }
+
+        let Point { x, y, z } = self.position;
+        if ![x, y, z, self.pitch, self.yaw, self.roll].iter().all(|value: &f32| value.is_finite()) {
+            return bad_input(String::from("position and angles must be finite"));
+        }
+
+        if let (None, Some(_)) = (self.lens_focal_length, self.sensor) {
+            return bad_input(String::from("sensor is set without lens_focal_length"));
+        }
+
+        let field_of_view_options: usize = [self.focal_length, self.vertical_fov, self.horizontal_fov, self.lens_focal_length]
+            .iter()
+            .filter(|option: &&Option<f32>| option.is_some())
+            .count();
+
+        if field_of_view_options > 1 {
+            return bad_input(String::from("focal_length, vertical_fov, horizontal_fov and lens_focal_length are exclusive"));
+        }
+
+        check_clipping_distances(self.near, self.far)?;
+
+        check_projection(&self.projection)?;
+
+        let display: Display = Display::new(self.width, self.height);
+        let (position, pitch, yaw, roll): (Point, f32, f32, f32) = (self.position, self.pitch, self.yaw, self.roll);
+
+        let mut camera: Camera = if let Some(focal_length) = self.focal_length {
+            check_positive("focal_length", focal_length)?;
+            Camera::new(focal_length, position, pitch, yaw, roll, display)
+        } else if let Some(vertical_fov) = self.vertical_fov {
+            check_fov("vertical_fov", vertical_fov)?;
+            Camera::from_vertical_fov(vertical_fov, position, pitch, yaw, roll, display)
+        } else if let Some(horizontal_fov) = self.horizontal_fov {
+            check_fov("horizontal_fov", horizontal_fov)?;
+            Camera::from_horizontal_fov(horizontal_fov, position, pitch, yaw, roll, display)
+        } else if let Some(lens_focal_length) = self.lens_focal_length {
+            let sensor: Sensor = self.sensor.unwrap_or(Sensor::FULL_FRAME);
+            check_lens(lens_focal_length, &sensor)?;
+            Camera::from_lens(lens_focal_length, &sensor, position, pitch, yaw, roll, display)
+        } else {
+            Camera::new((self.width + self.height) as f32, position, pitch, yaw, roll, display)
+        };
+
+        camera.set_clipping_distances(self.near, self.far);
+        camera.set_projection(self.projection);
+
+        Ok(camera)
+    }
+}
+
+pub fn check_positive(name: &str, value: f32) -> Result<(), RenderError> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(RenderError::BadInput(format!("{} {} is not positive", name, value)))
+    }
+}
+
+/** field of view in degrees */
+pub fn check_fov(name: &str, fov: f32) -> Result<(), RenderError> {
+    if fov > 0.0 && fov < 180.0 {
+        Ok(())
+    } else {
+        Err(RenderError::BadInput(format!("{} {} is not between 0 and 180 degrees", name, fov)))
+    }
+}
+
+pub fn check_clipping_distances(near: f32, far: f32) -> Result<(), RenderError> {
+    if near.is_finite() && near > 0.0 && far > near {
+        Ok(())
+    } else {
+        Err(RenderError::BadInput(format!("clipping distances {} and {} must satisfy 0 < near < far", near, far)))
+    }
+}
+
+pub fn check_lens(lens_focal_length: f32, sensor: &Sensor) -> Result<(), RenderError> {
+    check_positive("lens_focal_length", lens_focal_length)?;
+    check_positive("sensor width", sensor.width)?;
+    check_positive("sensor height", sensor.height)
+}
+
+pub fn check_projection(projection: &Projection) -> Result<(), RenderError> {
+    match projection {
+        Projection::Perspective => Ok(()),
+        Projection::Orthographic { scale } => check_positive("orthographic scale", *scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::scene::camera::options::CameraOptions;
+    use crate::rendering_engine::scene::camera::sensor::Sensor;
+    use crate::rendering_engine::scene::camera::{Camera, Projection};
+
+    #[test]
+    fn vertical_fov_sets_focal_length() {
+        let camera: Camera = CameraOptions {
+            width: 640,
+            height: 480,
+            vertical_fov: Some(90.0),
+            near: 0.5,
+            far: 50.0,
+            ..CameraOptions::default()
+        }.to_camera().unwrap();
+
+        assert!((camera.focal_length() - 240.0).abs() < 1e-3);
+        assert_eq!((camera.display().width, camera.display().height), (640, 480));
+        assert_eq!((camera.near(), camera.far()), (0.5, 50.0));
+
+        assert_eq!(CameraOptions::default().to_camera().unwrap().focal_length(), 1600.0);
+    }
+
+    #[test]
+    fn lens_and_horizontal_fov_are_consistent() {
+        /* a 16:9 display crops the top and bottom of the full frame sensor, so the sensor width fills the display */
+        let lens: Camera = CameraOptions {
+            width: 1920,
+            height: 1080,
+            lens_focal_length: Some(36.0),
+            ..CameraOptions::default()
+        }.to_camera().unwrap();
+
+        assert!((lens.focal_length() - 1920.0).abs() < 1e-2);
+        assert!((lens.horizontal_fov() - 53.130).abs() < 1e-2);
+        assert!((lens.lens_focal_length(&Sensor::FULL_FRAME) - 36.0).abs() < 1e-3);
+
+        let mut fov: Camera = CameraOptions {
+            width: 800,
+            height: 400,
+            horizontal_fov: Some(90.0),
+            ..CameraOptions::default()
+        }.to_camera().unwrap();
+
+        assert!((fov.focal_length() - 400.0).abs() < 1e-2);
+        assert!((fov.vertical_fov() - 53.130).abs() < 1e-2);
+
+        fov.set_vertical_fov(90.0);
+        assert!((fov.focal_length() - 200.0).abs() < 1e-2);
+        assert!((fov.horizontal_fov() - 126.870).abs() < 1e-2);
+    }
+
+    #[test]
+    fn invalid_options_are_rejected() {
+        let invalid: [CameraOptions; 9] = [
+            CameraOptions { width: 0, ..CameraOptions::default() },
+            CameraOptions { focal_length: Some(-1.0), ..CameraOptions::default() },
+            CameraOptions { vertical_fov: Some(180.0), ..CameraOptions::default() },
+            CameraOptions { focal_length: Some(100.0), vertical_fov: Some(60.0), ..CameraOptions::default() },
+            CameraOptions { near: 10.0, far: 10.0, ..CameraOptions::default() },
+            CameraOptions { yaw: f32::NAN, ..CameraOptions::default() },
+            CameraOptions { lens_focal_length: Some(50.0), sensor: Some(Sensor::new(0.0, 24.0)), ..CameraOptions::default() },
+            CameraOptions { sensor: Some(Sensor::APS_C), ..CameraOptions::default() },
+            CameraOptions { projection: Projection::Orthographic { scale: 0.0 }, ..CameraOptions::default() },
+        ];
+
+        for options in invalid {
+            assert!(options.to_camera().is_err());
+        }
+    }
+}
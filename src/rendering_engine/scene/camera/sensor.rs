@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rendering_engine::scene::camera::display::Display;
+
+/** physical size of a camera sensor in millimeters */
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct Sensor {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Sensor {
+    /** 35mm film */
+    pub const FULL_FRAME: Sensor = Sensor { width: 36.0, height: 24.0 };
+    pub const APS_C: Sensor = Sensor { width: 23.6, height: 15.6 };
+    pub const MICRO_FOUR_THIRDS: Sensor = Sensor { width: 17.3, height: 13.0 };
+
+    pub fn new(width: f32, height: f32) -> Sensor {
+        Sensor { width, height }
+    }
+
+    /**
+     * Scale of the sensor on the display.
+     * The display shows the largest part of the sensor with the display aspect ratio,
+     * so a 16:9 display on a 3:2 sensor crops its top and bottom.
+     */
+    pub fn pixels_per_millimeter(&self, display: &Display) -> f32 {
+        (display.width as f32 / self.width).max(display.height as f32 / self.height)
+    }
+}
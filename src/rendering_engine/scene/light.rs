@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rendering_engine::error::RenderError;
+use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model_3d::matrix::Mat4;
+use crate::rendering_engine::scene::model_3d::point::Point;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+/** intensity of a light at a distance is divided by constant + linear * distance + quadratic * distance^2 */
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct Attenuation {
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+/**
+ * Light of the scene, placed in the world space or in the space of the node it is attached to.
+ * The alpha of the color is ignored, intensity 1 lights a face facing the light by the full color of the light.
+ * Directions are the ones the light shines in, they don't have to be unit vectors.
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Light {
+    /** lights every face evenly, regardless of its orientation */
+    Ambient { color: Color, intensity: f32 },
+    /** infinitely distant light, like the sun */
+    Directional { color: Color, intensity: f32, direction: Vector },
+    Point {
+        color: Color,
+        intensity: f32,
+        position: Point,
+        #[serde(default)]
+        attenuation: Attenuation,
+    },
+    /** point light limited to a cone, full within the inner angle and fading out to the outer one, angles from the axis in degrees */
+    Spot {
+        color: Color,
+        intensity: f32,
+        position: Point,
+        direction: Vector,
+        inner_angle: f32,
+        outer_angle: f32,
+        #[serde(default)]
+        attenuation: Attenuation,
+    },
+}
+
+impl Attenuation {
+    /** intensity doesn't fall off with the distance */
+    pub const NONE: Attenuation = Attenuation { constant: 1.0, linear: 0.0, quadratic: 0.0 };
+
+    pub fn new(constant: f32, linear: f32, quadratic: f32) -> Attenuation {
+        Attenuation { constant, linear, quadratic }
+    }
+
+    pub fn factor(&self, distance: f32) -> f32 {
+        1.0 / (self.constant + self.linear * distance + self.quadratic * distance * distance)
+    }
+}
+
+impl Default for Attenuation {
+    fn default() -> Self {
+        Attenuation::NONE
+    }
+}
+
+impl Light {
+    /** fails with RenderError::BadInput naming the first invalid parameter */
+    pub fn check(&self) -> Result<(), RenderError> {
+        let bad_input = |message: &str| Err(RenderError::BadInput(format!("light {}", message)));
+
+        let intensity: f32 = match self {
+            Light::Ambient { intensity, .. }
+            | Light::Directional { intensity, .. }
+            | Light::Point { intensity, .. }
+            | Light::Spot { intensity, .. } => *intensity
+        };
+
+        if !(intensity.is_finite() && intensity >= 0.0) {
+            return bad_input("intensity must be finite and not negative");
+        }
+
+        match self {
+            Light::Ambient { .. } => Ok(()),
+            Light::Directional { direction, .. } => check_direction(direction),
+            Light::Point { position, attenuation, .. } => {
+                check_position(position)?;
+                check_attenuation(attenuation)
+            }
+            Light::Spot { position, direction, inner_angle, outer_angle, attenuation, .. } => {
+                check_position(position)?;
+                check_direction(direction)?;
+                check_attenuation(attenuation)?;
+
+                if !(*inner_angle >= 0.0 && inner_angle <= outer_angle && *outer_angle < 90.0) {
+                    return bad_input("angles must satisfy 0 <= inner_angle <= outer_angle < 90");
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /** the light moved by the matrix, for example into the camera space */
+    pub fn transform(&self, matrix: &Mat4) -> Light {
+        let mut light: Light = *self;
+
+        match &mut light {
+            Light::Ambient { .. } => {}
+            Light::Directional { direction, .. } => *direction = matrix.transform_vector(direction),
+            Light::Point { position, .. } => *position = matrix.transform_point(position),
+            Light::Spot { position, direction, .. } => {
+                *position = matrix.transform_point(position);
+                *direction = matrix.transform_vector(direction);
+            }
+        }
+
+        light
+    }
+
+    /**
+     * Light reaching the point: the unit direction from the point to the light, None for ambient lights,
+     * and the red, green and blue factors, attenuated but not yet weakened by the angle of the surface.
+     */
+    pub fn reach(&self, point: &Point) -> (Option<Vector>, [f32; 3]) {
+        let (color, intensity): (&Color, f32) = match self {
+            Light::Ambient { color, intensity }
+            | Light::Directional { color, intensity, .. }
+            | Light::Point { color, intensity, .. }
+            | Light::Spot { color, intensity, .. } => (color, *intensity)
+        };
+
+        let (to_light, strength): (Option<Vector>, f32) = match self {
+            Light::Ambient { .. } => (None, 1.0),
+            Light::Directional { direction, .. } => (Some(-direction.normalize()), 1.0),
+            Light::Point { position, attenuation, .. } => {
+                let to_light: Vector = *position - *point;
+
+                (Some(to_light.normalize()), attenuation.factor(to_light.length()))
+            }
+            Light::Spot { position, direction, inner_angle, outer_angle, attenuation, .. } => {
+                let to_light: Vector = *position - *point;
+                let to_light_direction: Vector = to_light.normalize();
+
+                let cos_angle: f32 = -to_light_direction.dot(&direction.normalize());
+                let cos_inner: f32 = inner_angle.to_radians().cos();
+                let cos_outer: f32 = outer_angle.to_radians().cos();
+
+                let cone: f32 = if cos_inner > cos_outer {
+                    ((cos_angle - cos_outer) / (cos_inner - cos_outer)).clamp(0.0, 1.0)
+                } else if cos_angle >= cos_outer {
+                    1.0
+                } else {
+                    0.0
+                };
+
+                (Some(to_light_direction), attenuation.factor(to_light.length()) * cone)
+            }
+        };
+
+        (to_light, [color.r, color.g, color.b].map(|channel: u8| channel as f32 / 255.0 * intensity * strength))
+    }
+
+    /**
+     * Light falling onto a surface at the point with the unit normal, by the Lambert's cosine law.
+     * Returns the red, green and blue factors the surface color is multiplied by.
+     */
+    pub fn illuminate(&self, point: &Point, normal: &Vector) -> [f32; 3] {
+        let (to_light, light): (Option<Vector>, [f32; 3]) = self.reach(point);
+        let cosine: f32 = to_light.map_or(1.0, |to_light: Vector| lambert(normal, &to_light));
+
+        light.map(|channel: f32| channel * cosine)
+    }
+}
+
+/** cosine of the angle between the normal and the direction to the light, zero for surfaces facing away */
+fn lambert(normal: &Vector, to_light: &Vector) -> f32 {
+    normal.dot(to_light).max(0.0)
+}
+
+fn check_position(position: &Point) -> Result<(), RenderError> {
+    if [position.x, position.y, position.z].iter().all(|value: &f32| value.is_finite()) {
+        Ok(())
+    } else {
+        Err(RenderError::BadInput(String::from("light position must be finite")))
+    }
+}
+
+fn check_direction(direction: &Vector) -> Result<(), RenderError> {
+    if direction.length().is_finite() && direction.length() > 0.0 {
+        Ok(())
+    } else {
+        Err(RenderError::BadInput(String::from("light direction must be finite and not zero")))
+    }
+}
+
+fn check_attenuation(attenuation: &Attenuation) -> Result<(), RenderError> {
+    let Attenuation { constant, linear, quadratic } = *attenuation;
+
+    if [constant, linear, quadratic].iter().all(|value: &f32| value.is_finite() && *value >= 0.0) && constant + linear + quadratic > 0.0 {
+        Ok(())
+    } else {
+        Err(RenderError::BadInput(String::from("light attenuation must not be negative and not all zero")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::scene::light::{Attenuation, Light};
+    use crate::rendering_engine::scene::model::color::Color;
+    use crate::rendering_engine::scene::model_3d::point::Point;
+    use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+    const WHITE: Color = Color { r: 255, g: 255, b: 255, a: 1.0 };
+    const ORIGIN: Point = Point { x: 0.0, y: 0.0, z: 0.0 };
+    const UP: Vector = Vector { x: 0.0, y: 1.0, z: 0.0 };
+
+    fn strength(light: &Light) -> f32 {
+        light.illuminate(&ORIGIN, &UP)[0]
+    }
+
+    #[test]
+    fn lambert_and_attenuation() {
+        let directional = |direction: Vector| Light::Directional { color: WHITE, intensity: 1.0, direction };
+
+        assert!((strength(&directional(Vector::new(0.0, -2.0, 0.0))) - 1.0).abs() < 1e-6);
+        assert!((strength(&directional(Vector::new(1.0, -1.0, 0.0))) - 0.5f32.sqrt()).abs() < 1e-6);
+        assert_eq!(strength(&directional(Vector::new(0.0, 1.0, 0.0))), 0.0);
+
+        let point: Light = Light::Point {
+            color: Color::new(255, 0, 0, 1.0),
+            intensity: 2.0,
+            position: Point { x: 0.0, y: 10.0, z: 0.0 },
+            attenuation: Attenuation::new(1.0, 0.1, 0.0),
+        };
+        assert_eq!(point.illuminate(&ORIGIN, &UP), [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn spot_cone_fades_out() {
+        let spot = |x: f32| Light::Spot {
+            color: WHITE,
+            intensity: 1.0,
+            position: Point { x, y: 10.0, z: 0.0 },
+            direction: Vector::new(0.0, -1.0, 0.0),
+            inner_angle: 10.0,
+            outer_angle: 50.0,
+            attenuation: Attenuation::NONE,
+        };
+
+        /* the origin is 45 degrees off the axis at x = 10, between the inner and the outer angle */
+        assert!(strength(&spot(0.0)) > 0.99);
+        assert!(strength(&spot(10.0)) > 0.0 && strength(&spot(10.0)) < 0.5f32.sqrt());
+        assert_eq!(strength(&spot(20.0)), 0.0);
+    }
+
+    #[test]
+    fn invalid_lights_are_rejected() {
+        let invalid: [Light; 4] = [
+            Light::Ambient { color: WHITE, intensity: -1.0 },
+            Light::Directional { color: WHITE, intensity: 1.0, direction: Vector::zero() },
+            Light::Point { color: WHITE, intensity: 1.0, position: ORIGIN, attenuation: Attenuation::new(0.0, 0.0, 0.0) },
+            Light::Spot {
+                color: WHITE,
+                intensity: 1.0,
+                position: ORIGIN,
+                direction: UP,
+                inner_angle: 40.0,
+                outer_angle: 30.0,
+                attenuation: Attenuation::NONE,
+            },
+        ];
+
+        for light in invalid {
+            assert!(light.check().is_err());
+        }
+    }
+}
@@ -1 +1,4 @@
-pub mod color;
\ No newline at end of file
+pub mod color;
+pub mod culling;
+pub mod shading;
+pub mod texture;
\ No newline at end of file
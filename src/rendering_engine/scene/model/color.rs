@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[derive(Serialize, Deserialize)]
 pub struct Color {
     pub r: u8,
@@ -13,4 +13,8 @@ impl Color {
     pub fn new(r: u8, g: u8, b: u8, a: f32) -> Self {
         Self { r, g, b, a }
     }
+
+    pub fn is_opaque(&self) -> bool {
+        self.a >= 1.0
+    }
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/** faces of a mesh which are not drawn, see Face for which side of a face is the front */
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Culling {
+    /** both sides are drawn, for meshes with inconsistent winding or open surfaces */
+    #[default]
+    None,
+    /** faces seen from the back are dropped, for closed meshes wound consistently */
+    Back,
+    Front,
+}
+
+impl Culling {
+    pub fn culls(&self, front_facing: bool) -> bool {
+        match self {
+            Culling::None => false,
+            Culling::Back => !front_facing,
+            Culling::Front => front_facing,
+        }
+    }
+}
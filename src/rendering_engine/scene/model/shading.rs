@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rendering_engine::error::RenderError;
+
+/** how the faces of a mesh are lit, meshes are drawn with their flat colors in scenes without lights */
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Shading {
+    /** one color per face, lit at its center */
+    #[default]
+    Flat,
+    /** lit at the vertices, the colors are interpolated across the face */
+    Gouraud,
+    /**
+     * Normals are interpolated and every pixel is lit, with a Blinn-Phong highlight in the color of the light.
+     * specular scales the highlight, higher shininess makes it smaller.
+     */
+    Phong { specular: f32, shininess: f32 },
+}
+
+impl Shading {
+    /** fails with RenderError::BadInput if a Phong parameter is invalid */
+    pub fn check(&self) -> Result<(), RenderError> {
+        match self {
+            Shading::Phong { specular, shininess } if !(specular.is_finite() && *specular >= 0.0 && shininess.is_finite() && *shininess > 0.0) => {
+                Err(RenderError::BadInput(format!("specular {} must not be negative and shininess {} must be positive", specular, shininess)))
+            }
+            _ => Ok(())
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rendering_engine::error::RenderError;
+use crate::rendering_engine::format::image::decode_image;
+use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
+
+/**
+ * Image mapped onto meshes by their texture coordinates.
+ * (0, 0) is the top left corner of the image and (1, 1) the bottom right one, like in glTF,
+ * coordinates outside of them are wrapped by the sampler.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct Texture {
+    width: usize,
+    height: usize,
+    /** 4 bytes per pixel, rows from top to bottom */
+    rgba: Vec<u8>,
+}
+
+/** how a texture is read between its pixels and outside of the coordinates 0 to 1 */
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sampler {
+    pub filter: Filter,
+    pub wrap: Wrap,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Filter {
+    /** color of the closest pixel, keeps pixel art sharp */
+    Nearest,
+    /** blend of the four closest pixels */
+    #[default]
+    Bilinear,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Wrap {
+    /** the texture tiles */
+    #[default]
+    Repeat,
+    /** the edge pixels stretch out */
+    Clamp,
+    /** the texture tiles, every other tile flipped */
+    Mirror,
+}
+
+impl Texture {
+    /** fails if the image is empty or the bytes are not 4 per pixel */
+    pub fn new(width: usize, height: usize, rgba: Vec<u8>) -> Result<Texture, RenderError> {
+        let texture: Texture = Texture { width, height, rgba };
+
+        texture.check().map_err(RenderError::BadInput)?;
+
+        Ok(texture)
+    }
+
+    /** PNG or PPM file contents */
+    pub fn decode(bytes: &[u8]) -> Result<Texture, RenderError> {
+        let (width, height, rgba): (usize, usize, Vec<u8>) = decode_image(bytes)?;
+
+        Texture::new(width, height, rgba)
+    }
+
+    /** describes the problem of a texture which was deserialized without Texture::new */
+    pub(crate) fn check(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err(format!("texture of {}x{} pixels is empty", self.width, self.height));
+        }
+
+        if self.width.checked_mul(self.height).and_then(|pixels: usize| pixels.checked_mul(4)) != Some(self.rgba.len()) {
+            return Err(format!("texture of {}x{} pixels has {} bytes", self.width, self.height, self.rgba.len()));
+        }
+
+        Ok(())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /** every pixel has full alpha */
+    pub fn is_opaque(&self) -> bool {
+        self.rgba.chunks_exact(4).all(|pixel: &[u8]| pixel[3] == 255)
+    }
+
+    /** color of the texture at the texture coordinates */
+    pub fn sample(&self, uv: &Point2D, sampler: &Sampler) -> Color {
+        let x: f32 = uv.x * self.width as f32;
+        let y: f32 = uv.y * self.height as f32;
+
+        let rgba: [f32; 4] = match sampler.filter {
+            Filter::Nearest => self.pixel(x.floor() as i64, y.floor() as i64, sampler.wrap),
+            Filter::Bilinear => {
+                /* pixel centers are at half coordinates */
+                let (x, y): (f32, f32) = (x - 0.5, y - 0.5);
+                let (left, top): (i64, i64) = (x.floor() as i64, y.floor() as i64);
+                let (tx, ty): (f32, f32) = (x - x.floor(), y - y.floor());
+
+                let lerp = |a: [f32; 4], b: [f32; 4], t: f32| [0, 1, 2, 3].map(|i: usize| a[i] + (b[i] - a[i]) * t);
+
+                lerp(
+                    lerp(self.pixel(left, top, sampler.wrap), self.pixel(left + 1, top, sampler.wrap), tx),
+                    lerp(self.pixel(left, top + 1, sampler.wrap), self.pixel(left + 1, top + 1, sampler.wrap), tx),
+                    ty
+                )
+            }
+        };
+
+        let channel = |value: f32| value.round().clamp(0.0, 255.0) as u8;
+
+        Color::new(channel(rgba[0]), channel(rgba[1]), channel(rgba[2]), rgba[3] / 255.0)
+    }
+
+    fn pixel(&self, x: i64, y: i64, wrap: Wrap) -> [f32; 4] {
+        let offset: usize = (wrap_index(y, self.height, wrap) * self.width + wrap_index(x, self.width, wrap)) * 4;
+
+        [0, 1, 2, 3].map(|i: usize| self.rgba[offset + i] as f32)
+    }
+}
+
+/** index of a pixel out of `size` for an index outside of the texture */
+fn wrap_index(index: i64, size: usize, wrap: Wrap) -> usize {
+    let size: i64 = size as i64;
+
+    let index: i64 = match wrap {
+        Wrap::Repeat => index.rem_euclid(size),
+        Wrap::Clamp => index.clamp(0, size - 1),
+        Wrap::Mirror => {
+            let index: i64 = index.rem_euclid(2 * size);
+
+            if index < size { index } else { 2 * size - 1 - index }
+        }
+    };
+
+    index as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::scene::model::color::Color;
+    use crate::rendering_engine::scene::model::texture::{wrap_index, Filter, Sampler, Texture, Wrap};
+    use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
+
+    /** black pixel on the left, white one on the right */
+    fn create_texture() -> Texture {
+        Texture::new(2, 1, vec![0, 0, 0, 255, 255, 255, 255, 255]).unwrap()
+    }
+
+    fn red(texture: &Texture, u: f32, filter: Filter, wrap: Wrap) -> u8 {
+        texture.sample(&Point2D { x: u, y: 0.5 }, &Sampler { filter, wrap }).r
+    }
+
+    #[test]
+    fn wrap_modes() {
+        assert_eq!([-1, 0, 3, 4].map(|index: i64| wrap_index(index, 3, Wrap::Repeat)), [2, 0, 0, 1]);
+        assert_eq!([-1, 0, 3, 4].map(|index: i64| wrap_index(index, 3, Wrap::Clamp)), [0, 0, 2, 2]);
+        assert_eq!([-1, 0, 3, 4, 6].map(|index: i64| wrap_index(index, 3, Wrap::Mirror)), [0, 0, 2, 1, 0]);
+    }
+
+    #[test]
+    fn filters() {
+        let texture: Texture = create_texture();
+
+        assert_eq!(red(&texture, 0.2, Filter::Nearest, Wrap::Repeat), 0);
+        assert_eq!(red(&texture, 0.7, Filter::Nearest, Wrap::Repeat), 255);
+        assert_eq!(red(&texture, 1.2, Filter::Nearest, Wrap::Repeat), 0);
+        assert_eq!(red(&texture, 1.2, Filter::Nearest, Wrap::Clamp), 255);
+
+        /* halfway between the pixel centers */
+        assert_eq!(red(&texture, 0.5, Filter::Bilinear, Wrap::Clamp), 128);
+        assert_eq!(red(&texture, 0.25, Filter::Bilinear, Wrap::Clamp), 0);
+        /* the left edge blends with the right pixel when repeating, and with itself when clamped */
+        assert_eq!(red(&texture, 0.0, Filter::Bilinear, Wrap::Repeat), 128);
+        assert_eq!(red(&texture, 0.0, Filter::Bilinear, Wrap::Clamp), 0);
+
+        assert_eq!(texture.sample(&Point2D { x: 0.75, y: 0.0 }, &Sampler::default()), Color::new(255, 255, 255, 1.0));
+    }
+
+    #[test]
+    fn ppm_is_decoded() {
+        assert_eq!(Texture::decode(b"P3\n# comment\n2 1\n15\n0 0 0 15 15 15\n").unwrap(), create_texture());
+        assert_eq!(Texture::decode(b"P6 2 1 255\n\x00\x00\x00\xff\xff\xff").unwrap(), create_texture());
+        assert!(Texture::decode(b"P6 2 1 255\n\x00\x00\x00\xff").is_err());
+    }
+
+    #[test]
+    fn invalid_textures_are_rejected() {
+        assert!(Texture::new(0, 1, Vec::new()).is_err());
+        assert!(Texture::new(2, 2, vec![0; 15]).is_err());
+        assert!(Texture::decode(b"GIF89a").is_err());
+    }
+}
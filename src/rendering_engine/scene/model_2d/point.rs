@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone)]
 #[derive(Serialize, Deserialize)]
 pub struct Point {
     pub x: f32,
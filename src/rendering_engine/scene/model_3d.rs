@@ -3,6 +3,8 @@ pub mod triangle;
 pub mod vector;
 pub mod plane;
 pub mod axis;
-pub mod plane_direction;
 pub mod mesh;
-pub mod face;
\ No newline at end of file
+pub mod face;
+pub mod matrix;
+pub mod quaternion;
+pub mod transform;
\ No newline at end of file
@@ -2,6 +2,13 @@ use serde::{Deserialize, Serialize};
 
 use crate::rendering_engine::scene::model::color::Color;
 
+/**
+ * Triangle of a mesh, indices of its points.
+ * The normal (b - a) x (c - a) of the points a, b, c points out of the front side of the face,
+ * so in the left-handed space the points of a front face go clockwise on the display.
+ * Imported meshes follow the same convention, see parse_gltf.
+ */
+#[derive(Copy, Clone)]
 #[derive(Serialize, Deserialize)]
 pub struct Face {
     pub vertices: [usize; 3],
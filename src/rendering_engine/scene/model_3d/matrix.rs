@@ -0,0 +1,424 @@
+use std::ops::Mul;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rendering_engine::scene::model_3d::point::Point;
+use crate::rendering_engine::scene::model_3d::quaternion::Quaternion;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+/**
+ * Matrices are stored by rows and transform column vectors, so `a * b` applies `b` first, then `a`.
+ * The camera space is left handed: x points right, y points up and z points forward.
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct Mat3 {
+    pub m: [[f32; 3]; 3],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct Mat4 {
+    pub m: [[f32; 4]; 4],
+}
+
+impl Mat3 {
+    pub fn identity() -> Mat3 {
+        Mat3 {
+            m: [
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+            ]
+        }
+    }
+
+    /** matrix which columns are the given vectors */
+    pub fn from_columns(x: &Vector, y: &Vector, z: &Vector) -> Mat3 {
+        Mat3 {
+            m: [
+                [x.x, y.x, z.x],
+                [x.y, y.y, z.y],
+                [x.z, y.z, z.z],
+            ]
+        }
+    }
+
+    pub fn transpose(&self) -> Mat3 {
+        let mut result: Mat3 = *self;
+
+        for (row, row_values) in self.m.iter().enumerate() {
+            for (col, value) in row_values.iter().enumerate() {
+                result.m[col][row] = *value;
+            }
+        }
+
+        result
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let m: [[f32; 3]; 3] = self.m;
+
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /**
+     * None for singular matrices.
+     * The determinant is compared with the product of the column lengths, which bounds it,
+     * so uniformly tiny or huge scales are still invertible.
+     */
+    pub fn inverse(&self) -> Option<Mat3> {
+        let determinant: f32 = self.determinant();
+        let column_lengths: f32 = (0..3)
+            .map(|col: usize| self.m.iter().map(|row: &[f32; 3]| row[col].powi(2)).sum::<f32>().sqrt())
+            .product();
+
+        if determinant.abs() <= f32::EPSILON * column_lengths {
+            return None;
+        }
+
+        let m: [[f32; 3]; 3] = self.m;
+        let cofactor = |row_0: usize, row_1: usize, col_0: usize, col_1: usize| -> f32 {
+            m[row_0][col_0] * m[row_1][col_1] - m[row_0][col_1] * m[row_1][col_0]
+        };
+
+        /* adjugate divided by determinant */
+        Some(Mat3 {
+            m: [
+                [cofactor(1, 2, 1, 2), -cofactor(0, 2, 1, 2), cofactor(0, 1, 1, 2)],
+                [-cofactor(1, 2, 0, 2), cofactor(0, 2, 0, 2), -cofactor(0, 1, 0, 2)],
+                [cofactor(1, 2, 0, 1), -cofactor(0, 2, 0, 1), cofactor(0, 1, 0, 1)],
+            ].map(|row: [f32; 3]| row.map(|value: f32| value / determinant))
+        })
+    }
+
+    pub fn transform_vector(&self, vector: &Vector) -> Vector {
+        let m: [[f32; 3]; 3] = self.m;
+
+        Vector {
+            x: m[0][0] * vector.x + m[0][1] * vector.y + m[0][2] * vector.z,
+            y: m[1][0] * vector.x + m[1][1] * vector.y + m[1][2] * vector.z,
+            z: m[2][0] * vector.x + m[2][1] * vector.y + m[2][2] * vector.z,
+        }
+    }
+}
+
+impl Mat4 {
+    pub fn identity() -> Mat4 {
+        Mat4 {
+            m: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]
+        }
+    }
+
+    pub fn translation(offset: &Vector) -> Mat4 {
+        let mut matrix: Mat4 = Mat4::identity();
+
+        matrix.m[0][3] = offset.x;
+        matrix.m[1][3] = offset.y;
+        matrix.m[2][3] = offset.z;
+
+        matrix
+    }
+
+    pub fn scale(factors: &Vector) -> Mat4 {
+        let mut matrix: Mat4 = Mat4::identity();
+
+        matrix.m[0][0] = factors.x;
+        matrix.m[1][1] = factors.y;
+        matrix.m[2][2] = factors.z;
+
+        matrix
+    }
+
+    pub fn rotation(rotation: &Quaternion) -> Mat4 {
+        Mat4::from_mat3(&rotation.to_mat3())
+    }
+
+    pub fn from_mat3(matrix: &Mat3) -> Mat4 {
+        let mut result: Mat4 = Mat4::identity();
+
+        for (row, row_values) in matrix.m.iter().enumerate() {
+            result.m[row][..3].copy_from_slice(row_values);
+        }
+
+        result
+    }
+
+    /** upper left 3x3 part, which holds rotation and scale */
+    pub fn to_mat3(self) -> Mat3 {
+        Mat3 {
+            m: [
+                [self.m[0][0], self.m[0][1], self.m[0][2]],
+                [self.m[1][0], self.m[1][1], self.m[1][2]],
+                [self.m[2][0], self.m[2][1], self.m[2][2]],
+            ]
+        }
+    }
+
+    /**
+     * Transforms world space into the camera space of a camera placed at `eye` and looking at `target`.
+     * `up` should not be parallel to the view direction.
+     */
+    pub fn look_at(eye: &Point, target: &Point, up: &Vector) -> Mat4 {
+        let forward: Vector = (*target - *eye).normalize();
+        let right: Vector = up.cross(&forward).normalize();
+        let up: Vector = forward.cross(&right);
+
+        let eye: Vector = eye.to_vector();
+
+        Mat4 {
+            m: [
+                [right.x, right.y, right.z, -right.dot(&eye)],
+                [up.x, up.y, up.z, -up.dot(&eye)],
+                [forward.x, forward.y, forward.z, -forward.dot(&eye)],
+                [0.0, 0.0, 0.0, 1.0],
+            ]
+        }
+    }
+
+    /**
+     * Perspective projection with the focal length in pixels.
+     * After division by w, x and y are pixel offsets from the display center and z is 0 on the near plane and 1 on the far one.
+     * w keeps the distance from the camera XY plane.
+     */
+    pub fn perspective(focal_length: f32, near: f32, far: f32) -> Mat4 {
+        let depth_range: f32 = far - near;
+
+        Mat4 {
+            m: [
+                [focal_length, 0.0, 0.0, 0.0],
+                [0.0, focal_length, 0.0, 0.0],
+                [0.0, 0.0, far / depth_range, -far * near / depth_range],
+                [0.0, 0.0, 1.0, 0.0],
+            ]
+        }
+    }
+
+    /**
+     * Orthographic projection, scale is the size of a pixel in the camera space units.
+     * x and y are pixel offsets from the display center and z is 0 on the near plane and 1 on the far one, w is 1.
+     */
+    pub fn orthographic(scale: f32, near: f32, far: f32) -> Mat4 {
+        let depth_range: f32 = far - near;
+
+        Mat4 {
+            m: [
+                [1.0 / scale, 0.0, 0.0, 0.0],
+                [0.0, 1.0 / scale, 0.0, 0.0],
+                [0.0, 0.0, 1.0 / depth_range, -near / depth_range],
+                [0.0, 0.0, 0.0, 1.0],
+            ]
+        }
+    }
+
+    /**
+     * Gauss–Jordan elimination with partial pivoting, None for singular matrices.
+     * Row operations scale every column along with the original one,
+     * so pivots are compared with the largest value of their original column rather than an absolute epsilon.
+     */
+    pub fn inverse(&self) -> Option<Mat4> {
+        let mut matrix: [[f32; 4]; 4] = self.m;
+        let mut inverse: [[f32; 4]; 4] = Mat4::identity().m;
+        let column_magnitudes: [f32; 4] = [0, 1, 2, 3]
+            .map(|col: usize| self.m.iter().map(|row: &[f32; 4]| row[col].abs()).fold(0.0, f32::max));
+
+        for col in 0..4 {
+            let pivot_row: usize = (col..4)
+                .max_by(|left: &usize, right: &usize| matrix[*left][col].abs().total_cmp(&matrix[*right][col].abs()))
+                .unwrap();
+
+            if matrix[pivot_row][col].abs() <= f32::EPSILON * column_magnitudes[col] {
+                return None;
+            }
+
+            matrix.swap(col, pivot_row);
+            inverse.swap(col, pivot_row);
+
+            let pivot: f32 = matrix[col][col];
+
+            for index in 0..4 {
+                matrix[col][index] /= pivot;
+                inverse[col][index] /= pivot;
+            }
+
+            for row in 0..4 {
+                if row == col {
+                    continue;
+                }
+
+                let factor: f32 = matrix[row][col];
+
+                for index in 0..4 {
+                    matrix[row][index] -= factor * matrix[col][index];
+                    inverse[row][index] -= factor * inverse[col][index];
+                }
+            }
+        }
+
+        Some(Mat4 { m: inverse })
+    }
+
+    /** applies the matrix to the point with w = 1 and returns all four homogeneous coordinates */
+    pub fn transform_homogeneous(&self, point: &Point) -> [f32; 4] {
+        self.m.map(|row: [f32; 4]| row[0] * point.x + row[1] * point.y + row[2] * point.z + row[3])
+    }
+
+    /** transforms a position, the matrix is expected to be affine */
+    pub fn transform_point(&self, point: &Point) -> Point {
+        let [x, y, z, _] = self.transform_homogeneous(point);
+
+        Point { x, y, z }
+    }
+
+    /** transforms a direction, translation is ignored */
+    pub fn transform_vector(&self, vector: &Vector) -> Vector {
+        self.to_mat3().transform_vector(vector)
+    }
+}
+
+impl Default for Mat3 {
+    fn default() -> Self {
+        Mat3::identity()
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Mat4::identity()
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, other: Mat3) -> Mat3 {
+        let mut result: Mat3 = Mat3 { m: [[0.0; 3]; 3] };
+
+        for row in 0..3 {
+            for col in 0..3 {
+                result.m[row][col] = (0..3).map(|index: usize| self.m[row][index] * other.m[index][col]).sum();
+            }
+        }
+
+        result
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, other: Mat4) -> Mat4 {
+        let mut result: Mat4 = Mat4 { m: [[0.0; 4]; 4] };
+
+        for row in 0..4 {
+            for col in 0..4 {
+                result.m[row][col] = (0..4).map(|index: usize| self.m[row][index] * other.m[index][col]).sum();
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::scene::model_3d::matrix::{Mat3, Mat4};
+    use crate::rendering_engine::scene::model_3d::point::Point;
+    use crate::rendering_engine::scene::model_3d::quaternion::Quaternion;
+    use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+    fn assert_identity(matrix: &Mat4, tolerance: f32) {
+        for (row, row_values) in matrix.m.iter().enumerate() {
+            for (col, value) in row_values.iter().enumerate() {
+                let expected: f32 = if row == col { 1.0 } else { 0.0 };
+
+                assert!((value - expected).abs() < tolerance, "{:?}", matrix);
+            }
+        }
+    }
+
+    fn assert_point_eq(actual: [f32; 3], expected: [f32; 3]) {
+        assert!(actual.iter().zip(expected).all(|(actual, expected): (&f32, f32)| (actual - expected).abs() < 1e-4), "{:?} != {:?}", actual, expected);
+    }
+
+    /** rotation, scale and translation in the same scale */
+    fn create_transform(scale: f32) -> Mat4 {
+        Mat4::translation(&(Vector::new(3.0, -40.0, 500.0) * scale))
+            * Mat4::rotation(&Quaternion::from_euler(30.0, 45.0, 60.0))
+            * Mat4::scale(&Vector::new(scale, scale * 2.0, scale))
+    }
+
+    #[test]
+    fn inverse_undoes_the_matrix() {
+        for scale in [1e-3, 1.0, 1e3] {
+            let matrix: Mat4 = create_transform(scale);
+
+            /* the translation is rounded in the scale of the matrix */
+            let inverse: Mat4 = matrix.inverse().unwrap();
+            assert_identity(&(inverse * matrix), 1e-4 * scale.max(1.0));
+
+            let inverse: Mat3 = matrix.to_mat3().inverse().unwrap();
+            assert_identity(&Mat4::from_mat3(&(inverse * matrix.to_mat3())), 1e-4);
+        }
+    }
+
+    #[test]
+    fn singular_matrices_have_no_inverse() {
+        let flat: Mat4 = Mat4::scale(&Vector::new(1.0, 1.0, 0.0));
+
+        assert!(flat.inverse().is_none());
+        assert!(flat.to_mat3().inverse().is_none());
+
+        /* two equal rows */
+        let mut matrix: Mat3 = Mat3::identity();
+        matrix.m[1] = matrix.m[0];
+
+        assert!(matrix.inverse().is_none());
+    }
+
+    #[test]
+    fn perspective_projects_to_pixels_and_depth() {
+        let projection: Mat4 = Mat4::perspective(100.0, 1.0, 101.0);
+
+        let [x, y, z, w] = projection.transform_homogeneous(&Point { x: 10.0, y: 20.0, z: 50.0 });
+        assert_point_eq([x / w, y / w, w], [20.0, 40.0, 50.0]);
+        assert!(z / w > 0.0 && z / w < 1.0);
+
+        let [_, _, near_z, near_w] = projection.transform_homogeneous(&Point { x: 0.0, y: 0.0, z: 1.0 });
+        let [_, _, far_z, far_w] = projection.transform_homogeneous(&Point { x: 0.0, y: 0.0, z: 101.0 });
+        assert_point_eq([near_z / near_w, far_z / far_w, 0.0], [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn orthographic_keeps_sizes() {
+        let projection: Mat4 = Mat4::orthographic(2.0, 1.0, 101.0);
+
+        let [x, y, z, w] = projection.transform_homogeneous(&Point { x: 10.0, y: 20.0, z: 51.0 });
+        assert_point_eq([x, y, z], [5.0, 10.0, 0.5]);
+        assert_eq!(w, 1.0);
+
+        let [_, _, far_z, _] = projection.transform_homogeneous(&Point { x: 10.0, y: 20.0, z: 101.0 });
+        assert_point_eq([far_z, 0.0, 0.0], [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn look_at_moves_target_in_front_of_eye() {
+        let up: Vector = Vector::new(0.0, 1.0, 0.0);
+
+        let view: Mat4 = Mat4::look_at(&Point { x: 0.0, y: 0.0, z: -10.0 }, &Point { x: 0.0, y: 0.0, z: 0.0 }, &up);
+        let Point { x, y, z } = view.transform_point(&Point { x: 1.0, y: 2.0, z: 0.0 });
+        assert_point_eq([x, y, z], [1.0, 2.0, 10.0]);
+
+        /* looking along x, world z is on the left */
+        let view: Mat4 = Mat4::look_at(&Point { x: 0.0, y: 0.0, z: 0.0 }, &Point { x: 1.0, y: 0.0, z: 0.0 }, &up);
+        let Point { x, y, z } = view.transform_point(&Point { x: 5.0, y: 0.0, z: 1.0 });
+        assert_point_eq([x, y, z], [-1.0, 0.0, 5.0]);
+    }
+}
@@ -1,34 +1,276 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
 use serde::{Deserialize, Serialize};
 
+use crate::rendering_engine::error::RenderError;
+use crate::rendering_engine::scene::model::culling::Culling;
+use crate::rendering_engine::scene::model::shading::Shading;
+use crate::rendering_engine::scene::model::texture::Sampler;
+use crate::rendering_engine::scene::model_2d::point::Point as Point2D;
 use crate::rendering_engine::scene::model_3d::face::Face;
 use crate::rendering_engine::scene::model_3d::point::Point;
+use crate::rendering_engine::scene::model_3d::transform::Transform;
 use crate::rendering_engine::scene::model_3d::triangle::Triangle;
 use crate::rendering_engine::scene::model_3d::vector::Vector;
+use crate::rendering_engine::scene::TextureID;
+
+/** in degrees */
+pub const DEFAULT_CREASE_ANGLE: f32 = 60.0;
 
+/**
+ * Corner normals are cached for rendering, changing the points, faces, normals or the crease angle directly
+ * instead of through the methods needs Mesh::invalidate_corner_normals.
+ */
+#[derive(Clone)]
 #[derive(Serialize, Deserialize)]
 pub struct Mesh {
     pub points: Vec<Point>,
     pub faces: Vec<Face>,
+    /** placement of the mesh in the world, points are kept in the mesh local space */
+    #[serde(default)]
+    pub transform: Transform,
+    /** per point normals, empty if the mesh has none */
+    #[serde(default)]
+    pub normals: Vec<Vector>,
+    /** per point texture coordinates, empty if the mesh has none, see Texture */
+    #[serde(default)]
+    pub uvs: Vec<Point2D>,
+    #[serde(default)]
+    pub shading: Shading,
+    /** in degrees, faces meeting at a sharper angle don't share normals computed for smooth shading */
+    #[serde(default = "default_crease_angle")]
+    pub crease_angle: f32,
+    #[serde(default)]
+    pub culling: Culling,
+    /** texture of the scene multiplying the face colors, meshes without texture coordinates aren't textured */
+    #[serde(default)]
+    pub texture: Option<TextureID>,
+    #[serde(default)]
+    pub sampler: Sampler,
+    #[serde(skip)]
+    corner_normals: OnceLock<Vec<[Vector; 3]>>,
 }
 
 impl Mesh {
+    pub fn new(points: Vec<Point>, faces: Vec<Face>) -> Mesh {
+        Mesh {
+            points,
+            faces,
+            transform: Transform::identity(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            shading: Shading::Flat,
+            crease_angle: DEFAULT_CREASE_ANGLE,
+            culling: Culling::None,
+            texture: None,
+            sampler: Sampler::default(),
+            corner_normals: OnceLock::new()
+        }
+    }
+
     pub fn move_point(&mut self, point_index: usize, delta: Vector) {
         if let Some(point) = self.points.get_mut(point_index) {
             point.x += delta.x;
             point.y += delta.y;
             point.z += delta.z;
+
+            self.invalidate_corner_normals();
+        }
+    }
+
+    /** one normal per point, or none to compute them from the faces, fails with RenderError::InvalidGeometry otherwise */
+    pub fn set_normals(&mut self, normals: Vec<Vector>) -> Result<(), RenderError> {
+        if !normals.is_empty() && normals.len() != self.points.len() {
+            return Err(RenderError::InvalidGeometry(format!("{} normals for {} points", normals.len(), self.points.len())));
         }
+
+        self.normals = normals;
+        self.invalidate_corner_normals();
+
+        Ok(())
+    }
+
+    /** in degrees, see Mesh::corner_normals */
+    pub fn set_crease_angle(&mut self, crease_angle: f32) {
+        self.crease_angle = crease_angle;
+        self.invalidate_corner_normals();
+    }
+
+    /** drops the cached corner normals, they are computed again when needed */
+    pub fn invalidate_corner_normals(&mut self) {
+        self.corner_normals = OnceLock::new();
+    }
+
+    /**
+     * Fails with RenderError::InvalidGeometry if a point is not finite, a face refers to a point that does not exist
+     * or the normals or texture coordinates are neither empty nor one per point.
+     * The fields are public, so the renderer checks every mesh again before drawing it.
+     */
+    pub fn check(&self) -> Result<(), RenderError> {
+        let invalid = |message: String| Err(RenderError::InvalidGeometry(message));
+
+        if let Some(index) = self.points.iter().position(|point: &Point| !(point.x.is_finite() && point.y.is_finite() && point.z.is_finite())) {
+            return invalid(format!("point {} is not finite", index));
+        }
+
+        for (index, face) in self.faces.iter().enumerate() {
+            if let Some(vertex) = face.vertices.iter().find(|vertex: &&usize| **vertex >= self.points.len()) {
+                return invalid(format!("face {} refers to point {}, the mesh has {} points", index, vertex, self.points.len()));
+            }
+        }
+
+        if !self.normals.is_empty() && self.normals.len() != self.points.len() {
+            return invalid(format!("{} normals for {} points", self.normals.len(), self.points.len()));
+        }
+
+        if !self.uvs.is_empty() && self.uvs.len() != self.points.len() {
+            return invalid(format!("{} texture coordinates for {} points", self.uvs.len(), self.points.len()));
+        }
+
+        Ok(())
     }
 
     pub fn triangulate(&self) -> Vec<Triangle> {
         self.faces.iter()
             .map(|face: &Face| {
                 Triangle::new([
-                    self.points[face.vertices[0]].clone(),
-                    self.points[face.vertices[1]].clone(),
-                    self.points[face.vertices[2]].clone()
+                    self.points[face.vertices[0]],
+                    self.points[face.vertices[1]],
+                    self.points[face.vertices[2]]
                 ], face.color)
             })
             .collect()
     }
+
+    /**
+     * Unit normals of the corners of every face, in the order of the faces.
+     * Normals of the mesh are used if it has them, otherwise a corner gets the average normal of the faces around its point
+     * which meet the face at most at the crease angle, weighted by their areas.
+     * Points at the same position are taken as one, so faces split apart, for example at texture seams, are smoothed too.
+     * Computed once and cached until the mesh changes.
+     */
+    pub fn corner_normals(&self) -> &[[Vector; 3]] {
+        self.corner_normals.get_or_init(|| self.compute_corner_normals())
+    }
+
+    fn compute_corner_normals(&self) -> Vec<[Vector; 3]> {
+        /* not normalized, so the length is twice the area of the face */
+        let face_normals: Vec<Vector> = self.triangulate()
+            .iter()
+            .map(|triangle: &Triangle| {
+                let [a, b, c]: [Point; 3] = triangle.vertices();
+                (b - a).cross(&(c - a))
+            })
+            .collect();
+
+        let position_key = |point_index: usize| {
+            let Point { x, y, z } = self.points[point_index];
+            [x.to_bits(), y.to_bits(), z.to_bits()]
+        };
+
+        let mut position_faces: HashMap<[u32; 3], Vec<usize>> = HashMap::new();
+        for (face_index, face) in self.faces.iter().enumerate() {
+            for vertex in face.vertices {
+                position_faces.entry(position_key(vertex)).or_default().push(face_index);
+            }
+        }
+
+        let cos_crease_angle: f32 = self.crease_angle.to_radians().cos();
+
+        self.faces
+            .iter()
+            .enumerate()
+            .map(|(face_index, face): (usize, &Face)| {
+                let face_normal: Vector = face_normals[face_index].normalize();
+
+                face.vertices.map(|vertex: usize| {
+                    let normal: Vector = self.normals.get(vertex).map(Vector::normalize).unwrap_or(Vector::zero());
+
+                    if normal.length() > 0.0 {
+                        return normal;
+                    }
+
+                    position_faces[&position_key(vertex)]
+                        .iter()
+                        .map(|other: &usize| face_normals[*other])
+                        .filter(|other: &Vector| other.normalize().dot(&face_normal) >= cos_crease_angle)
+                        .fold(Vector::zero(), |sum: Vector, other: Vector| sum + other)
+                        .normalize()
+                })
+            })
+            .collect()
+    }
+}
+
+fn default_crease_angle() -> f32 {
+    DEFAULT_CREASE_ANGLE
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::scene::model::color::Color;
+    use crate::rendering_engine::scene::model_3d::face::Face;
+    use crate::rendering_engine::scene::model_3d::mesh::Mesh;
+    use crate::rendering_engine::scene::model_3d::point::Point;
+    use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+    /** two triangles folded by 90 degrees along the edge 0-1, the second one duplicates the points of the edge */
+    fn create_folded_mesh() -> Mesh {
+        let color: Color = Color::new(0, 0, 0, 1.0);
+
+        Mesh::new(
+            vec![
+                Point { x: 0.0, y: 0.0, z: 0.0 },
+                Point { x: 1.0, y: 0.0, z: 0.0 },
+                Point { x: 0.0, y: 0.0, z: 1.0 },
+                Point { x: 1.0, y: 0.0, z: 0.0 },
+                Point { x: 0.0, y: 0.0, z: 0.0 },
+                Point { x: 0.0, y: -1.0, z: 0.0 },
+            ],
+            vec![Face::new([0, 1, 2], color), Face::new([3, 4, 5], color)]
+        )
+    }
+
+    fn assert_close(actual: &Vector, expected: &Vector) {
+        assert!((*actual - *expected).length() < 1e-4, "{:?} is not {:?}", actual, expected);
+    }
+
+    #[test]
+    fn crease_angle_splits_normals() {
+        let mut mesh: Mesh = create_folded_mesh();
+
+        let sharp: Vec<[Vector; 3]> = mesh.corner_normals().to_vec();
+        assert_close(&sharp[0][0], &Vector::new(0.0, -1.0, 0.0));
+        assert_close(&sharp[1][0], &Vector::new(0.0, 0.0, 1.0));
+
+        mesh.set_crease_angle(100.0);
+        let smooth: &[[Vector; 3]] = mesh.corner_normals();
+        let diagonal: Vector = Vector::new(0.0, -1.0, 1.0).normalize();
+
+        assert_close(&smooth[0][0], &diagonal);
+        assert_close(&smooth[1][1], &diagonal);
+        /* the points off the edge belong to one face only */
+        assert_close(&smooth[0][2], &Vector::new(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn mesh_normals_take_precedence() {
+        let mut mesh: Mesh = create_folded_mesh();
+        mesh.set_normals(vec![Vector::new(0.0, 2.0, 0.0); 6]).unwrap();
+        assert!(mesh.set_normals(vec![Vector::new(0.0, 1.0, 0.0); 5]).is_err());
+
+        assert_close(&mesh.corner_normals()[1][2], &Vector::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn moving_a_point_updates_the_normals() {
+        let mut mesh: Mesh = create_folded_mesh();
+        assert_close(&mesh.corner_normals()[0][2], &Vector::new(0.0, -1.0, 0.0));
+
+        /* folding the first triangle onto the plane of the second one */
+        mesh.move_point(2, Vector::new(0.0, -1.0, -1.0));
+
+        assert_close(&mesh.corner_normals()[0][2], &Vector::new(0.0, 0.0, -1.0));
+    }
 }
@@ -14,10 +14,11 @@ pub struct Plane {
 }
 
 impl Plane {
+    /** plane a*x + b*y + c*z + d = 0 passing through the point */
     pub fn new(normal: Vector, point: Point) -> Plane {
         let Vector { x: a, y: b, z: c } = normal;
 
-        let d: f32 = a*point.x + b*point.y + c*point.z;
+        let d: f32 = -(a*point.x + b*point.y + c*point.z);
 
         Plane {
             coefficients: Coefficients { a, b, c, d, }
@@ -1,9 +1,12 @@
+use std::ops::{Add, Sub};
+
 use serde::{Deserialize, Serialize};
 
 use crate::rendering_engine::scene::model_3d::plane::coefficients::Coefficients;
 use crate::rendering_engine::scene::model_3d::plane::Plane;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[derive(Serialize, Deserialize)]
 pub struct Point {
     pub x: f32,
@@ -14,11 +17,36 @@ pub struct Point {
 impl Point {
     pub fn distance_from_plane(&self, plane: &Plane) -> f32 {
         let Coefficients { a, b, c, d } = plane.coefficients;
-        let Self { x, y, z } = self.clone();
+        let Self { x, y, z } = *self;
 
         let numerator: f32 = a*x + b*y + c*z + d;
         let sum_of_squares: f32 = a.powi(2) + b.powi(2) + c.powi(2);
 
         numerator / sum_of_squares.sqrt()
     }
+
+    /** vector from the origin to the point */
+    pub fn to_vector(self) -> Vector {
+        Vector::new(self.x, self.y, self.z)
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, vector: Vector) -> Point {
+        Point {
+            x: self.x + vector.x,
+            y: self.y + vector.y,
+            z: self.z + vector.z,
+        }
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
 }
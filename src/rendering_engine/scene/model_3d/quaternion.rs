@@ -0,0 +1,215 @@
+use std::ops::Mul;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rendering_engine::scene::model_3d::axis::Axis;
+use crate::rendering_engine::scene::model_3d::matrix::Mat3;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+/**
+ * Unit quaternion describing a rotation.
+ * Angles are in degrees, positive angles rotate counterclockwise when looking from the end of the axis.
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quaternion {
+    pub fn identity() -> Quaternion {
+        Quaternion { w: 1.0, x: 0.0, y: 0.0, z: 0.0 }
+    }
+
+    pub fn from_axis_angle(axis: &Vector, angle: f32) -> Quaternion {
+        let axis: Vector = axis.normalize();
+        let half_angle: f32 = angle.to_radians() / 2.0;
+        let sin: f32 = half_angle.sin();
+
+        Quaternion {
+            w: half_angle.cos(),
+            x: axis.x * sin,
+            y: axis.y * sin,
+            z: axis.z * sin,
+        }
+    }
+
+    pub fn from_axis(axis: Axis, angle: f32) -> Quaternion {
+        let axis: Vector = match axis {
+            Axis::X => Vector::new(1.0, 0.0, 0.0),
+            Axis::Y => Vector::new(0.0, 1.0, 0.0),
+            Axis::Z => Vector::new(0.0, 0.0, 1.0),
+        };
+
+        Quaternion::from_axis_angle(&axis, angle)
+    }
+
+    /**
+     * Rotation by roll around the z axis, then by pitch around the x axis, then by yaw around the y axis.
+     * For a camera it means yaw turns it left and right, pitch tilts it up and down, roll tilts the horizon.
+     */
+    pub fn from_euler(pitch: f32, yaw: f32, roll: f32) -> Quaternion {
+        Quaternion::from_axis(Axis::Y, yaw)
+            * Quaternion::from_axis(Axis::X, pitch)
+            * Quaternion::from_axis(Axis::Z, roll)
+    }
+
+    /** inverse of `from_euler`, returns (pitch, yaw, roll) */
+    pub fn to_euler(self) -> (f32, f32, f32) {
+        let m: [[f32; 3]; 3] = self.to_mat3().m;
+
+        let sin_pitch: f32 = (-m[1][2]).clamp(-1.0, 1.0);
+        let pitch: f32 = sin_pitch.asin();
+
+        /* gimbal lock, yaw and roll rotate around the same axis, so roll is taken as zero */
+        if sin_pitch.abs() > 0.9999 {
+            let yaw: f32 = (-m[2][0]).atan2(m[0][0]);
+
+            return (pitch.to_degrees(), yaw.to_degrees(), 0.0);
+        }
+
+        let yaw: f32 = m[0][2].atan2(m[2][2]);
+        let roll: f32 = m[1][0].atan2(m[1][1]);
+
+        (pitch.to_degrees(), yaw.to_degrees(), roll.to_degrees())
+    }
+
+    /** rotation which turns the identity basis into the given orthonormal one */
+    pub fn from_mat3(matrix: &Mat3) -> Quaternion {
+        let m: [[f32; 3]; 3] = matrix.m;
+        let trace: f32 = m[0][0] + m[1][1] + m[2][2];
+
+        let quaternion: Quaternion = if trace > 0.0 {
+            let s: f32 = (trace + 1.0).sqrt() * 2.0;
+
+            Quaternion {
+                w: 0.25 * s,
+                x: (m[2][1] - m[1][2]) / s,
+                y: (m[0][2] - m[2][0]) / s,
+                z: (m[1][0] - m[0][1]) / s,
+            }
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s: f32 = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+
+            Quaternion {
+                w: (m[2][1] - m[1][2]) / s,
+                x: 0.25 * s,
+                y: (m[0][1] + m[1][0]) / s,
+                z: (m[0][2] + m[2][0]) / s,
+            }
+        } else if m[1][1] > m[2][2] {
+            let s: f32 = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+
+            Quaternion {
+                w: (m[0][2] - m[2][0]) / s,
+                x: (m[0][1] + m[1][0]) / s,
+                y: 0.25 * s,
+                z: (m[1][2] + m[2][1]) / s,
+            }
+        } else {
+            let s: f32 = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+
+            Quaternion {
+                w: (m[1][0] - m[0][1]) / s,
+                x: (m[0][2] + m[2][0]) / s,
+                y: (m[1][2] + m[2][1]) / s,
+                z: 0.25 * s,
+            }
+        };
+
+        quaternion.normalize()
+    }
+
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion { w: self.w, x: -self.x, y: -self.y, z: -self.z }
+    }
+
+    pub fn normalize(&self) -> Quaternion {
+        let length: f32 = (self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt();
+
+        if length == 0.0 {
+            return Quaternion::identity();
+        }
+
+        Quaternion {
+            w: self.w / length,
+            x: self.x / length,
+            y: self.y / length,
+            z: self.z / length,
+        }
+    }
+
+    pub fn rotate_vector(&self, vector: &Vector) -> Vector {
+        self.to_mat3().transform_vector(vector)
+    }
+
+    pub fn to_mat3(self) -> Mat3 {
+        let Quaternion { w, x, y, z } = self;
+
+        Mat3 {
+            m: [
+                [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+                [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+                [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)],
+            ]
+        }
+    }
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Quaternion::identity()
+    }
+}
+
+/** `a * b` rotates by `b` first, then by `a` */
+impl Mul for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, other: Quaternion) -> Quaternion {
+        Quaternion {
+            w: self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            x: self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            y: self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            z: self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering_engine::scene::model_3d::quaternion::Quaternion;
+    use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+    fn assert_angles_eq(actual: (f32, f32, f32), expected: (f32, f32, f32)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-2 && (actual.1 - expected.1).abs() < 1e-2 && (actual.2 - expected.2).abs() < 1e-2,
+            "{:?} != {:?}", actual, expected
+        );
+    }
+
+    #[test]
+    fn euler_angles_round_trip() {
+        for angles in [(0.0, 0.0, 0.0), (30.0, 45.0, 60.0), (-80.0, 170.0, -120.0), (10.0, -90.0, 5.0)] {
+            assert_angles_eq(Quaternion::from_euler(angles.0, angles.1, angles.2).to_euler(), angles);
+        }
+    }
+
+    #[test]
+    fn gimbal_lock_keeps_the_rotation() {
+        let quaternion: Quaternion = Quaternion::from_euler(90.0, 20.0, 30.0);
+        let (pitch, yaw, roll): (f32, f32, f32) = quaternion.to_euler();
+
+        assert_eq!(roll, 0.0);
+
+        let vector: Vector = Vector::new(1.0, 2.0, 3.0);
+        let expected: Vector = quaternion.rotate_vector(&vector);
+        let actual: Vector = Quaternion::from_euler(pitch, yaw, roll).rotate_vector(&vector);
+
+        /* the pitch is rounded near the pole */
+        assert!((actual - expected).length() < 1e-2, "{:?} != {:?}", actual, expected);
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rendering_engine::scene::model_3d::matrix::{Mat3, Mat4};
+use crate::rendering_engine::scene::model_3d::quaternion::Quaternion;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
+
+/** placement of an object: scaled first, then rotated around its origin, then translated */
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct Transform {
+    pub translation: Vector,
+    pub rotation: Quaternion,
+    pub scale: Vector,
+}
+
+impl Transform {
+    pub fn identity() -> Transform {
+        Transform {
+            translation: Vector::zero(),
+            rotation: Quaternion::identity(),
+            scale: Vector::new(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn from_translation(translation: Vector) -> Transform {
+        Transform {
+            translation,
+            ..Transform::identity()
+        }
+    }
+
+    /** splits an affine matrix into translation, rotation and scale, shear can't be represented and is lost */
+    pub fn from_matrix(matrix: &Mat4) -> Transform {
+        let m: [[f32; 4]; 4] = matrix.m;
+
+        let translation: Vector = Vector::new(m[0][3], m[1][3], m[2][3]);
+
+        let x_axis: Vector = Vector::new(m[0][0], m[1][0], m[2][0]);
+        let y_axis: Vector = Vector::new(m[0][1], m[1][1], m[2][1]);
+        let z_axis: Vector = Vector::new(m[0][2], m[1][2], m[2][2]);
+
+        let mut scale: Vector = Vector::new(x_axis.length(), y_axis.length(), z_axis.length());
+
+        /* a mirrored basis is kept as a rotation with a negative scale on x */
+        if x_axis.cross(&y_axis).dot(&z_axis) < 0.0 {
+            scale.x = -scale.x;
+        }
+
+        if scale.x == 0.0 || scale.y == 0.0 || scale.z == 0.0 {
+            return Transform {
+                translation,
+                rotation: Quaternion::identity(),
+                scale
+            };
+        }
+
+        let rotation: Mat3 = Mat3::from_columns(&(x_axis * (1.0 / scale.x)), &(y_axis * (1.0 / scale.y)), &(z_axis * (1.0 / scale.z)));
+
+        Transform {
+            translation,
+            rotation: Quaternion::from_mat3(&rotation),
+            scale
+        }
+    }
+
+    pub fn translate(&mut self, delta: &Vector) {
+        self.translation = self.translation + *delta;
+    }
+
+    /** angles of delta are in degrees: x is pitch, y is yaw, z is roll, the rotation is around world axes */
+    pub fn rotate(&mut self, delta: &Vector) {
+        self.rotation = (Quaternion::from_euler(delta.x, delta.y, delta.z) * self.rotation).normalize();
+    }
+
+    /** multiplies current scale factors by the given ones */
+    pub fn scale(&mut self, factors: &Vector) {
+        self.scale = Vector::new(
+            self.scale.x * factors.x,
+            self.scale.y * factors.y,
+            self.scale.z * factors.z,
+        );
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::translation(&self.translation) * Mat4::rotation(&self.rotation) * Mat4::scale(&self.scale)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
 use crate::rendering_engine::scene::model::color::Color;
+use crate::rendering_engine::scene::model_3d::matrix::Mat4;
 use crate::rendering_engine::scene::model_3d::point::Point;
+use crate::rendering_engine::scene::model_3d::vector::Vector;
 
 pub type TriangleVertices = [Point; 3];
 
@@ -26,4 +28,49 @@ impl Triangle {
     pub fn color(&self) -> Color {
         self.color
     }
+
+    /** unit normal, (b - a) x (c - a) of the vertices a, b, c, zero for degenerate triangles */
+    pub fn normal(&self) -> Vector {
+        let [a, b, c]: TriangleVertices = self.vertices;
+
+        (b - a).cross(&(c - a)).normalize()
+    }
+
+    pub fn centroid(&self) -> Point {
+        let [a, b, c]: TriangleVertices = self.vertices;
+
+        Point {
+            x: (a.x + b.x + c.x) / 3.0,
+            y: (a.y + b.y + c.y) / 3.0,
+            z: (a.z + b.z + c.z) / 3.0,
+        }
+    }
+
+    /**
+     * Barycentric coordinates of a point in the plane of the triangle, the weights of its vertices summing up to 1.
+     * Degenerate triangles give the same weight to every vertex.
+     */
+    pub fn barycentric(&self, point: &Point) -> [f32; 3] {
+        let [a, b, c]: TriangleVertices = self.vertices;
+        let (ab, ac, ap): (Vector, Vector, Vector) = (b - a, c - a, *point - a);
+
+        let (ab_ab, ab_ac, ac_ac): (f32, f32, f32) = (ab.dot(&ab), ab.dot(&ac), ac.dot(&ac));
+        let (ap_ab, ap_ac): (f32, f32) = (ap.dot(&ab), ap.dot(&ac));
+
+        let denominator: f32 = ab_ab * ac_ac - ab_ac * ab_ac;
+
+        if denominator == 0.0 {
+            return [1.0 / 3.0; 3];
+        }
+
+        let v: f32 = (ac_ac * ap_ab - ab_ac * ap_ac) / denominator;
+        let w: f32 = (ab_ab * ap_ac - ab_ac * ap_ab) / denominator;
+
+        [1.0 - v - w, v, w]
+    }
+
+    /** the vertices moved by the matrix, the color is kept */
+    pub fn transform(&self, matrix: &Mat4) -> Triangle {
+        Triangle::new(self.vertices.map(|vertex: Point| matrix.transform_point(&vertex)), self.color)
+    }
 }
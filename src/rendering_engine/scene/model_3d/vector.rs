@@ -1,9 +1,8 @@
-use std::f32::consts::PI;
+use std::ops::{Add, Mul, Neg, Sub};
 
 use serde::{Deserialize, Serialize};
 
-use crate::rendering_engine::scene::model_3d::axis::Axis;
-
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[derive(Serialize, Deserialize)]
 pub struct Vector {
     pub x: f32,
@@ -12,52 +11,70 @@ pub struct Vector {
 }
 
 impl Vector {
-    pub fn rotate(&mut self, axis: Axis, angle: f32) {
-        let angle_radian: f32 = (PI * angle) / 180.0;
-
-        let sin_alpha: f32 = angle_radian.sin();
-        let cos_alpha: f32 = angle_radian.cos();
-
-        match axis {
-            Axis::X => {
-                /*
-                * |  0       0      1| |x|   |        x        |   |x'|
-                * |cos θ   −sin θ   0| |y|   |y cos θ − z sin θ|   |y'|
-                * |sin θ    cos θ   0| |z| = |y sin θ + z cos θ| = |z'|
-                * */
-                let y = self.y * cos_alpha - self.z * sin_alpha;
-                let z = self.y * sin_alpha + self.z * cos_alpha;
-
-                /* x stays the same */
-                self.y = y;
-                self.z = z;
-            }
-            Axis::Y => {
-                /*
-                * | cos θ    0   sin θ| |x|   | x cos θ + z sin θ|   |x'|
-                * |   0      y       0| |y| = |         y        | = |y'|
-                * |−sin θ    0   cos θ| |z|   |−x sin θ + z cos θ|   |z'|
-                * */
-                let x = self.x * cos_alpha + self.z * sin_alpha;
-                let z = -self.x * sin_alpha + self.z * cos_alpha;
-
-                self.x = x;
-                /* y stays the same */
-                self.z = z;
-            }
-            Axis::Z => {
-                /*
-                * |0   cos θ    −sin θ| |x| = |x cos θ − y sin θ| = |x'|
-                * |0   sin θ     cos θ| |y|   |x sin θ + y cos θ|   |y'|
-                * |1     0           0| |z|   |        z        |   |z'|
-                * */
-                let x = self.x * cos_alpha - self.y * sin_alpha;
-                let y = self.x * sin_alpha + self.y * cos_alpha;
-
-                self.x = x;
-                self.y = y;
-                /* z stays the same */
-            }
+    pub fn new(x: f32, y: f32, z: f32) -> Vector {
+        Vector { x, y, z }
+    }
+
+    pub fn zero() -> Vector {
+        Vector::new(0.0, 0.0, 0.0)
+    }
+
+    pub fn dot(&self, other: &Vector) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Vector) -> Vector {
+        Vector {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
         }
     }
+
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /** zero vector stays zero */
+    pub fn normalize(&self) -> Vector {
+        let length: f32 = self.length();
+
+        if length == 0.0 {
+            return *self;
+        }
+
+        *self * (1.0 / length)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<f32> for Vector {
+    type Output = Vector;
+
+    fn mul(self, scalar: f32) -> Vector {
+        Vector::new(self.x * scalar, self.y * scalar, self.z * scalar)
+    }
 }
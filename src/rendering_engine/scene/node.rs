@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rendering_engine::scene::model_3d::transform::Transform;
+use crate::rendering_engine::scene::NodeID;
+
+/**
+ * Element of the scene hierarchy.
+ * The transform is local, the world transform of a node is the world transform of its parent combined with it.
+ */
+#[derive(Serialize, Deserialize)]
+pub struct Node {
+    parent: Option<NodeID>,
+    children: Vec<NodeID>,
+    pub transform: Transform,
+}
+
+impl Node {
+    pub fn new(parent: Option<NodeID>, transform: Transform) -> Node {
+        Node {
+            parent,
+            children: Vec::new(),
+            transform
+        }
+    }
+
+    pub fn parent(&self) -> Option<NodeID> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[NodeID] {
+        &self.children
+    }
+
+    pub(crate) fn set_parent(&mut self, parent: Option<NodeID>) {
+        self.parent = parent;
+    }
+
+    pub(crate) fn add_child(&mut self, child: NodeID) {
+        self.children.push(child);
+    }
+
+    pub(crate) fn remove_child(&mut self, child: NodeID) {
+        self.children.retain(|node_id: &NodeID| *node_id != child);
+    }
+}
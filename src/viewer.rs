@@ -0,0 +1,157 @@
+use std::path::Path;
+use std::time::Duration;
+
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::{Texture, TextureCreator, WindowCanvas};
+use sdl2::video::{Window, WindowContext};
+use sdl2::{Sdl, VideoSubsystem};
+use simple_3D_renderer::rendering_engine::engine::compositor::Image;
+use simple_3D_renderer::rendering_engine::scene::camera::controller::{CameraController, FlyController, OrbitController};
+use simple_3D_renderer::rendering_engine::scene::camera::display::Display;
+use simple_3D_renderer::rendering_engine::scene::camera::Camera;
+use simple_3D_renderer::rendering_engine::scene::light::Light;
+use simple_3D_renderer::rendering_engine::scene::model::color::Color as RenderingColor;
+use simple_3D_renderer::rendering_engine::scene::model_3d::point::Point as Point3D;
+use simple_3D_renderer::rendering_engine::scene::model_3d::vector::Vector;
+use simple_3D_renderer::rendering_engine::scene::{CameraID, Scene};
+use simple_3D_renderer::rendering_engine::{RenderingEngine, SceneId};
+
+use crate::cli::{default_camera, open_scene};
+
+const WIDTH: usize = 800;
+const HEIGHT: usize = 800;
+const MOVE_STEP: f32 = 10.0;
+/** in degrees */
+const TURN_STEP: f32 = 2.0;
+const ZOOM_STEP: f32 = 1.1;
+/** distance of the orbit pivot in front of the camera */
+const ORBIT_DISTANCE: f32 = 500.0;
+
+/** opens a window showing the file of the args, or demo cubes without one */
+pub fn run(args: &[String]) {
+    let mut renderer: RenderingEngine = RenderingEngine::new();
+    let display: Display = Display::new(WIDTH, HEIGHT);
+
+    let (scene_id, file_cameras): (SceneId, Vec<CameraID>) = match args.first() {
+        Some(path) => {
+            let mtl_path: Option<&Path> = args.get(1).map(Path::new);
+
+            open_scene(&mut renderer, Path::new(path), mtl_path, &display).unwrap_or_else(|error: String| {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            })
+        }
+        None => {
+            let scene_id: SceneId = renderer.create_scene();
+            let scene: &mut Scene = renderer.get_scene(scene_id).unwrap();
+
+            scene.add_cube(Point3D { x: 0.0, y: 0.0, z: 300.0 }, 100.0, 100.0, 100.0, RenderingColor::new(255, 0, 0, 0.2)).unwrap();
+            scene.add_cube(Point3D { x: 100.0, y: 0.0, z: 500.0 }, 100.0, 100.0, 100.0, RenderingColor::new(0, 0, 80, 1.0)).unwrap();
+            scene.add_cube(Point3D { x: 0.0, y: 0.0, z: 800.0 }, 100.0, 100.0, 100.0, RenderingColor::new(0, 125, 0, 0.3)).unwrap();
+
+            let white: RenderingColor = RenderingColor::new(255, 255, 255, 1.0);
+            scene.add_light(Light::Ambient { color: white, intensity: 0.3 }).unwrap();
+            scene.add_light(Light::Directional { color: white, intensity: 0.8, direction: Vector::new(-1.0, -2.0, 1.5) }).unwrap();
+
+            (scene_id, Vec::new())
+        }
+    };
+
+    /* the file is viewed through its own camera if it has one */
+    let camera_id: CameraID = match file_cameras.first() {
+        Some(camera_id) => *camera_id,
+        None => renderer
+            .get_scene(scene_id).unwrap()
+            .add_camera(default_camera(&display, Point3D { x: 0.0, y: 0.0, z: 0.0 }, &Vector::zero()).unwrap())
+    };
+
+    let sdl_context: Sdl = sdl2::init().unwrap();
+    let video_subsystem: VideoSubsystem = sdl_context.video().unwrap();
+
+    let window: Window = video_subsystem.window("rust-sdl2 demo", WIDTH as u32, HEIGHT as u32)
+        .position_centered()
+        .build()
+        .unwrap();
+
+    let mut canvas: WindowCanvas = window.into_canvas().build().unwrap();
+    let texture_creator: TextureCreator<WindowContext> = canvas.texture_creator();
+    let mut texture: Texture = texture_creator
+        .create_texture_streaming(PixelFormatEnum::RGB24, WIDTH as u32, HEIGHT as u32)
+        .unwrap();
+
+    render(&renderer, scene_id, camera_id, &mut canvas, &mut texture);
+
+    renderer.set_camera_controller(scene_id, camera_id, Some(CameraController::Fly(FlyController))).unwrap();
+    let mut orbiting: bool = false;
+
+    let mut event_pump = sdl_context.event_pump().unwrap();
+    'running: loop {
+        for event in event_pump.poll_iter() {
+            let keycode: Keycode = match event {
+                Event::Quit {..} |
+                Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
+                    break 'running
+                },
+                Event::KeyDown { keycode: Some(keycode), .. } => keycode,
+                _ => continue
+            };
+
+            /* Tab switches between flying and orbiting around the point in front of the camera */
+            if keycode == Keycode::Tab {
+                orbiting = !orbiting;
+
+                let controller: CameraController = if orbiting {
+                    let camera: &Camera = renderer.get_scene(scene_id).unwrap().get_camera(camera_id).unwrap();
+                    CameraController::Orbit(OrbitController::new(*camera.center() + camera.forward() * ORBIT_DISTANCE))
+                } else {
+                    CameraController::Fly(FlyController)
+                };
+
+                renderer.set_camera_controller(scene_id, camera_id, Some(controller)).unwrap();
+                continue;
+            }
+
+            if orbiting {
+                match keycode {
+                    Keycode::W => renderer.zoom_camera(scene_id, camera_id, 1.0 / ZOOM_STEP).unwrap(),
+                    Keycode::S => renderer.zoom_camera(scene_id, camera_id, ZOOM_STEP).unwrap(),
+                    Keycode::D => renderer.pan_camera(scene_id, camera_id, MOVE_STEP, 0.0).unwrap(),
+                    Keycode::A => renderer.pan_camera(scene_id, camera_id, -MOVE_STEP, 0.0).unwrap(),
+                    Keycode::Left => renderer.orbit_camera(scene_id, camera_id, -TURN_STEP, 0.0).unwrap(),
+                    Keycode::Right => renderer.orbit_camera(scene_id, camera_id, TURN_STEP, 0.0).unwrap(),
+                    Keycode::Up => renderer.orbit_camera(scene_id, camera_id, 0.0, TURN_STEP).unwrap(),
+                    Keycode::Down => renderer.orbit_camera(scene_id, camera_id, 0.0, -TURN_STEP).unwrap(),
+                    _ => {}
+                }
+            } else {
+                match keycode {
+                    Keycode::W => renderer.fly_camera(scene_id, camera_id, &Vector { x: 0.0, y: 0.0, z: MOVE_STEP }).unwrap(),
+                    Keycode::S => renderer.fly_camera(scene_id, camera_id, &Vector { x: 0.0, y: 0.0, z: -MOVE_STEP }).unwrap(),
+                    Keycode::D => renderer.fly_camera(scene_id, camera_id, &Vector { x: MOVE_STEP, y: 0.0, z: 0.0 }).unwrap(),
+                    Keycode::A => renderer.fly_camera(scene_id, camera_id, &Vector { x: -MOVE_STEP, y: 0.0, z: 0.0 }).unwrap(),
+                    Keycode::Left => renderer.look_camera(scene_id, camera_id, -TURN_STEP, 0.0).unwrap(),
+                    Keycode::Right => renderer.look_camera(scene_id, camera_id, TURN_STEP, 0.0).unwrap(),
+                    Keycode::Up => renderer.look_camera(scene_id, camera_id, 0.0, TURN_STEP).unwrap(),
+                    Keycode::Down => renderer.look_camera(scene_id, camera_id, 0.0, -TURN_STEP).unwrap(),
+                    _ => {}
+                }
+            }
+        }
+
+        render(&renderer, scene_id, camera_id, &mut canvas, &mut texture);
+
+        std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+    }
+}
+
+/** the texture has to be of the window size, the image is uploaded to it at once */
+fn render(rendering_engine: &RenderingEngine, scene_id: SceneId, camera_id: CameraID, canvas: &mut WindowCanvas, texture: &mut Texture) {
+    let image: Image = rendering_engine.render(scene_id, camera_id, RenderingColor::new(255, 255, 255, 1.0)).unwrap();
+
+    texture.update(None, &image.to_rgb8(), image.width() * 3).unwrap();
+
+    canvas.copy(texture, None, None).unwrap();
+    canvas.present();
+}
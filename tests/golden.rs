@@ -0,0 +1,349 @@
+//! Renders canonical scenes headlessly and compares them against the reference images in tests/golden.
+//!
+//! A pixel matches if none of its channels differs by more than TOLERANCE.
+//! On a mismatch the rendered image and a diff image (mismatching pixels red) are written
+//! next to the test binaries and their paths are printed.
+//! Run with UPDATE_GOLDEN=1 to write the current renders as new references.
+
+use std::path::PathBuf;
+
+use simple_3D_renderer::rendering_engine::engine::compositor::Image;
+use simple_3D_renderer::rendering_engine::engine::model::pixel::Pixel;
+use simple_3D_renderer::rendering_engine::engine::framebuffer::Framebuffer;
+use simple_3D_renderer::rendering_engine::format::image::{decode_png, encode_image, ImageFormat};
+use simple_3D_renderer::rendering_engine::scene::camera::display::Display;
+use simple_3D_renderer::rendering_engine::scene::camera::{Camera, Projection};
+use simple_3D_renderer::rendering_engine::scene::light::{Attenuation, Light};
+use simple_3D_renderer::rendering_engine::scene::model::color::Color;
+use simple_3D_renderer::rendering_engine::scene::model::culling::Culling;
+use simple_3D_renderer::rendering_engine::scene::model::shading::Shading;
+use simple_3D_renderer::rendering_engine::scene::model::texture::{Filter, Sampler, Texture, Wrap};
+use simple_3D_renderer::rendering_engine::scene::model_2d::point::Point as Point2D;
+use simple_3D_renderer::rendering_engine::scene::model_3d::face::Face;
+use simple_3D_renderer::rendering_engine::scene::model_3d::point::Point;
+use simple_3D_renderer::rendering_engine::scene::model_3d::vector::Vector;
+use simple_3D_renderer::rendering_engine::scene::{CameraID, MeshID, Scene, TextureID};
+use simple_3D_renderer::rendering_engine::{RenderingEngine, SceneId};
+
+/** largest allowed difference of a channel */
+const TOLERANCE: u8 = 2;
+
+const WIDTH: usize = 64;
+const HEIGHT: usize = 64;
+
+const BACKGROUND: Color = Color { r: 255, g: 255, b: 255, a: 1.0 };
+
+fn reference_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(format!("{}.png", name))
+}
+
+fn output_path(name: &str, kind: &str) -> PathBuf {
+    let directory: PathBuf = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden");
+    std::fs::create_dir_all(&directory).unwrap();
+
+    directory.join(format!("{}.{}.png", name, kind))
+}
+
+fn write_png(image: &Image, path: &PathBuf) {
+    std::fs::write(path, encode_image(image, ImageFormat::Png).unwrap()).unwrap();
+}
+
+/** scene with a camera at the origin looking along +z */
+fn create_scene(renderer: &mut RenderingEngine) -> (SceneId, CameraID) {
+    let scene_id: SceneId = renderer.create_scene();
+
+    let camera_id: CameraID = renderer
+        .get_scene(scene_id).unwrap()
+        .add_camera(Camera::new(64.0, Point { x: 0.0, y: 0.0, z: 0.0 }, 0.0, 0.0, 0.0, Display::new(WIDTH, HEIGHT)));
+
+    (scene_id, camera_id)
+}
+
+fn assert_golden(name: &str, image: &Image) {
+    let path: PathBuf = reference_path(name);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        write_png(image, &path);
+        return;
+    }
+
+    let reference: Vec<u8> = std::fs::read(&path)
+        .unwrap_or_else(|error| panic!("{}: {}, run with UPDATE_GOLDEN=1 to create it", path.display(), error));
+    let (width, height, expected) = decode_png(&reference).unwrap();
+
+    let actual_path: PathBuf = output_path(name, "actual");
+
+    if (width, height) != (image.width(), image.height()) {
+        write_png(image, &actual_path);
+
+        panic!("{}: rendered {}x{}, reference is {}x{}, render written to {}",
+               name, image.width(), image.height(), width, height, actual_path.display());
+    }
+
+    let actual: Vec<u8> = image.to_rgba8();
+
+    let mut mismatches: usize = 0;
+    let mut largest_difference: u8 = 0;
+
+    let diff_pixels: Vec<Pixel> = actual
+        .chunks_exact(4)
+        .zip(expected.chunks_exact(4))
+        .map(|(actual, expected): (&[u8], &[u8])| {
+            let difference: u8 = (0..3)
+                .map(|channel: usize| actual[channel].abs_diff(expected[channel]))
+                .max()
+                .unwrap();
+
+            largest_difference = largest_difference.max(difference);
+
+            if difference > TOLERANCE {
+                mismatches += 1;
+                Pixel::new(255, 0, 0)
+            } else {
+                /* matching pixels are faded, so the mismatches stand out */
+                let fade = |channel: u8| 192 + channel / 4;
+                Pixel::new(fade(expected[0]), fade(expected[1]), fade(expected[2]))
+            }
+        })
+        .collect();
+
+    let diff: Image = Framebuffer::from_data(width, height, diff_pixels).unwrap();
+
+    if mismatches > 0 {
+        let diff_path: PathBuf = output_path(name, "diff");
+
+        write_png(image, &actual_path);
+        write_png(&diff, &diff_path);
+
+        panic!("{}: {} of {} pixels differ by more than {} (largest difference {}), render written to {}, diff to {}",
+               name, mismatches, width * height, TOLERANCE, largest_difference, actual_path.display(), diff_path.display());
+    }
+}
+
+#[test]
+fn single_triangle() {
+    let mut renderer: RenderingEngine = RenderingEngine::new();
+    let (scene_id, camera_id) = create_scene(&mut renderer);
+
+    renderer.get_scene(scene_id).unwrap().add_mesh(
+        vec![
+            Point { x: -20.0, y: -20.0, z: 100.0 },
+            Point { x: 0.0, y: 25.0, z: 100.0 },
+            Point { x: 20.0, y: -15.0, z: 100.0 },
+        ],
+        vec![
+            Face::new([0, 1, 2], Color::new(200, 30, 30, 1.0))
+        ]
+    ).unwrap();
+
+    assert_golden("single_triangle", &renderer.render(scene_id, camera_id, BACKGROUND).unwrap());
+}
+
+#[test]
+fn cube() {
+    let mut renderer: RenderingEngine = RenderingEngine::new();
+    let (scene_id, camera_id) = create_scene(&mut renderer);
+
+    let scene: &mut Scene = renderer.get_scene(scene_id).unwrap();
+    let cube_id: MeshID = scene.add_cube(Point { x: 0.0, y: 0.0, z: 300.0 }, 100.0, 100.0, 100.0, Color::new(30, 60, 200, 1.0)).unwrap();
+    scene.rotate_mesh(cube_id, &Vector::new(25.0, 35.0, 0.0)).unwrap();
+
+    assert_golden("cube", &renderer.render(scene_id, camera_id, BACKGROUND).unwrap());
+}
+
+#[test]
+fn overlapping_translucent_cubes() {
+    let mut renderer: RenderingEngine = RenderingEngine::new();
+    let (scene_id, camera_id) = create_scene(&mut renderer);
+
+    let scene: &mut Scene = renderer.get_scene(scene_id).unwrap();
+    scene.add_cube(Point { x: 0.0, y: 0.0, z: 500.0 }, 200.0, 200.0, 50.0, Color::new(90, 90, 90, 1.0)).unwrap();
+    scene.add_cube(Point { x: -25.0, y: 0.0, z: 300.0 }, 100.0, 100.0, 100.0, Color::new(255, 0, 0, 0.5)).unwrap();
+
+    let green_id: MeshID = scene.add_cube(Point { x: 25.0, y: 15.0, z: 340.0 }, 100.0, 100.0, 100.0, Color::new(0, 200, 0, 0.4)).unwrap();
+    scene.rotate_mesh(green_id, &Vector::new(0.0, 30.0, 0.0)).unwrap();
+
+    assert_golden("overlapping_translucent_cubes", &renderer.render(scene_id, camera_id, BACKGROUND).unwrap());
+}
+
+/** translucent cubes drawing both sides, only the front and only the back of their faces */
+#[test]
+fn cube_culling() {
+    let mut renderer: RenderingEngine = RenderingEngine::new();
+    let (scene_id, camera_id) = create_scene(&mut renderer);
+
+    let scene: &mut Scene = renderer.get_scene(scene_id).unwrap();
+
+    for (x, culling) in [(-120.0, Culling::None), (0.0, Culling::Back), (120.0, Culling::Front)] {
+        let cube_id: MeshID = scene.add_cube(Point { x, y: 0.0, z: 400.0 }, 80.0, 80.0, 80.0, Color::new(30, 60, 200, 0.5)).unwrap();
+        scene.rotate_mesh(cube_id, &Vector::new(25.0, 35.0, 0.0)).unwrap();
+        scene.set_mesh_culling(cube_id, culling).unwrap();
+    }
+
+    /* lit, so the front and the back faces differ */
+    scene.add_light(Light::Ambient { color: Color::new(255, 255, 255, 1.0), intensity: 0.2 }).unwrap();
+    scene.add_light(Light::Directional { color: Color::new(255, 255, 255, 1.0), intensity: 0.9, direction: Vector::new(1.0, -1.0, 1.0) }).unwrap();
+
+    assert_golden("cube_culling", &renderer.render(scene_id, camera_id, BACKGROUND).unwrap());
+}
+
+/** tilted quads with texture coordinates from 0 to 2, so the texture is wrapped */
+#[test]
+fn textured_quads() {
+    let mut renderer: RenderingEngine = RenderingEngine::new();
+    let (scene_id, camera_id) = create_scene(&mut renderer);
+
+    let scene: &mut Scene = renderer.get_scene(scene_id).unwrap();
+
+    /* red and green on the top row, blue and white on the bottom one */
+    let texture_id: TextureID = scene.add_texture(Texture::new(2, 2, vec![
+        255, 0, 0, 255, 0, 255, 0, 255,
+        0, 0, 255, 255, 255, 255, 255, 255,
+    ]).unwrap());
+
+    let samplers: [(f32, Sampler); 3] = [
+        (-120.0, Sampler { filter: Filter::Nearest, wrap: Wrap::Repeat }),
+        (0.0, Sampler { filter: Filter::Bilinear, wrap: Wrap::Clamp }),
+        (120.0, Sampler { filter: Filter::Nearest, wrap: Wrap::Mirror }),
+    ];
+
+    for (x, sampler) in samplers {
+        let white: Color = Color::new(255, 255, 255, 1.0);
+        let quad_id: MeshID = scene.add_mesh(
+            vec![
+                Point { x: -45.0, y: -45.0, z: 0.0 },
+                Point { x: -45.0, y: 45.0, z: 0.0 },
+                Point { x: 45.0, y: 45.0, z: 0.0 },
+                Point { x: 45.0, y: -45.0, z: 0.0 },
+            ],
+            vec![Face::new([0, 1, 2], white), Face::new([0, 2, 3], white)]
+        ).unwrap();
+
+        scene.set_mesh_uvs(quad_id, vec![
+            Point2D { x: 0.0, y: 2.0 },
+            Point2D { x: 0.0, y: 0.0 },
+            Point2D { x: 2.0, y: 0.0 },
+            Point2D { x: 2.0, y: 2.0 },
+        ]).unwrap();
+        scene.set_mesh_texture(quad_id, Some(texture_id), sampler).unwrap();
+
+        scene.translate_mesh(quad_id, &Vector::new(x, 0.0, 400.0)).unwrap();
+        scene.rotate_mesh(quad_id, &Vector::new(-50.0, 0.0, 0.0)).unwrap();
+    }
+
+    assert_golden("textured_quads", &renderer.render(scene_id, camera_id, BACKGROUND).unwrap());
+}
+
+/** equal cubes at different distances have the same size */
+#[test]
+fn orthographic_cubes() {
+    let mut renderer: RenderingEngine = RenderingEngine::new();
+    let (scene_id, camera_id) = create_scene(&mut renderer);
+
+    let scene: &mut Scene = renderer.get_scene(scene_id).unwrap();
+    scene.get_camera_mut(camera_id).unwrap().set_projection(Projection::Orthographic { scale: 2.0 });
+
+    scene.add_cube(Point { x: -30.0, y: 0.0, z: 200.0 }, 40.0, 40.0, 40.0, Color::new(200, 30, 30, 1.0)).unwrap();
+    scene.add_cube(Point { x: 30.0, y: 0.0, z: 600.0 }, 40.0, 40.0, 40.0, Color::new(30, 60, 200, 1.0)).unwrap();
+
+    let in_front_id: MeshID = scene.add_cube(Point { x: 0.0, y: 30.0, z: 400.0 }, 40.0, 40.0, 40.0, Color::new(0, 200, 0, 1.0)).unwrap();
+    scene.rotate_mesh(in_front_id, &Vector::new(0.0, 45.0, 0.0)).unwrap();
+
+    assert_golden("orthographic_cubes", &renderer.render(scene_id, camera_id, BACKGROUND).unwrap());
+}
+
+/** every kind of light, on a floor and a cube */
+#[test]
+fn lit_cube() {
+    let mut renderer: RenderingEngine = RenderingEngine::new();
+    let (scene_id, camera_id) = create_scene(&mut renderer);
+
+    let scene: &mut Scene = renderer.get_scene(scene_id).unwrap();
+    scene.add_cube(Point { x: 0.0, y: -70.0, z: 350.0 }, 400.0, 20.0, 400.0, Color::new(200, 200, 200, 1.0)).unwrap();
+
+    let cube_id: MeshID = scene.add_cube(Point { x: 0.0, y: 0.0, z: 300.0 }, 80.0, 80.0, 80.0, Color::new(230, 230, 230, 1.0)).unwrap();
+    scene.rotate_mesh(cube_id, &Vector::new(25.0, 35.0, 0.0)).unwrap();
+
+    scene.add_light(Light::Ambient { color: Color::new(255, 255, 255, 1.0), intensity: 0.15 }).unwrap();
+    scene.add_light(Light::Directional { color: Color::new(255, 240, 220, 1.0), intensity: 0.6, direction: Vector::new(-1.0, -1.0, 1.0) }).unwrap();
+    scene.add_light(Light::Point {
+        color: Color::new(60, 60, 255, 1.0),
+        intensity: 1.0,
+        position: Point { x: 100.0, y: 0.0, z: 200.0 },
+        attenuation: Attenuation::new(0.5, 0.005, 0.0),
+    }).unwrap();
+    scene.add_light(Light::Spot {
+        color: Color::new(255, 60, 60, 1.0),
+        intensity: 2.0,
+        position: Point { x: -100.0, y: 100.0, z: 350.0 },
+        direction: Vector::new(0.0, -1.0, 0.0),
+        inner_angle: 15.0,
+        outer_angle: 30.0,
+        attenuation: Attenuation::NONE,
+    }).unwrap();
+
+    assert_golden("lit_cube", &renderer.render(scene_id, camera_id, BACKGROUND).unwrap());
+}
+
+/** UV sphere, the poles are single points, so normals have to be averaged over faces sharing the points */
+fn add_sphere(scene: &mut Scene, center: Point, radius: f32, color: Color) -> MeshID {
+    const RINGS: usize = 12;
+    const SEGMENTS: usize = 16;
+
+    let mut points: Vec<Point> = vec![Point { x: center.x, y: center.y + radius, z: center.z }];
+
+    for ring in 1..RINGS {
+        let polar: f32 = std::f32::consts::PI * ring as f32 / RINGS as f32;
+
+        for segment in 0..SEGMENTS {
+            let azimuth: f32 = 2.0 * std::f32::consts::PI * segment as f32 / SEGMENTS as f32;
+
+            points.push(Point {
+                x: center.x + radius * polar.sin() * azimuth.cos(),
+                y: center.y + radius * polar.cos(),
+                z: center.z + radius * polar.sin() * azimuth.sin(),
+            });
+        }
+    }
+
+    let bottom: usize = points.len();
+    points.push(Point { x: center.x, y: center.y - radius, z: center.z });
+
+    let ring_point = |ring: usize, segment: usize| 1 + (ring - 1) * SEGMENTS + segment % SEGMENTS;
+    let mut faces: Vec<Face> = Vec::new();
+
+    for segment in 0..SEGMENTS {
+        faces.push(Face::new([0, ring_point(1, segment), ring_point(1, segment + 1)], color));
+        faces.push(Face::new([bottom, ring_point(RINGS - 1, segment + 1), ring_point(RINGS - 1, segment)], color));
+
+        for ring in 1..RINGS - 1 {
+            faces.push(Face::new([ring_point(ring, segment), ring_point(ring + 1, segment), ring_point(ring + 1, segment + 1)], color));
+            faces.push(Face::new([ring_point(ring, segment), ring_point(ring + 1, segment + 1), ring_point(ring, segment + 1)], color));
+        }
+    }
+
+    scene.add_mesh(points, faces).unwrap()
+}
+
+/** flat, Gouraud and Phong shaded spheres from left to right */
+#[test]
+fn smooth_shading() {
+    let mut renderer: RenderingEngine = RenderingEngine::new();
+    let (scene_id, camera_id) = create_scene(&mut renderer);
+
+    let scene: &mut Scene = renderer.get_scene(scene_id).unwrap();
+    let color: Color = Color::new(40, 160, 220, 1.0);
+
+    let shadings: [Shading; 3] = [Shading::Flat, Shading::Gouraud, Shading::Phong { specular: 0.6, shininess: 24.0 }];
+
+    for (i, shading) in shadings.into_iter().enumerate() {
+        let sphere_id: MeshID = add_sphere(scene, Point { x: (i as f32 - 1.0) * 50.0, y: 0.0, z: 150.0 }, 20.0, color);
+        scene.set_mesh_shading(sphere_id, shading).unwrap();
+    }
+
+    scene.add_light(Light::Ambient { color: Color::new(255, 255, 255, 1.0), intensity: 0.2 }).unwrap();
+    scene.add_light(Light::Directional { color: Color::new(255, 255, 255, 1.0), intensity: 0.9, direction: Vector::new(1.0, -1.0, 1.0) }).unwrap();
+
+    assert_golden("smooth_shading", &renderer.render(scene_id, camera_id, BACKGROUND).unwrap());
+}